@@ -84,3 +84,12 @@ fn vs_conv_main(
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
     return in.color * textureSample(r_tex_color, r_tex_sampler, in.tex_coord);
 }
+
+// For font textures with a signed distance field in the (linear) alpha channel, where 0.5 is the edge of the glyph.
+[[stage(fragment)]]
+fn fs_main_distance_field(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let distance = textureSample(r_tex_color, r_tex_sampler, in.tex_coord).a;
+    let width = max(fwidth(distance), 0.0001);
+    let coverage = clamp((distance - 0.5) / width + 0.5, 0.0, 1.0);
+    return in.color * coverage;
+}
@@ -1,6 +1,10 @@
 #![allow(unsafe_code)]
 
-use std::{borrow::Cow, collections::HashMap, num::NonZeroU32};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    num::NonZeroU32,
+};
 
 use egui::epaint::Primitive;
 use wgpu;
@@ -51,6 +55,8 @@ struct SizedBuffer {
 /// Render pass to render a egui based GUI.
 pub struct RenderPass {
     render_pipeline: wgpu::RenderPipeline,
+    /// Used for font textures that store signed distance fields.
+    distance_field_pipeline: wgpu::RenderPipeline,
     index_buffers: Vec<SizedBuffer>,
     vertex_buffers: Vec<SizedBuffer>,
     uniform_buffer: SizedBuffer,
@@ -60,6 +66,8 @@ pub struct RenderPass {
     /// sampler). The texture may be None if the TextureId is just a handle to a user-provided
     /// sampler.
     textures: HashMap<egui::TextureId, (Option<wgpu::Texture>, wgpu::BindGroup)>,
+    /// Font textures that store signed distance fields, see [`egui::epaint::GlyphAtlasMode`].
+    distance_field_textures: HashSet<egui::TextureId>,
     next_user_texture_id: u64,
 }
 
@@ -147,72 +155,79 @@ impl RenderPass {
             push_constant_ranges: &[],
         });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("egui_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                entry_point: if output_format.describe().srgb {
-                    "vs_main"
-                } else {
-                    "vs_conv_main"
+        let create_pipeline = |label, fragment_entry_point| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    entry_point: if output_format.describe().srgb {
+                        "vs_main"
+                    } else {
+                        "vs_conv_main"
+                    },
+                    module: &module,
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: 5 * 4,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        // 0: vec2 position
+                        // 1: vec2 texture coordinates
+                        // 2: uint color
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Uint32],
+                    }],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    unclipped_depth: false,
+                    conservative: false,
+                    cull_mode: None,
+                    front_face: wgpu::FrontFace::default(),
+                    polygon_mode: wgpu::PolygonMode::default(),
+                    strip_index_format: None,
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    alpha_to_coverage_enabled: false,
+                    count: msaa_samples,
+                    mask: !0,
                 },
-                module: &module,
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: 5 * 4,
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    // 0: vec2 position
-                    // 1: vec2 texture coordinates
-                    // 2: uint color
-                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Uint32],
-                }],
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                unclipped_depth: false,
-                conservative: false,
-                cull_mode: None,
-                front_face: wgpu::FrontFace::default(),
-                polygon_mode: wgpu::PolygonMode::default(),
-                strip_index_format: None,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                alpha_to_coverage_enabled: false,
-                count: msaa_samples,
-                mask: !0,
-            },
 
-            fragment: Some(wgpu::FragmentState {
-                module: &module,
-                entry_point: "fs_main",
-                targets: &[wgpu::ColorTargetState {
-                    format: output_format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                        alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
-                            dst_factor: wgpu::BlendFactor::One,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                    }),
-                    write_mask: wgpu::ColorWrites::ALL,
-                }],
-            }),
-            multiview: None,
-        });
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: fragment_entry_point,
+                    targets: &[wgpu::ColorTargetState {
+                        format: output_format,
+                        blend: Some(wgpu::BlendState {
+                            color: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::One,
+                                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                            alpha: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
+                                dst_factor: wgpu::BlendFactor::One,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                        }),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }],
+                }),
+                multiview: None,
+            })
+        };
+        let render_pipeline = create_pipeline("egui_pipeline", "fs_main");
+        let distance_field_pipeline =
+            create_pipeline("egui_distance_field_pipeline", "fs_main_distance_field");
 
         Self {
             render_pipeline,
+            distance_field_pipeline,
             vertex_buffers: Vec::with_capacity(64),
             index_buffers: Vec::with_capacity(64),
             uniform_buffer,
             uniform_bind_group,
             texture_bind_group_layout,
             textures: HashMap::new(),
+            distance_field_textures: HashSet::new(),
             next_user_texture_id: 0,
         }
     }
@@ -259,6 +274,7 @@ impl RenderPass {
         screen_descriptor: &ScreenDescriptor,
     ) {
         rpass.set_pipeline(&self.render_pipeline);
+        let mut distance_field_pipeline_is_set = false;
 
         rpass.set_bind_group(0, &self.uniform_bind_group, &[]);
 
@@ -317,6 +333,16 @@ impl RenderPass {
             match primitive {
                 Primitive::Mesh(mesh) => {
                     if let Some((_texture, bind_group)) = self.textures.get(&mesh.texture_id) {
+                        let distance_field =
+                            self.distance_field_textures.contains(&mesh.texture_id);
+                        if distance_field != distance_field_pipeline_is_set {
+                            rpass.set_pipeline(if distance_field {
+                                &self.distance_field_pipeline
+                            } else {
+                                &self.render_pipeline
+                            });
+                            distance_field_pipeline_is_set = distance_field;
+                        }
                         rpass.set_bind_group(1, bind_group, &[]);
                         rpass.set_index_buffer(
                             index_buffer.buffer.slice(..),
@@ -367,6 +393,11 @@ impl RenderPass {
                     image.pixels.len(),
                     "Mismatch between texture size and texel count"
                 );
+                if image.distance_field {
+                    self.distance_field_textures.insert(id);
+                } else {
+                    self.distance_field_textures.remove(&id);
+                }
                 Cow::Owned(image.srgba_pixels(1.0).collect::<Vec<_>>())
             }
         };
@@ -451,6 +482,7 @@ impl RenderPass {
     /// Should be called before `execute()`.
     pub fn free_texture(&mut self, id: &egui::TextureId) {
         self.textures.remove(id);
+        self.distance_field_textures.remove(id);
     }
 
     /// Registers a `wgpu::Texture` with a `egui::TextureId`.
@@ -0,0 +1,661 @@
+//! A docking layout of tabs, which the user can drag into tab stacks, splits and floating windows.
+//!
+//! ```
+//! # egui::__run_test_ctx(|ctx| {
+//! use egui::dock::{DockArea, DockNode};
+//! let layout = DockNode::horizontal(
+//!     DockNode::tabs(["Files"]),
+//!     DockNode::vertical(DockNode::tabs(["Editor", "Preview"]), DockNode::tabs(["Console"]), 0.7),
+//!     0.25,
+//! );
+//! DockArea::new("my_dock", layout).show(ctx, |ui, tab| {
+//!     ui.label(format!("This is the {} tab.", tab));
+//! });
+//! # });
+//! ```
+
+use epaint::util::FloatOrd;
+
+use crate::*;
+
+/// How the two nodes of a [`DockNode::Split`] are placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SplitDirection {
+    /// Side by side, the first on the left.
+    Horizontal,
+
+    /// Above each other, the first on top.
+    Vertical,
+}
+
+/// The layout of a [`DockArea`]: a tree of splits with stacks of tabs as leaves.
+///
+/// The tabs are identified by their names, which are also their titles.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum DockNode {
+    /// A stack of tabs, of which the active one is shown.
+    Tabs { tabs: Vec<String>, active: usize },
+
+    /// Two nodes next to each other, with a handle in between to resize them.
+    Split {
+        direction: SplitDirection,
+        /// How much of the space goes to the first node, from 0 to 1.
+        fraction: f32,
+        nodes: Box<[DockNode; 2]>,
+    },
+}
+
+impl DockNode {
+    /// A stack of tabs, with the first one active.
+    pub fn tabs(tabs: impl IntoIterator<Item = impl ToString>) -> Self {
+        Self::Tabs {
+            tabs: tabs.into_iter().map(|tab| tab.to_string()).collect(),
+            active: 0,
+        }
+    }
+
+    /// `left` next to `right`, with `fraction` of the width going to `left`.
+    pub fn horizontal(left: DockNode, right: DockNode, fraction: f32) -> Self {
+        Self::Split {
+            direction: SplitDirection::Horizontal,
+            fraction,
+            nodes: Box::new([left, right]),
+        }
+    }
+
+    /// `top` above `bottom`, with `fraction` of the height going to `top`.
+    pub fn vertical(top: DockNode, bottom: DockNode, fraction: f32) -> Self {
+        Self::Split {
+            direction: SplitDirection::Vertical,
+            fraction,
+            nodes: Box::new([top, bottom]),
+        }
+    }
+
+    /// Is this an empty stack of tabs?
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Tabs { tabs, .. } if tabs.is_empty())
+    }
+
+    /// The names of all tabs, from the top left.
+    pub fn tab_names(&self) -> Vec<&str> {
+        match self {
+            Self::Tabs { tabs, .. } => tabs.iter().map(String::as_str).collect(),
+            Self::Split { nodes, .. } => nodes.iter().flat_map(Self::tab_names).collect(),
+        }
+    }
+
+    /// Remove the tab, and the split it was in if its stack is left empty.
+    fn remove(&mut self, tab: &str) -> bool {
+        match self {
+            Self::Tabs { tabs, active } => {
+                let len = tabs.len();
+                tabs.retain(|other| other != tab);
+                *active = (*active).min(tabs.len().saturating_sub(1));
+                tabs.len() < len
+            }
+            Self::Split { nodes, .. } => {
+                let removed = nodes.iter_mut().any(|node| node.remove(tab));
+                if let Some(empty) = nodes.iter().position(Self::is_empty) {
+                    *self = std::mem::replace(&mut nodes[1 - empty], Self::tabs([""; 0]));
+                }
+                removed
+            }
+        }
+    }
+
+    /// The stack of tabs with this tab in it.
+    fn stack_mut(&mut self, tab: &str) -> Option<&mut Self> {
+        match self {
+            Self::Tabs { tabs, .. } => tabs.iter().any(|other| other == tab).then(|| self),
+            Self::Split { nodes, .. } => nodes.iter_mut().find_map(|node| node.stack_mut(tab)),
+        }
+    }
+
+    /// Add a tab to this stack, or split the stack to put the tab next to it.
+    fn insert(&mut self, tab: String, zone: DropZone) {
+        let (direction, first) = match zone {
+            DropZone::Left => (SplitDirection::Horizontal, true),
+            DropZone::Right => (SplitDirection::Horizontal, false),
+            DropZone::Top => (SplitDirection::Vertical, true),
+            DropZone::Bottom => (SplitDirection::Vertical, false),
+            DropZone::Center => {
+                if let Self::Tabs { tabs, active } = self {
+                    tabs.push(tab);
+                    *active = tabs.len() - 1;
+                }
+                return;
+            }
+        };
+        if self.is_empty() {
+            return self.insert(tab, DropZone::Center);
+        }
+        let new = Self::tabs([tab]);
+        let old = std::mem::replace(self, Self::tabs([""; 0]));
+        *self = Self::Split {
+            direction,
+            fraction: 0.5,
+            nodes: Box::new(if first { [new, old] } else { [old, new] }),
+        };
+    }
+}
+
+/// Tabs in a window of their own, see [`DockState::floating`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FloatingTabs {
+    id: Id,
+    pub node: DockNode,
+    /// Where the window is first shown. After that it is wherever the user moves it.
+    pub default_pos: Pos2,
+    pub default_size: Vec2,
+}
+
+/// The layout of a [`DockArea`], kept in [`Memory`] and persisted with it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct DockState {
+    /// The docked tabs.
+    pub root: DockNode,
+
+    /// The tabs that have been dragged out of the dock.
+    pub floating: Vec<FloatingTabs>,
+
+    /// Tells the floating windows apart.
+    next_floating_id: u64,
+
+    /// The tab being dragged.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dragged: Option<String>,
+}
+
+impl DockState {
+    pub fn new(root: DockNode) -> Self {
+        Self {
+            root,
+            floating: Vec::new(),
+            next_floating_id: 0,
+            dragged: None,
+        }
+    }
+
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data().get_persisted(id)
+    }
+
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data().insert_persisted(id, self);
+    }
+
+    /// Is the tab docked or floating?
+    pub fn contains(&self, tab: &str) -> bool {
+        self.nodes().any(|node| node.tab_names().contains(&tab))
+    }
+
+    /// Add a tab to the first stack of the dock, e.g. to open a view that was not in the layout it was stored with.
+    pub fn push_tab(&mut self, tab: impl ToString) {
+        let mut node = &mut self.root;
+        while let DockNode::Split { nodes, .. } = node {
+            node = &mut nodes[0];
+        }
+        node.insert(tab.to_string(), DropZone::Center);
+    }
+
+    /// Remove a tab wherever it is.
+    pub fn remove_tab(&mut self, tab: &str) -> bool {
+        // Each tab is in one place only:
+        let removed = self.nodes_mut().any(|node| node.remove(tab));
+        self.floating.retain(|floating| !floating.node.is_empty());
+        removed
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &DockNode> {
+        std::iter::once(&self.root).chain(self.floating.iter().map(|floating| &floating.node))
+    }
+
+    fn nodes_mut(&mut self) -> impl Iterator<Item = &mut DockNode> {
+        std::iter::once(&mut self.root)
+            .chain(self.floating.iter_mut().map(|floating| &mut floating.node))
+    }
+
+    /// Move the dragged tab to the stack it was dropped on, or into a new floating window.
+    fn drop_tab(
+        &mut self,
+        dock_id: Id,
+        tab: String,
+        target: Option<(&DropTarget, DropZone)>,
+        pos: Pos2,
+    ) {
+        match target {
+            Some((target, zone)) => {
+                // Find the stack by one of its other tabs, as it may move when the tab is removed:
+                let other = target.tabs.iter().find(|other| **other != tab).cloned();
+                if other.is_none() && !target.tabs.is_empty() {
+                    return; // Dropped on its own stack, where it is the only tab.
+                }
+                self.remove_tab(&tab);
+                let stack = match &other {
+                    Some(other) => self.nodes_mut().find_map(|node| node.stack_mut(other)),
+                    None => self.nodes_mut().find(|node| node.is_empty()),
+                };
+                if let Some(stack) = stack {
+                    stack.insert(tab, zone);
+                }
+            }
+            None => {
+                self.remove_tab(&tab);
+                self.floating.push(FloatingTabs {
+                    id: dock_id.with(("floating", self.next_floating_id)),
+                    node: DockNode::tabs([tab]),
+                    default_pos: pos - vec2(20.0, 10.0),
+                    default_size: vec2(320.0, 240.0),
+                });
+                self.next_floating_id += 1;
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Where on a stack of tabs a dragged tab is dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DropZone {
+    Center,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl DropZone {
+    /// The pointer near an edge of the rectangle splits it, elsewhere it adds to the stack.
+    fn at(rect: Rect, pointer: Pos2) -> Self {
+        let relative = (pointer - rect.min) / rect.size();
+        let edges = [
+            (relative.x, Self::Left),
+            (1.0 - relative.x, Self::Right),
+            (relative.y, Self::Top),
+            (1.0 - relative.y, Self::Bottom),
+        ];
+        edges
+            .into_iter()
+            .filter(|(distance, _)| *distance < 0.25)
+            .min_by_key(|(distance, _)| distance.ord())
+            .map_or(Self::Center, |(_, zone)| zone)
+    }
+
+    /// The part of the rectangle the tab would take.
+    fn preview(self, rect: Rect) -> Rect {
+        let center = rect.center();
+        match self {
+            Self::Center => rect,
+            Self::Left => Rect::from_min_max(rect.min, pos2(center.x, rect.max.y)),
+            Self::Right => Rect::from_min_max(pos2(center.x, rect.min.y), rect.max),
+            Self::Top => Rect::from_min_max(rect.min, pos2(rect.max.x, center.y)),
+            Self::Bottom => Rect::from_min_max(pos2(rect.min.x, center.y), rect.max),
+        }
+    }
+}
+
+/// A stack of tabs shown this frame, which a dragged tab can be dropped on.
+struct DropTarget {
+    rect: Rect,
+    tabs: Vec<String>,
+}
+
+/// What is gathered while showing the nodes.
+struct DockUi<'a> {
+    dragged: Option<String>,
+    targets: Vec<DropTarget>,
+    add_tab: &'a mut dyn FnMut(&mut Ui, &str),
+}
+
+impl DockUi<'_> {
+    fn show_node(&mut self, ui: &mut Ui, node: &mut DockNode, rect: Rect, id: Id) {
+        match node {
+            DockNode::Tabs { tabs, active } => self.show_tabs(ui, tabs, active, rect, id),
+            DockNode::Split {
+                direction,
+                fraction,
+                nodes,
+            } => {
+                let rects = split_handle(ui, *direction, fraction, rect, id);
+                for (index, node) in nodes.iter_mut().enumerate() {
+                    self.show_node(ui, node, rects[index], id.with(index));
+                }
+            }
+        }
+    }
+
+    fn show_tabs(&mut self, ui: &mut Ui, tabs: &[String], active: &mut usize, rect: Rect, id: Id) {
+        self.targets.push(DropTarget {
+            rect,
+            tabs: tabs.to_vec(),
+        });
+
+        let bar_height = ui.spacing().interact_size.y;
+        let bar_rect = Rect::from_min_size(rect.min, vec2(rect.width(), bar_height));
+        let content_rect = Rect::from_min_max(pos2(rect.min.x, bar_rect.max.y), rect.max);
+        let painter = ui.painter_at(bar_rect);
+        painter.rect_filled(bar_rect, 0.0, ui.visuals().extreme_bg_color);
+
+        let mut x = bar_rect.left();
+        for (index, tab) in tabs.iter().enumerate() {
+            let padding = ui.spacing().button_padding;
+            let text = WidgetText::from(tab.as_str()).into_galley(
+                ui,
+                Some(false),
+                f32::INFINITY,
+                TextStyle::Button,
+            );
+            let tab_rect = Rect::from_min_size(
+                pos2(x, bar_rect.top()),
+                vec2(text.size().x + 2.0 * padding.x, bar_height),
+            );
+            x = tab_rect.right();
+
+            let response = ui
+                .interact(
+                    tab_rect.intersect(bar_rect),
+                    id.with(tab),
+                    Sense::click_and_drag(),
+                )
+                .on_hover_cursor(CursorIcon::Grab);
+            if response.clicked() {
+                *active = index;
+            }
+            // Only drag once the pointer has moved too much for a click:
+            if response.dragged()
+                && !ui.input().pointer.could_any_button_be_click()
+                && self.dragged.is_none()
+            {
+                self.dragged = Some(tab.clone());
+            }
+
+            let selected = index == *active;
+            let visuals = ui.style().interact_selectable(&response, selected);
+            let fill = if selected {
+                ui.visuals().window_fill()
+            } else {
+                visuals.bg_fill
+            };
+            if selected || response.hovered() {
+                painter.rect_filled(tab_rect, Rounding::none(), fill);
+            }
+            let text_pos = ui
+                .layout()
+                .align_size_within_rect(text.size(), tab_rect.shrink2(padding))
+                .min;
+            text.paint_with_visuals(&painter, text_pos, &visuals);
+        }
+
+        ui.painter()
+            .rect_filled(content_rect, 0.0, ui.visuals().window_fill());
+        if let Some(tab) = tabs.get(*active) {
+            let mut content_ui = ui.child_ui_with_id_source(
+                content_rect.shrink(ui.spacing().window_margin.left),
+                Layout::top_down(Align::Min),
+                tab,
+            );
+            content_ui.set_clip_rect(content_rect.intersect(ui.clip_rect()));
+            ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(&mut content_ui, |ui| (self.add_tab)(ui, tab));
+        }
+    }
+}
+
+/// Let the user drag the line between the nodes of a split, like the edge of a [`SidePanel`].
+/// Returns the rectangles of the two nodes.
+fn split_handle(
+    ui: &Ui,
+    direction: SplitDirection,
+    fraction: &mut f32,
+    rect: Rect,
+    id: Id,
+) -> [Rect; 2] {
+    let horizontal = direction == SplitDirection::Horizontal;
+    let range = if horizontal {
+        rect.x_range()
+    } else {
+        rect.y_range()
+    };
+    let length = range.end() - range.start();
+    let grab_radius = ui.style().interaction.resize_grab_radius_side;
+
+    let split = lerp(range.clone(), *fraction);
+    let handle_rect = if horizontal {
+        Rect::from_x_y_ranges(split - grab_radius..=split + grab_radius, rect.y_range())
+    } else {
+        Rect::from_x_y_ranges(rect.x_range(), split - grab_radius..=split + grab_radius)
+    };
+    let response = ui.interact(handle_rect, id.with("__resize"), Sense::drag());
+    if let Some(pointer) = response
+        .interact_pointer_pos()
+        .filter(|_| response.dragged())
+    {
+        let pointer = if horizontal { pointer.x } else { pointer.y };
+        // Keep some of both nodes visible:
+        let min_fraction = (3.0 * ui.spacing().interact_size.y / length).min(0.5);
+        *fraction = ((pointer - range.start()) / length).clamp(min_fraction, 1.0 - min_fraction);
+    }
+    if response.hovered() || response.dragged() {
+        ui.output().cursor_icon = if horizontal {
+            CursorIcon::ResizeHorizontal
+        } else {
+            CursorIcon::ResizeVertical
+        };
+    }
+
+    let split = lerp(range, *fraction);
+    let stroke = if response.dragged() {
+        ui.visuals().widgets.active.bg_stroke
+    } else if response.hovered() {
+        ui.visuals().widgets.hovered.bg_stroke
+    } else {
+        ui.visuals().widgets.noninteractive.bg_stroke
+    };
+    let gap = 0.5 * stroke.width.at_least(1.0);
+    if horizontal {
+        ui.painter().vline(split, rect.y_range(), stroke);
+        [
+            Rect::from_min_max(rect.min, pos2(split - gap, rect.max.y)),
+            Rect::from_min_max(pos2(split + gap, rect.min.y), rect.max),
+        ]
+    } else {
+        ui.painter().hline(rect.x_range(), split, stroke);
+        [
+            Rect::from_min_max(rect.min, pos2(rect.max.x, split - gap)),
+            Rect::from_min_max(pos2(rect.min.x, split + gap), rect.max),
+        ]
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Views in tabs, which the user can drag to rearrange them: onto another stack of tabs,
+/// to the edge of one to split it, or out of the dock into a floating window and back.
+///
+/// The layout is kept in [`Memory`] as a [`DockState`], so it is persisted with the `persistence` feature.
+/// See the [module docs](self) for an example.
+pub struct DockArea {
+    id: Id,
+    default_layout: DockNode,
+}
+
+impl DockArea {
+    /// The `default_layout` is used until the user changes it.
+    pub fn new(id_source: impl std::hash::Hash, default_layout: DockNode) -> Self {
+        Self {
+            id: Id::new(id_source),
+            default_layout,
+        }
+    }
+
+    /// The id the [`DockState`] is stored with.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Show the dock in the space left by the panels, like a [`CentralPanel`].
+    ///
+    /// `add_tab` is called with the name of each visible tab, to show its contents.
+    pub fn show(self, ctx: &Context, add_tab: impl FnMut(&mut Ui, &str)) -> Response {
+        CentralPanel::default()
+            .frame(Frame::none())
+            .show(ctx, |ui| self.show_inside(ui, add_tab))
+            .inner
+    }
+
+    /// Show the dock in the rest of the [`Ui`].
+    ///
+    /// `add_tab` is called with the name of each visible tab, to show its contents.
+    pub fn show_inside(self, ui: &mut Ui, mut add_tab: impl FnMut(&mut Ui, &str)) -> Response {
+        let Self { id, default_layout } = self;
+        let mut state =
+            DockState::load(ui.ctx(), id).unwrap_or_else(|| DockState::new(default_layout));
+
+        let rect = ui.available_rect_before_wrap();
+        let response = ui.allocate_rect(rect, Sense::hover());
+
+        let mut dock_ui = DockUi {
+            dragged: state.dragged.take(),
+            targets: Vec::new(),
+            add_tab: &mut add_tab,
+        };
+        dock_ui.show_node(ui, &mut state.root, rect, id);
+        for floating in &mut state.floating {
+            Area::new(floating.id)
+                .default_pos(floating.default_pos)
+                .show(ui.ctx(), |ui| {
+                    Frame::window(ui.style()).show(ui, |ui| {
+                        Resize::default()
+                            .default_size(floating.default_size)
+                            .show(ui, |ui| {
+                                let rect = ui.available_rect_before_wrap();
+                                ui.allocate_rect(rect, Sense::hover());
+                                dock_ui.show_node(ui, &mut floating.node, rect, floating.id);
+                            });
+                    });
+                });
+        }
+
+        let DockUi {
+            dragged, targets, ..
+        } = dock_ui;
+        state.dragged = dragged;
+        if let (Some(tab), Some(pointer)) = (state.dragged.clone(), ui.ctx().pointer_latest_pos()) {
+            // The floating windows are on top, and were added last:
+            let target = targets
+                .iter()
+                .rev()
+                .find(|target| target.rect.contains(pointer))
+                .map(|target| (target, DropZone::at(target.rect, pointer)));
+
+            if ui.input().pointer.any_down() {
+                paint_drag(ui, &tab, target, pointer);
+            } else {
+                state.dragged = None;
+                state.drop_tab(id, tab, target, pointer);
+            }
+        }
+
+        state.store(ui.ctx(), id);
+        response
+    }
+}
+
+/// The dragged tab at the pointer, and where it would go if dropped.
+fn paint_drag(ui: &Ui, tab: &str, target: Option<(&DropTarget, DropZone)>, pointer: Pos2) {
+    ui.output().cursor_icon = CursorIcon::Grabbing;
+    let painter = ui
+        .ctx()
+        .layer_painter(LayerId::new(Order::Tooltip, Id::new("dock_drag")));
+    if let Some((target, zone)) = target {
+        let selection = ui.visuals().selection;
+        painter.rect(
+            zone.preview(target.rect),
+            ui.visuals().widgets.noninteractive.rounding,
+            selection.bg_fill.linear_multiply(0.3),
+            selection.stroke,
+        );
+    }
+    let text = WidgetText::from(tab).into_galley(ui, Some(false), f32::INFINITY, TextStyle::Button);
+    let rect = Rect::from_min_size(pointer, text.size() + 2.0 * ui.spacing().button_padding);
+    painter.rect(
+        rect,
+        ui.visuals().widgets.noninteractive.rounding,
+        ui.visuals().window_fill(),
+        ui.visuals().window_stroke(),
+    );
+    text.paint_with_fallback_color(
+        &painter,
+        rect.min + ui.spacing().button_padding,
+        ui.visuals().text_color(),
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_dock_drop() {
+    let mut state = DockState::new(DockNode::horizontal(
+        DockNode::tabs(["a"]),
+        DockNode::tabs(["b", "c"]),
+        0.5,
+    ));
+    let target = |tabs: &[&str]| DropTarget {
+        rect: Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0)),
+        tabs: tabs.iter().map(|tab| tab.to_string()).collect(),
+    };
+    let id = Id::new("dock");
+
+    // Dropping the only tab of a stack on the edge of another collapses the split it was in:
+    let zone = DropZone::at(target(&[]).rect, pos2(50.0, 90.0));
+    assert_eq!(zone, DropZone::Bottom);
+    state.drop_tab(
+        id,
+        "a".to_owned(),
+        Some((&target(&["b", "c"]), zone)),
+        Pos2::ZERO,
+    );
+    assert_eq!(
+        state.root,
+        DockNode::vertical(DockNode::tabs(["b", "c"]), DockNode::tabs(["a"]), 0.5)
+    );
+
+    // Out of the dock into a window, and back onto a stack:
+    state.drop_tab(id, "c".to_owned(), None, Pos2::ZERO);
+    assert_eq!(state.floating.len(), 1);
+    assert!(state.contains("c"));
+    state.drop_tab(
+        id,
+        "c".to_owned(),
+        Some((&target(&["a"]), DropZone::Center)),
+        Pos2::ZERO,
+    );
+    assert!(state.floating.is_empty());
+    assert_eq!(
+        state.root,
+        DockNode::vertical(
+            DockNode::tabs(["b"]),
+            DockNode::Tabs {
+                tabs: vec!["a".to_owned(), "c".to_owned()],
+                active: 1
+            },
+            0.5
+        )
+    );
+
+    // Dropping a lone tab on its own stack changes nothing:
+    let before = state.clone();
+    state.drop_tab(
+        id,
+        "b".to_owned(),
+        Some((&target(&["b"]), DropZone::Left)),
+        Pos2::ZERO,
+    );
+    assert_eq!(state, before);
+}
@@ -5,21 +5,27 @@
 pub(crate) mod area;
 pub mod collapsing_header;
 mod combo_box;
+pub mod dock;
 pub(crate) mod frame;
+mod modal;
 pub mod panel;
 pub mod popup;
 pub(crate) mod resize;
 pub(crate) mod scroll_area;
+pub mod tree_view;
 pub(crate) mod window;
 
 pub use {
     area::Area,
     collapsing_header::{CollapsingHeader, CollapsingResponse},
     combo_box::*,
+    dock::{DockArea, DockNode, DockState},
     frame::Frame,
+    modal::Modal,
     panel::{CentralPanel, SidePanel, TopBottomPanel},
     popup::*,
     resize::Resize,
     scroll_area::ScrollArea,
+    tree_view::{TreeData, TreeView, TreeViewState},
     window::Window,
 };
@@ -0,0 +1,192 @@
+//! A dialog on top of everything else, which blocks input to the rest of the UI.
+
+use crate::*;
+
+/// A dialog that has to be dealt with before the rest of the UI can be used again.
+///
+/// A modal is shown in the middle of the screen, over a dimmed background.
+/// While it is open, the widgets below it can't be hovered, clicked or focused,
+/// so Tab only moves the keyboard focus between the widgets of the modal.
+/// It closes when the user presses Escape, if you give it an `open` flag.
+///
+/// Modals can be stacked: a modal shown from within another modal blocks that one too.
+///
+/// ```
+/// # egui::__run_test_ctx(|ctx| {
+/// # let mut open = true;
+/// egui::Modal::new("confirm_quit").open(&mut open).show(ctx, |ui| {
+///     ui.label("Quit without saving?");
+///     if ui.button("Quit").clicked() {
+///         // …
+///     }
+/// });
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+pub struct Modal<'open> {
+    area: Area,
+    open: Option<&'open mut bool>,
+    frame: Option<Frame>,
+    backdrop_color: Color32,
+}
+
+impl<'open> Modal<'open> {
+    /// The `id_source` must be unique, and should not change.
+    pub fn new(id_source: impl std::hash::Hash) -> Self {
+        Self {
+            area: Area::new(id_source).order(Order::Foreground),
+            open: None,
+            frame: None,
+            backdrop_color: Color32::from_black_alpha(100),
+        }
+    }
+
+    /// * If `*open == false`, the modal will not be shown.
+    /// * If the user presses Escape, `*open` will be set to `false`.
+    pub fn open(mut self, open: &'open mut bool) -> Self {
+        self.open = Some(open);
+        self
+    }
+
+    /// Change the background color, margins, etc. of the dialog. Default is [`Frame::window`].
+    pub fn frame(mut self, frame: Frame) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+
+    /// The color painted over everything below the modal.
+    pub fn backdrop_color(mut self, backdrop_color: impl Into<Color32>) -> Self {
+        self.backdrop_color = backdrop_color.into();
+        self
+    }
+
+    /// Returns `None` if the modal is not open (if [`Modal::open`] was called with `&mut false`).
+    ///
+    /// The response is that of the dialog.
+    pub fn show<R>(
+        self,
+        ctx: &Context,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> Option<InnerResponse<R>> {
+        let Self {
+            area,
+            open,
+            frame,
+            backdrop_color,
+        } = self;
+
+        let layer_id = area.layer();
+        if let Some(open) = open {
+            // Only the top-most modal closes, and not while one of its popups is open:
+            let is_top = ctx.memory().areas.top_modal() == Some(layer_id);
+            let escape = ctx.input().key_pressed(Key::Escape);
+            if is_top && escape && !ctx.memory().any_popup_open() {
+                *open = false;
+            }
+            if !*open {
+                return None;
+            }
+        }
+        ctx.memory().areas.set_modal(layer_id);
+
+        let screen_rect = ctx.input().screen_rect();
+        let frame = frame.unwrap_or_else(|| Frame::window(&ctx.style()));
+        let size_id = area.id.with("dialog_size");
+        let last_size = ctx.data().get_temp::<Vec2>(size_id);
+        if last_size.is_none() {
+            ctx.request_repaint(); // We don't know where to put the dialog yet.
+        }
+
+        let InnerResponse { inner, .. } = area.fixed_pos(screen_rect.min).show(ctx, |ui| {
+            // The backdrop covers the whole screen, so it catches all clicks outside the dialog:
+            ui.set_clip_rect(screen_rect);
+            ui.painter().rect_filled(screen_rect, 0.0, backdrop_color);
+            ui.allocate_rect(screen_rect, Sense::hover());
+
+            let dialog_rect =
+                Rect::from_center_size(screen_rect.center(), last_size.unwrap_or_default());
+            let mut dialog_ui = ui.child_ui(
+                Rect::from_min_max(dialog_rect.min, screen_rect.max),
+                Layout::top_down(Align::Min),
+            );
+            frame.show(&mut dialog_ui, add_contents)
+        });
+        ctx.data().insert_temp(size_id, inner.response.rect.size());
+        Some(inner)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_modal() {
+    let ctx = Context::default();
+    // Returns the rectangle of a button below the modals, if it was clicked, and the focusable widgets of the first modal.
+    let run = |events: Vec<Event>, open: &mut [bool; 2]| {
+        let raw_input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 400.0))),
+            events,
+            ..Default::default()
+        };
+        let (mut below, mut clicked, mut focusable) = (Rect::NOTHING, false, Vec::new());
+        let _ = ctx.run(raw_input, |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                let response = ui.button("Below");
+                below = response.rect;
+                clicked = response.clicked();
+            });
+            let [first, second] = open;
+            Modal::new("first").open(first).show(ctx, |ui| {
+                focusable = vec![ui.button("One").id, ui.text_edit_singleline(&mut "").id];
+                Modal::new("second").open(second).show(ctx, |ui| {
+                    ui.label("On top");
+                });
+            });
+        });
+        (below, clicked, focusable)
+    };
+    let key = |key| Event::Key {
+        key,
+        pressed: true,
+        modifiers: Default::default(),
+    };
+    let click = |pos| {
+        [true, false]
+            .map(|pressed| Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed,
+                modifiers: Default::default(),
+            })
+            .to_vec()
+    };
+
+    let mut open = [false, false];
+    let (below, ..) = run(vec![], &mut open);
+    assert!(run(click(below.center()), &mut open).1);
+
+    // The modal blocks the button, and keeps the focus to itself:
+    open[0] = true;
+    run(vec![], &mut open);
+    run(vec![], &mut open);
+    assert!(!run(click(below.center()), &mut open).1);
+    for _ in 0..5 {
+        // After the last widget, the focus goes to the first one the next frame:
+        run(vec![key(Key::Tab)], &mut open);
+        let (.., mut focusable) = run(vec![], &mut open);
+        // Like that of a window, the area of the modal takes part in the tab order too:
+        focusable.push(Id::new("first").with("move"));
+        let focus = ctx.memory().focus();
+        assert!(focus.map_or(false, |focus| focusable.contains(&focus)));
+    }
+
+    // Escape closes the top-most modal only:
+    open[1] = true;
+    run(vec![], &mut open);
+    run(vec![key(Key::Escape)], &mut open);
+    assert_eq!(open, [true, false]);
+    run(vec![], &mut open);
+    run(vec![key(Key::Escape)], &mut open);
+    assert_eq!(open, [false, false]);
+    run(vec![], &mut open);
+    assert!(run(click(below.center()), &mut open).1);
+}
@@ -0,0 +1,799 @@
+//! A tree of nodes that the user can expand, select, and drag to other parents.
+//!
+//! The children of a node are only asked for while it is expanded, and only the rows in view are shown,
+//! so it works for big trees. The expanded nodes are asked for their children every frame though,
+//! so for slow trees, like a file system, keep the children you have loaded in your [`TreeData`].
+//!
+//! ```
+//! # egui::__run_test_ui(|ui| {
+//! use egui::tree_view::{TreeData, TreeView};
+//!
+//! /// Node `n` has the children `10 * n + 1` to `10 * n + 3`.
+//! struct Numbers;
+//!
+//! impl TreeData for Numbers {
+//!     type Node = u64;
+//!
+//!     fn roots(&mut self) -> Vec<u64> {
+//!         vec![1, 2, 3]
+//!     }
+//!
+//!     fn is_branch(&mut self, node: &u64) -> bool {
+//!         *node < 1_000
+//!     }
+//!
+//!     fn children(&mut self, node: &u64) -> Vec<u64> {
+//!         (1..=3).map(|i| 10 * node + i).collect()
+//!     }
+//!
+//!     fn node_ui(&mut self, ui: &mut egui::Ui, node: &u64) {
+//!         ui.label(node.to_string());
+//!     }
+//! }
+//!
+//! let response = TreeView::new("numbers").multi_select(true).show(ui, &mut Numbers);
+//! for node in &response.selected {
+//!     ui.label(format!("Selected: {}", node));
+//! }
+//! # });
+//! ```
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::*;
+
+/// The nodes of a [`TreeView`], and how to show them.
+pub trait TreeData {
+    /// Tells the nodes apart, like a path or a handle.
+    ///
+    /// The state of a node, like whether it is expanded, is stored with the hash of it,
+    /// so it must stay the same from frame to frame.
+    type Node: Clone + Hash;
+
+    /// The nodes at the top of the tree.
+    fn roots(&mut self) -> Vec<Self::Node>;
+
+    /// Can the node have children? If so, it gets an arrow to expand it.
+    fn is_branch(&mut self, node: &Self::Node) -> bool;
+
+    /// The children of a branch.
+    ///
+    /// This is called every frame for each expanded node, also those out of view, to know which rows there are.
+    /// It should be quick, so load the children the first time it is called, e.g. by reading a directory,
+    /// and keep them until they change.
+    fn children(&mut self, node: &Self::Node) -> Vec<Self::Node>;
+
+    /// Show the node in its row, e.g. with a label.
+    fn node_ui(&mut self, ui: &mut Ui, node: &Self::Node);
+}
+
+/// Which nodes of a [`TreeView`] are expanded and selected, kept in [`Memory`] and persisted with it.
+///
+/// The nodes are referred to by the hash of their [`TreeData::Node`], see [`Self::node_id`].
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TreeViewState {
+    /// The expanded nodes.
+    pub open: HashSet<Id>,
+
+    /// The selected nodes.
+    pub selected: HashSet<Id>,
+
+    /// The node that was clicked or moved to with the keyboard last.
+    pub active: Option<Id>,
+
+    /// Where a range selected with Shift starts.
+    anchor: Option<Id>,
+
+    /// The nodes being dragged, from the top.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dragged: Vec<Id>,
+}
+
+impl TreeViewState {
+    pub fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data().get_persisted(id)
+    }
+
+    pub fn store(self, ctx: &Context, id: Id) {
+        ctx.data().insert_persisted(id, self);
+    }
+
+    /// The id of a node in the state.
+    pub fn node_id(node: &impl Hash) -> Id {
+        Id::new(node)
+    }
+
+    pub fn is_open(&self, node: &impl Hash) -> bool {
+        self.open.contains(&Self::node_id(node))
+    }
+
+    /// Expand or collapse the node, e.g. to show a node the user has to see.
+    pub fn set_open(&mut self, node: &impl Hash, open: bool) {
+        let id = Self::node_id(node);
+        if open {
+            self.open.insert(id);
+        } else {
+            self.open.remove(&id);
+        }
+    }
+
+    pub fn is_selected(&self, node: &impl Hash) -> bool {
+        self.selected.contains(&Self::node_id(node))
+    }
+
+    /// Select the row like when it is clicked with these modifiers.
+    fn click<N>(&mut self, rows: &[Row<N>], row: usize, multi_select: bool, modifiers: Modifiers) {
+        let id = rows[row].id;
+        let range_start = if multi_select && modifiers.shift {
+            self.anchor
+                .and_then(|anchor| rows.iter().position(|row| row.id == anchor))
+        } else {
+            None
+        };
+        if let Some(anchor) = range_start {
+            if !modifiers.command {
+                self.selected.clear();
+            }
+            let range = anchor.min(row)..=anchor.max(row);
+            self.selected.extend(rows[range].iter().map(|row| row.id));
+        } else if modifiers.command {
+            if !self.selected.remove(&id) {
+                if !multi_select {
+                    self.selected.clear();
+                }
+                self.selected.insert(id);
+            }
+            self.anchor = Some(id);
+        } else {
+            self.selected.clear();
+            self.selected.insert(id);
+            self.anchor = Some(id);
+        }
+        self.active = Some(id);
+    }
+
+    /// Move the active row with the arrow keys, Home and End, selecting it, like [`Self::click`].
+    /// Right expands the active node or moves to its first child, Left collapses it or moves to its parent.
+    ///
+    /// Returns `false` if the key does nothing.
+    fn key_pressed<N>(
+        &mut self,
+        rows: &[Row<N>],
+        key: Key,
+        modifiers: Modifiers,
+        multi_select: bool,
+    ) -> bool {
+        let last = match rows.len().checked_sub(1) {
+            Some(last) => last,
+            None => return false,
+        };
+        let active = self
+            .active
+            .and_then(|active| rows.iter().position(|row| row.id == active));
+        let row = match (key, active) {
+            (Key::ArrowUp, Some(active)) => active.saturating_sub(1),
+            (Key::ArrowDown, Some(active)) => (active + 1).min(last),
+            (Key::ArrowRight, Some(active)) if rows[active].is_branch && !rows[active].is_open => {
+                self.open.insert(rows[active].id);
+                return true;
+            }
+            (Key::ArrowRight, Some(active)) => match rows.get(active + 1) {
+                Some(child) if child.parent == Some(active) => active + 1,
+                _ => return false,
+            },
+            (Key::ArrowLeft, Some(active)) if rows[active].is_open => {
+                self.open.remove(&rows[active].id);
+                return true;
+            }
+            (Key::ArrowLeft, Some(active)) => match rows[active].parent {
+                Some(parent) => parent,
+                None => return false,
+            },
+            (Key::ArrowUp | Key::ArrowDown | Key::ArrowRight | Key::ArrowLeft | Key::Home, _) => 0,
+            (Key::End, _) => last,
+            _ => return false,
+        };
+        let modifiers = Modifiers {
+            command: false,
+            ..modifiers
+        };
+        self.click(rows, row, multi_select, modifiers);
+        true
+    }
+}
+
+/// A node in an expanded branch, in the order they are shown.
+struct Row<N> {
+    node: N,
+    id: Id,
+    depth: usize,
+    /// The row of the parent.
+    parent: Option<usize>,
+    /// Among the children of the parent.
+    index: usize,
+    is_branch: bool,
+    is_open: bool,
+}
+
+/// The rows of the tree from the top, for which only the expanded nodes are asked for their children.
+///
+/// They aren't kept from frame to frame, so that changes to the [`TreeData`] show right away.
+fn tree_rows<D: TreeData>(data: &mut D, state: &TreeViewState) -> Vec<Row<D::Node>> {
+    fn add_rows<D: TreeData>(
+        data: &mut D,
+        state: &TreeViewState,
+        rows: &mut Vec<Row<D::Node>>,
+        nodes: Vec<D::Node>,
+        parent: Option<usize>,
+        depth: usize,
+    ) {
+        for (index, node) in nodes.into_iter().enumerate() {
+            let id = TreeViewState::node_id(&node);
+            let is_branch = data.is_branch(&node);
+            let is_open = is_branch && state.open.contains(&id);
+            let children = if is_open {
+                data.children(&node)
+            } else {
+                Vec::new()
+            };
+            rows.push(Row {
+                node,
+                id,
+                depth,
+                parent,
+                index,
+                is_branch,
+                is_open,
+            });
+            add_rows(data, state, rows, children, Some(rows.len() - 1), depth + 1);
+        }
+    }
+
+    let mut rows = Vec::new();
+    let roots = data.roots();
+    add_rows(data, state, &mut rows, roots, None, 0);
+    rows
+}
+
+/// Is the row, or any of its ancestors, one of these?
+fn is_within<N>(rows: &[Row<N>], mut row: Option<usize>, is_one: impl Fn(&Id) -> bool) -> bool {
+    while let Some(index) = row {
+        if is_one(&rows[index].id) {
+            return true;
+        }
+        row = rows[index].parent;
+    }
+    false
+}
+
+/// Where the dragged nodes go when they are dropped on a row.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct DropTarget {
+    /// The row of the new parent, `None` at the top of the tree.
+    parent: Option<usize>,
+
+    /// Among the children of the parent, `None` after the last one.
+    index: Option<usize>,
+}
+
+impl DropTarget {
+    /// The middle of a branch puts the nodes in it, the top and bottom of a row before and after it.
+    fn at<N>(
+        rows: &[Row<N>],
+        row: usize,
+        rect: Rect,
+        pointer: Pos2,
+        dragged: &[Id],
+    ) -> Option<Self> {
+        let relative_y = (pointer.y - rect.top()) / rect.height();
+        let target = &rows[row];
+        let (parent, index) = if target.is_branch && (0.25..0.75).contains(&relative_y) {
+            (Some(row), None)
+        } else if relative_y < 0.5 {
+            (target.parent, Some(target.index))
+        } else if target.is_open {
+            // Its children are right below it:
+            (Some(row), Some(0))
+        } else {
+            (target.parent, Some(target.index + 1))
+        };
+        // Not into themselves:
+        (!is_within(rows, parent, |id| dragged.contains(id))).then(|| Self { parent, index })
+    }
+
+    /// A line where the nodes go, or the row they go into, for the target at the row.
+    fn paint<N>(
+        self,
+        ui: &Ui,
+        painter: &Painter,
+        rows: &[Row<N>],
+        row: usize,
+        rect: Rect,
+        indent: f32,
+    ) {
+        let stroke = ui.visuals().selection.stroke;
+        match self.index {
+            None => {
+                painter.rect_stroke(rect, 0.0, stroke);
+            }
+            Some(index) => {
+                let depth = self.parent.map_or(0, |parent| rows[parent].depth + 1);
+                let above = self.parent == rows[row].parent && index == rows[row].index;
+                let half_spacing = 0.5 * ui.spacing().item_spacing.y;
+                let y = if above {
+                    rect.top() - half_spacing
+                } else {
+                    rect.bottom() + half_spacing
+                };
+                let x = rect.left() + depth as f32 * indent;
+                painter.hline(x..=rect.right(), y, stroke);
+            }
+        }
+    }
+}
+
+/// What the user dropped where, see [`TreeView::drag_and_drop`].
+///
+/// Moving the nodes is up to you.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeDrop<N> {
+    /// The dragged nodes, from the top. If a node and its parent are selected, only the parent is dragged.
+    pub nodes: Vec<N>,
+
+    /// The new parent, `None` for the top of the tree.
+    pub parent: Option<N>,
+
+    /// Where the nodes go among the children of the parent,
+    /// counted before the nodes are removed from where they were.
+    pub index: usize,
+}
+
+/// The result of [`TreeView::show`].
+pub struct TreeViewResponse<N> {
+    /// The tree as a whole, which has the keyboard focus when the user is moving through it.
+    pub response: Response,
+
+    /// The selected nodes in the expanded branches, from the top.
+    pub selected: Vec<N>,
+
+    /// The nodes the user dropped this frame, if any.
+    pub dropped: Option<TreeDrop<N>>,
+}
+
+// ----------------------------------------------------------------------------
+
+/// A tree with an expandable row for each node, like that of a file browser.
+///
+/// The user selects nodes by clicking them, with Ctrl (Cmd on Mac) and Shift to select more if [`Self::multi_select`] is on.
+/// Once a node is clicked the tree has the keyboard focus, and the arrow keys move the selection and expand and collapse the nodes.
+///
+/// The rows all have the same height, and only those in view are shown, in a [`ScrollArea`].
+/// The state is kept in [`Memory`] as a [`TreeViewState`], so it is persisted with the `persistence` feature.
+/// See the [module docs](self) for an example.
+pub struct TreeView {
+    id: Id,
+    row_height: Option<f32>,
+    indent: Option<f32>,
+    multi_select: bool,
+    drag_and_drop: bool,
+}
+
+impl TreeView {
+    pub fn new(id_source: impl Hash) -> Self {
+        Self {
+            id: Id::new(id_source),
+            row_height: None,
+            indent: None,
+            multi_select: false,
+            drag_and_drop: false,
+        }
+    }
+
+    /// The id the [`TreeViewState`] is stored with.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// The height of the rows, without the spacing between them.
+    /// Default is [`style::Spacing::interact_size`]`.y`.
+    pub fn row_height(mut self, row_height: f32) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// How much the children are indented from their parent.
+    /// Default is [`style::Spacing::indent`].
+    pub fn indent(mut self, indent: f32) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// Can the user select several nodes, with Ctrl (Cmd on Mac) and Shift? Default is `false`.
+    pub fn multi_select(mut self, multi_select: bool) -> Self {
+        self.multi_select = multi_select;
+        self
+    }
+
+    /// Can the user drag the selected nodes to another parent? Default is `false`.
+    ///
+    /// The drops are in [`TreeViewResponse::dropped`], for you to move the nodes.
+    pub fn drag_and_drop(mut self, drag_and_drop: bool) -> Self {
+        self.drag_and_drop = drag_and_drop;
+        self
+    }
+
+    /// Show the tree in the width of the [`Ui`], scrolling when it is higher than the [`Ui`].
+    pub fn show<D: TreeData>(self, ui: &mut Ui, data: &mut D) -> TreeViewResponse<D::Node> {
+        let Self {
+            id,
+            row_height,
+            indent,
+            multi_select,
+            drag_and_drop,
+        } = self;
+        let row_height = row_height.unwrap_or_else(|| ui.spacing().interact_size.y);
+        let indent = indent.unwrap_or_else(|| ui.spacing().indent);
+        let mut state = TreeViewState::load(ui.ctx(), id).unwrap_or_default();
+
+        let mut rows = tree_rows(data, &state);
+
+        let mut scroll_to_active = false;
+        if ui.memory().has_focus(id) {
+            let keys: Vec<(Key, Modifiers)> = ui
+                .input()
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+                .collect();
+            for (key, modifiers) in keys {
+                if state.key_pressed(&rows, key, modifiers, multi_select) {
+                    // Some nodes may have been expanded or collapsed:
+                    rows = tree_rows(data, &state);
+                    scroll_to_active = true;
+                }
+            }
+        }
+
+        let mut toggled = Vec::new();
+        let output = ScrollArea::vertical()
+            .id_source(id)
+            .auto_shrink([false, true])
+            .show_rows(ui, row_height, rows.len(), |ui, range| {
+                // So that the tree can have the keyboard focus, for moving the selection:
+                let response = ui.interact(ui.clip_rect(), id, Sense::focusable_noninteractive());
+                let has_focus = response.has_focus();
+
+                let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+                let top = ui.max_rect().top() - range.start as f32 * row_height_with_spacing;
+                let x_range = ui.max_rect().x_range();
+                let row_rect = |row: usize| {
+                    let top = top + row as f32 * row_height_with_spacing;
+                    Rect::from_x_y_ranges(x_range.clone(), top..=top + row_height)
+                };
+
+                if scroll_to_active {
+                    let active = state
+                        .active
+                        .and_then(|active| rows.iter().position(|row| row.id == active));
+                    if let Some(active) = active {
+                        ui.scroll_to_rect(row_rect(active), None);
+                    }
+                }
+
+                let mut row_rects = Vec::new();
+                for row in range {
+                    let rect = row_rect(row);
+                    let row_ui = RowUi {
+                        id,
+                        rows: &rows,
+                        row,
+                        rect,
+                        indent,
+                        multi_select,
+                        drag_and_drop,
+                        has_focus,
+                    };
+                    if row_ui.show(ui, data, &mut state) {
+                        toggled.push(row);
+                    }
+                    row_rects.push((row, rect));
+                }
+                (response, row_rects)
+            });
+        let (response, row_rects) = output.inner;
+
+        for row in toggled {
+            let row = &rows[row];
+            state.set_open(&row.node, !row.is_open);
+            ui.ctx().request_repaint(); // To load and show the children.
+        }
+
+        let mut dropped = None;
+        if !state.dragged.is_empty() {
+            ui.output().cursor_icon = CursorIcon::Grabbing;
+            let target = ui
+                .ctx()
+                .pointer_latest_pos()
+                .filter(|pointer| output.inner_rect.contains(*pointer))
+                .and_then(|pointer| {
+                    // Between the rows counts as the row above:
+                    let spacing = vec2(0.0, ui.spacing().item_spacing.y);
+                    let (row, rect) = row_rects.iter().find(|(_, rect)| {
+                        Rect::from_min_max(rect.min, rect.max + spacing).contains(pointer)
+                    })?;
+                    let target = DropTarget::at(&rows, *row, *rect, pointer, &state.dragged)?;
+                    Some((target, *row, *rect))
+                });
+
+            if ui.input().pointer.any_down() {
+                if let Some((target, row, rect)) = target {
+                    let painter = ui.painter_at(output.inner_rect);
+                    target.paint(ui, &painter, &rows, row, rect, indent);
+                }
+            } else {
+                let dragged = std::mem::take(&mut state.dragged);
+                dropped = target.map(|(target, _, _)| {
+                    let parent = target.parent.map(|parent| rows[parent].node.clone());
+                    TreeDrop {
+                        nodes: rows
+                            .iter()
+                            .filter(|row| dragged.contains(&row.id))
+                            .map(|row| row.node.clone())
+                            .collect(),
+                        index: target.index.unwrap_or_else(|| match &parent {
+                            Some(parent) => data.children(parent).len(),
+                            None => data.roots().len(),
+                        }),
+                        parent,
+                    }
+                });
+            }
+        }
+
+        let selected = rows
+            .iter()
+            .filter(|row| state.selected.contains(&row.id))
+            .map(|row| row.node.clone())
+            .collect();
+        state.store(ui.ctx(), id);
+
+        TreeViewResponse {
+            response,
+            selected,
+            dropped,
+        }
+    }
+}
+
+/// One row of a [`TreeView`].
+struct RowUi<'a, N> {
+    id: Id,
+    rows: &'a [Row<N>],
+    row: usize,
+    rect: Rect,
+    indent: f32,
+    multi_select: bool,
+    drag_and_drop: bool,
+    has_focus: bool,
+}
+
+impl<N: Clone + Hash> RowUi<'_, N> {
+    /// Returns `true` if the node is expanded or collapsed.
+    fn show<D: TreeData<Node = N>>(
+        self,
+        ui: &mut Ui,
+        data: &mut D,
+        state: &mut TreeViewState,
+    ) -> bool {
+        let Self {
+            id,
+            rows,
+            row,
+            rect,
+            indent,
+            multi_select,
+            drag_and_drop,
+            has_focus,
+        } = self;
+        let node = &rows[row];
+        let row_id = id.with(node.id);
+        let selected = state.selected.contains(&node.id);
+        let hovered = state.dragged.is_empty() && ui.rect_contains_pointer(rect);
+
+        let visuals = ui.visuals();
+        let highlight = if selected {
+            Some(visuals.selection.bg_fill)
+        } else if hovered {
+            Some(visuals.widgets.hovered.bg_fill)
+        } else {
+            None
+        };
+        if let Some(fill) = highlight {
+            ui.painter().rect_filled(rect, 0.0, fill);
+        }
+
+        // A line down from each of the parents, through their children:
+        let icon_width = ui.spacing().icon_width;
+        let guide_stroke = ui.visuals().widgets.noninteractive.bg_stroke;
+        let half_spacing = 0.5 * ui.spacing().item_spacing.y;
+        for depth in 0..node.depth {
+            let x = rect.left() + depth as f32 * indent + 0.5 * icon_width;
+            let y_range = rect.top() - half_spacing..=rect.bottom() + half_spacing;
+            ui.painter().vline(x, y_range, guide_stroke);
+        }
+
+        let icon_rect = Rect::from_center_size(
+            pos2(
+                rect.left() + node.depth as f32 * indent + 0.5 * icon_width,
+                rect.center().y,
+            ),
+            Vec2::splat(icon_width),
+        );
+        let mut toggled = false;
+        if node.is_branch {
+            let response = ui.interact(icon_rect, row_id.with("__toggle"), Sense::click());
+            toggled = response.clicked();
+            let openness = if node.is_open { 1.0 } else { 0.0 };
+            collapsing_header::paint_default_icon(ui, openness, &response);
+        }
+
+        let content_rect = Rect::from_min_max(
+            pos2(icon_rect.right() + ui.spacing().icon_spacing, rect.top()),
+            rect.max,
+        );
+        let mut content_ui = ui.child_ui_with_id_source(
+            content_rect,
+            Layout::left_to_right().with_cross_align(Align::Center),
+            node.id,
+        );
+        content_ui.set_clip_rect(content_rect.intersect(ui.clip_rect()));
+        data.node_ui(&mut content_ui, &node.node);
+
+        // After the contents, so that the widgets in it get the clicks first:
+        let sense = if drag_and_drop {
+            Sense::click_and_drag()
+        } else {
+            Sense::click()
+        };
+        let response = ui.interact(rect, row_id, sense);
+        // With drag, the row also gets the clicks on the widgets in it, and on the arrow:
+        let pressed_on_row = ui.memory().interaction.click_id == Some(row_id);
+        if response.clicked() && pressed_on_row {
+            let modifiers = ui.input().modifiers;
+            state.click(rows, row, multi_select, modifiers);
+            ui.memory().request_focus(id);
+            ui.ctx().request_repaint(); // To show the new selection.
+        }
+        if response.double_clicked() && pressed_on_row && node.is_branch {
+            toggled = true;
+        }
+        if response.dragged()
+            && pressed_on_row
+            && ui.input().pointer.has_moved_too_much_for_a_click()
+            && state.dragged.is_empty()
+        {
+            state.dragged = if selected {
+                // The selected nodes, but not those in a selected parent, as they go along with it:
+                let selected = &state.selected;
+                rows.iter()
+                    .filter(|row| {
+                        selected.contains(&row.id)
+                            && !is_within(rows, row.parent, |id| selected.contains(id))
+                    })
+                    .map(|row| row.id)
+                    .collect()
+            } else {
+                vec![node.id]
+            };
+        }
+
+        if has_focus && state.active == Some(node.id) {
+            ui.painter()
+                .rect_stroke(rect, 0.0, ui.visuals().selection.stroke);
+        }
+        toggled
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_tree_view_rows() {
+    /// Node `n` has the children `10 * n + 1` and `10 * n + 2`.
+    struct Numbers(Vec<u64>);
+
+    impl TreeData for Numbers {
+        type Node = u64;
+        fn roots(&mut self) -> Vec<u64> {
+            vec![1, 2]
+        }
+        fn is_branch(&mut self, node: &u64) -> bool {
+            *node < 100
+        }
+        fn children(&mut self, node: &u64) -> Vec<u64> {
+            self.0.push(*node);
+            vec![10 * node + 1, 10 * node + 2]
+        }
+        fn node_ui(&mut self, _ui: &mut Ui, _node: &u64) {}
+    }
+
+    let mut data = Numbers(Vec::new());
+    let mut state = TreeViewState::default();
+    let nodes = |rows: &[Row<u64>]| rows.iter().map(|row| row.node).collect::<Vec<_>>();
+
+    // Only the expanded nodes are asked for their children:
+    state.set_open(&1, true);
+    state.set_open(&11, true);
+    state.set_open(&21, true);
+    let rows = tree_rows(&mut data, &state);
+    assert_eq!(nodes(&rows), [1, 11, 111, 112, 12, 2]);
+    assert_eq!(data.0, [1, 11]);
+
+    // Right expands, and then moves to the first child. Left moves to the parent, and then collapses:
+    state.active = Some(TreeViewState::node_id(&12));
+    assert!(state.key_pressed(&rows, Key::ArrowRight, Modifiers::default(), false));
+    let rows = tree_rows(&mut data, &state);
+    assert_eq!(nodes(&rows), [1, 11, 111, 112, 12, 121, 122, 2]);
+    assert!(state.key_pressed(&rows, Key::ArrowRight, Modifiers::default(), false));
+    assert!(state.is_selected(&121));
+    assert!(state.key_pressed(&rows, Key::ArrowLeft, Modifiers::default(), false));
+    assert!(state.key_pressed(&rows, Key::ArrowLeft, Modifiers::default(), false));
+    assert!(!state.is_open(&12));
+
+    // Shift selects a range:
+    let shift = Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    state.key_pressed(&rows, Key::ArrowUp, shift, true);
+    state.key_pressed(&rows, Key::ArrowUp, shift, true);
+    let selected = |state: &TreeViewState| {
+        rows.iter()
+            .filter(|row| state.selected.contains(&row.id))
+            .map(|row| row.node)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(selected(&state), [111, 112, 12]);
+
+    // Dropped in the middle of a branch goes into it, but not into a dragged node:
+    let rect = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 20.0));
+    let row_11 = 1;
+    let dragged = [TreeViewState::node_id(&2)];
+    assert_eq!(
+        DropTarget::at(&rows, row_11, rect, pos2(50.0, 10.0), &dragged),
+        Some(DropTarget {
+            parent: Some(row_11),
+            index: None
+        })
+    );
+    let dragged = [TreeViewState::node_id(&1)];
+    assert_eq!(
+        DropTarget::at(&rows, row_11, rect, pos2(50.0, 10.0), &dragged),
+        None
+    );
+
+    // At the bottom of an expanded branch the nodes go first in it, and at the top before it:
+    let dragged = [TreeViewState::node_id(&2)];
+    assert_eq!(
+        DropTarget::at(&rows, row_11, rect, pos2(50.0, 19.0), &dragged),
+        Some(DropTarget {
+            parent: Some(row_11),
+            index: Some(0)
+        })
+    );
+    assert_eq!(
+        DropTarget::at(&rows, row_11, rect, pos2(50.0, 1.0), &dragged),
+        Some(DropTarget {
+            parent: Some(0),
+            index: Some(0)
+        })
+    );
+}
@@ -99,6 +99,13 @@ impl ContextImpl {
         });
 
         fonts.begin_frame(pixels_per_point, max_texture_side);
+        fonts.set_subpixel_positioning(
+            self.memory
+                .options
+                .tessellation_options
+                .subpixel_text_positioning,
+        );
+        fonts.set_glyph_atlas_mode(self.memory.options.tessellation_options.glyph_atlas_mode);
 
         if self.memory.options.preload_font_glyphs {
             // Preload the most common characters for the most common fonts.
@@ -324,7 +331,9 @@ impl Context {
         enabled: bool,
         hovered: bool,
     ) -> Response {
-        let hovered = hovered && enabled; // can't even hover disabled widgets
+        // Widgets below a modal can't be used:
+        let blocked = self.memory().areas.is_blocked_by_modal(layer_id);
+        let hovered = hovered && enabled && !blocked; // can't even hover disabled widgets
 
         let mut response = Response {
             ctx: self.clone(),
@@ -344,8 +353,8 @@ impl Context {
             changed: false, // must be set by the widget itself
         };
 
-        if !enabled || !sense.focusable || !layer_id.allow_interaction() {
-            // Not interested or allowed input:
+        if !enabled || !sense.focusable || !layer_id.allow_interaction() || blocked {
+            // Not interested or allowed input. This also keeps the keyboard focus inside a modal:
             self.memory().surrender_focus(id);
             return response;
         }
@@ -857,7 +866,10 @@ impl Context {
             prepared_discs,
             shapes,
         );
-        self.write().paint_stats = paint_stats.with_clipped_primitives(&clipped_primitives);
+        let paint_stats = paint_stats
+            .with_clipped_primitives(&clipped_primitives)
+            .with_font_atlas(&texture_atlas.lock());
+        self.write().paint_stats = paint_stats;
         clipped_primitives
     }
 
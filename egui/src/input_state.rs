@@ -775,6 +775,14 @@ impl PointerState {
         self.down[button as usize]
     }
 
+    /// Has the pointer moved too much since the last press for it to be a click?
+    ///
+    /// This stays `true` the frame the button is released, so it tells a drag from a click then too.
+    #[inline(always)]
+    pub fn has_moved_too_much_for_a_click(&self) -> bool {
+        self.has_moved_too_much_for_a_click
+    }
+
     /// If the pointer button is down, will it register as a click when released?
     #[inline(always)]
     pub(crate) fn could_any_button_be_click(&self) -> bool {
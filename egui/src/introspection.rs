@@ -19,6 +19,23 @@ pub fn font_id_ui(ui: &mut Ui, font_id: &mut FontId) {
             let text = alternative.to_string();
             ui.radio_value(&mut font_id.font_type, alternative, text);
         }
+
+        let mut bold = font_id.weight.is_bold();
+        if ui.checkbox(&mut bold, "Bold").changed() {
+            font_id.weight = if bold {
+                FontWeight::BOLD
+            } else {
+                FontWeight::NORMAL
+            };
+        }
+        let mut italic = font_id.style == FontStyle::Italic;
+        if ui.checkbox(&mut italic, "Italic").changed() {
+            font_id.style = if italic {
+                FontStyle::Italic
+            } else {
+                FontStyle::Normal
+            };
+        }
     });
 }
 
@@ -97,6 +114,7 @@ impl Widget for &epaint::stats::PaintStats {
                 clipped_primitives,
                 vertices,
                 indices,
+                font_atlas,
             } = self;
 
             ui.label("Intermediate:");
@@ -124,6 +142,17 @@ impl Widget for &epaint::stats::PaintStats {
             label(ui, indices, "indices").on_hover_text("Three 32-bit indices per triangles");
             ui.add_space(10.0);
 
+            ui.label("Font atlas:");
+            ui.label(format!(
+                "{:6} glyphs in {} x {} texels, {:.0}% full",
+                font_atlas.num_allocations,
+                font_atlas.size[0],
+                font_atlas.size[1],
+                100.0 * font_atlas.fill_ratio
+            ));
+            ui.label(format!("{:6} times grown", font_atlas.num_resizes));
+            ui.add_space(10.0);
+
             // ui.label("Total:");
             // ui.label(self.total().format(""));
         })
@@ -144,6 +173,8 @@ impl Widget for &mut epaint::TessellationOptions {
                 coarse_tessellation_culling,
                 prerasterized_discs,
                 round_text_to_pixels,
+                subpixel_text_positioning,
+                glyph_atlas_mode,
                 debug_paint_clip_rects,
                 debug_paint_text_rects,
                 debug_ignore_clip_rects,
@@ -161,6 +192,17 @@ impl Widget for &mut epaint::TessellationOptions {
 
             ui.checkbox(prerasterized_discs, "Speed up filled circles with pre-rasterization");
 
+            ui.checkbox(subpixel_text_positioning, "Subpixel text positioning")
+                .on_hover_text("Space small text evenly by rasterizing each glyph at several offsets within a pixel. Uses more of the font texture.");
+
+            ui.horizontal(|ui| {
+                ui.label("Glyph atlas:");
+                ui.radio_value(glyph_atlas_mode, epaint::GlyphAtlasMode::Coverage, "Coverage")
+                    .on_hover_text("Rasterize each glyph for each font size.");
+                ui.radio_value(glyph_atlas_mode, epaint::GlyphAtlasMode::DistanceField, "Distance field")
+                    .on_hover_text("Store each glyph once as a signed distance field and scale it to all sizes. Needs backend support.");
+            });
+
             ui.add(
                 crate::widgets::Slider::new(bezier_tolerance, 0.0001..=10.0)
                     .logarithmic(true)
@@ -125,6 +125,17 @@ impl PaintList {
         idx
     }
 
+    /// The index that the next added [`Shape`] will get.
+    #[inline(always)]
+    pub(crate) fn next_idx(&self) -> ShapeIdx {
+        ShapeIdx(self.0.len())
+    }
+
+    /// The shapes added since [`Self::next_idx`] returned `idx`.
+    pub(crate) fn shapes_since(&self, idx: ShapeIdx) -> &[ClippedShape] {
+        &self.0[idx.0..]
+    }
+
     pub fn extend(&mut self, clip_rect: Rect, mut shapes: Vec<Shape>) {
         self.0
             .extend(shapes.drain(..).map(|shape| ClippedShape(clip_rect, shape)));
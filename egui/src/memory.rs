@@ -437,6 +437,11 @@ impl Memory {
         self.popup == Some(popup_id) || self.everything_is_visible()
     }
 
+    /// Is any popup open?
+    pub fn any_popup_open(&self) -> bool {
+        self.popup.is_some() || self.everything_is_visible()
+    }
+
     pub fn open_popup(&mut self, popup_id: Id) {
         self.popup = Some(popup_id);
     }
@@ -493,6 +498,14 @@ pub struct Areas {
     /// So if you close three windows and then reopen them all in one frame,
     /// they will all be sent to the top, but keep their previous internal order.
     wants_to_be_on_top: AHashSet<LayerId>,
+
+    /// The [`Modal`](crate::Modal)s shown this frame.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    modals: Vec<LayerId>,
+
+    /// The top-most [`Modal`](crate::Modal) shown last frame, which blocks input to all layers below it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    top_modal: Option<LayerId>,
 }
 
 impl Areas {
@@ -560,6 +573,33 @@ impl Areas {
             .collect()
     }
 
+    /// Block input to the layers below this one, see [`Self::is_blocked_by_modal`].
+    pub(crate) fn set_modal(&mut self, layer_id: LayerId) {
+        self.modals.push(layer_id);
+    }
+
+    /// The top-most [`Modal`](crate::Modal) shown last frame, if any.
+    pub fn top_modal(&self) -> Option<LayerId> {
+        self.top_modal
+    }
+
+    /// Is this layer below the top-most [`Modal`](crate::Modal),
+    /// so that its widgets should neither be hovered, clicked nor focused?
+    pub fn is_blocked_by_modal(&self, layer_id: LayerId) -> bool {
+        match self.top_modal {
+            Some(modal) if modal != layer_id => {
+                if layer_id.order == modal.order {
+                    // Layers that are not areas, like panels, are below all areas of their order.
+                    let position = |layer| self.order.iter().position(|x| *x == layer);
+                    position(layer_id) < position(modal)
+                } else {
+                    layer_id.order < modal.order
+                }
+            }
+            _ => false,
+        }
+    }
+
     pub fn move_to_top(&mut self, layer_id: LayerId) {
         self.visible_current_frame.insert(layer_id);
         self.wants_to_be_on_top.insert(layer_id);
@@ -575,6 +615,8 @@ impl Areas {
             visible_current_frame,
             order,
             wants_to_be_on_top,
+            modals,
+            top_modal,
             ..
         } = self;
 
@@ -582,6 +624,12 @@ impl Areas {
         visible_current_frame.clear();
         order.sort_by_key(|layer| (layer.order, wants_to_be_on_top.contains(layer)));
         wants_to_be_on_top.clear();
+        *top_modal = order
+            .iter()
+            .rev()
+            .find(|layer| modals.contains(layer))
+            .copied();
+        modals.clear();
     }
 }
 
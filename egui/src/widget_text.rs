@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use crate::{
-    style::WidgetVisuals, text::LayoutJob, Align, Color32, FontSelection, FontType, Galley, Pos2,
-    Style, TextStyle, Ui, Visuals,
+    style::WidgetVisuals, text::LayoutJob, Align, Color32, FontSelection, FontStyle, FontType,
+    FontWeight, Galley, Pos2, Style, TextStyle, Ui, Visuals,
 };
 
 /// Text and optional style choices for it.
@@ -19,12 +19,14 @@ use crate::{
 /// RichText::new("Plain");
 /// RichText::new("colored").color(Color32::RED);
 /// RichText::new("Large and underlined").size(20.0).underline();
+/// RichText::new("Bold and slanted").strong().italics();
 /// ```
 #[derive(Clone, Default, PartialEq)]
 pub struct RichText {
     text: String,
     size: Option<f32>,
     family: Option<FontType>,
+    weight: Option<FontWeight>,
     text_style: Option<TextStyle>,
     background_color: Color32,
     text_color: Option<Color32>,
@@ -35,6 +37,8 @@ pub struct RichText {
     underline: bool,
     italics: bool,
     raised: bool,
+    line_height: Option<f32>,
+    extra_letter_spacing: f32,
 }
 
 impl From<&str> for RichText {
@@ -103,16 +107,39 @@ impl RichText {
         self
     }
 
-    /// Select the font and size.
+    /// Select the font weight, e.g. [`FontWeight::BOLD`].
+    ///
+    /// This overrides the bold weight of [`Self::strong`].
+    ///
+    /// Uses the closest font in [`crate::FontDefinitions::font_variants`],
+    /// and makes the characters bolder if none is heavy enough.
+    #[inline]
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Select the font, size, weight and style.
     /// This overrides the value from [`Self::text_style`].
+    ///
+    /// A normal weight or upright style in `font_id` keeps what was set with
+    /// [`Self::weight`], [`Self::strong`] or [`Self::italics`].
     #[inline]
     pub fn font(mut self, font_id: crate::FontId) -> Self {
         let crate::FontId {
             size,
             font_type: family,
+            weight,
+            style,
         } = font_id;
         self.size = Some(size);
         self.family = Some(family);
+        if weight != FontWeight::default() {
+            self.weight = Some(weight);
+        }
+        if style == FontStyle::Italic {
+            self.italics = true;
+        }
         self
     }
 
@@ -149,7 +176,7 @@ impl RichText {
         self.text_style(TextStyle::Monospace)
     }
 
-    /// Extra strong text (stronger color).
+    /// Extra strong text: bold, with a stronger color.
     #[inline]
     pub fn strong(mut self) -> Self {
         self.strong = true;
@@ -181,7 +208,9 @@ impl RichText {
         self
     }
 
-    /// Tilt the characters to the right.
+    /// Use an italic font, or tilt the characters to the right if there is none.
+    ///
+    /// See [`crate::FontDefinitions::font_variants`].
     #[inline]
     pub fn italics(mut self) -> Self {
         self.italics = true;
@@ -207,6 +236,22 @@ impl RichText {
         self
     }
 
+    /// Set the height of each row of text, in points.
+    ///
+    /// The text is centered vertically within its row.
+    #[inline]
+    pub fn line_height(mut self, line_height: f32) -> Self {
+        self.line_height = Some(line_height);
+        self
+    }
+
+    /// Extra space between the characters, in points. A negative value tightens the text.
+    #[inline]
+    pub fn extra_letter_spacing(mut self, extra_letter_spacing: f32) -> Self {
+        self.extra_letter_spacing = extra_letter_spacing;
+        self
+    }
+
     /// Fill-color behind the text.
     #[inline]
     pub fn background_color(mut self, background_color: impl Into<Color32>) -> Self {
@@ -234,6 +279,14 @@ impl RichText {
         if let Some(family) = &self.family {
             font_id.font_type = family.clone();
         }
+        if let Some(weight) = self.weight {
+            font_id.weight = weight;
+        } else if self.strong {
+            font_id.weight = FontWeight::BOLD;
+        }
+        if self.italics {
+            font_id.style = FontStyle::Italic;
+        }
         fonts.row_height(&font_id)
     }
 
@@ -249,16 +302,19 @@ impl RichText {
             text,
             size,
             family,
+            weight,
             text_style,
             background_color,
             text_color: _, // already used by `get_text_color`
             code,
-            strong: _, // already used by `get_text_color`
-            weak: _,   // already used by `get_text_color`
+            strong,
+            weak: _, // already used by `get_text_color`
             strikethrough,
             underline,
             italics,
             raised,
+            line_height,
+            extra_letter_spacing,
         } = self;
 
         let job_has_color = text_color.is_some();
@@ -278,6 +334,14 @@ impl RichText {
             if let Some(family) = family {
                 font_id.font_type = family;
             }
+            if let Some(weight) = weight {
+                font_id.weight = weight;
+            } else if strong {
+                font_id.weight = FontWeight::BOLD;
+            }
+            if italics {
+                font_id.style = FontStyle::Italic;
+            }
             font_id
         };
 
@@ -306,10 +370,12 @@ impl RichText {
             font_id,
             color: text_color,
             background: background_color,
-            italics,
             underline,
             strikethrough,
             valign,
+            line_height,
+            extra_letter_spacing,
+            ..Default::default()
         };
 
         let job = LayoutJob::single_section(text, text_format);
@@ -449,6 +515,14 @@ impl WidgetText {
         }
     }
 
+    /// Prefer using [`RichText`] directly!
+    pub fn weight(self, weight: FontWeight) -> Self {
+        match self {
+            Self::RichText(text) => Self::RichText(text.weight(weight)),
+            Self::LayoutJob(_) | Self::Galley(_) => self,
+        }
+    }
+
     /// Prefer using [`RichText`] directly!
     pub fn weak(self) -> Self {
         match self {
@@ -711,3 +785,25 @@ impl WidgetTextGalley {
         painter.galley_with_color(text_pos, self.galley, text_color);
     }
 }
+
+#[test]
+fn test_rich_text_font_keeps_weight_and_italics() {
+    use crate::FontId;
+
+    let text = RichText::new("text")
+        .italics()
+        .weight(FontWeight::BOLD)
+        .font(FontId::proportional(10.0));
+    assert!(text.italics);
+    assert_eq!(text.weight, Some(FontWeight::BOLD));
+    assert_eq!(text.size, Some(10.0));
+
+    let text = RichText::new("text").font(FontId {
+        weight: FontWeight::LIGHT,
+        style: FontStyle::Italic,
+        ..FontId::monospace(12.0)
+    });
+    assert!(text.italics);
+    assert_eq!(text.weight, Some(FontWeight::LIGHT));
+    assert_eq!(text.family, Some(FontType::Monospace));
+}
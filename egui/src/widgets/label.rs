@@ -1,4 +1,4 @@
-use crate::{widget_text::WidgetTextGalley, *};
+use crate::{text::TextOverflow, widget_text::WidgetTextGalley, *};
 
 /// Static text.
 ///
@@ -9,6 +9,7 @@ use crate::{widget_text::WidgetTextGalley, *};
 /// ui.label("Equivalent");
 /// ui.add(egui::Label::new("Equivalent"));
 /// ui.add(egui::Label::new("With Options").wrap(false));
+/// ui.add(egui::Label::new("Cut off with … if it is too long").truncate(true));
 /// ui.label(egui::RichText::new("With formatting").underline());
 /// # });
 /// ```
@@ -16,6 +17,8 @@ use crate::{widget_text::WidgetTextGalley, *};
 pub struct Label {
     text: WidgetText,
     wrap: Option<bool>,
+    max_rows: Option<usize>,
+    overflow: Option<TextOverflow>,
     sense: Sense,
 }
 
@@ -24,6 +27,8 @@ impl Label {
         Self {
             text: text.into(),
             wrap: None,
+            max_rows: None,
+            overflow: None,
             sense: Sense::focusable_noninteractive(),
         }
     }
@@ -45,6 +50,44 @@ impl Label {
         self
     }
 
+    /// If `true`, show only one row of text, cut off with `…` at the right edge of the [`Ui`].
+    ///
+    /// Hovering a truncated label shows the full text.
+    ///
+    /// Shorthand for `.wrap(true).max_rows(1)`.
+    /// `false` goes back to the limit of the [`LayoutJob`](text::LayoutJob).
+    #[inline]
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        if truncate {
+            self.wrap(true).max_rows(1)
+        } else {
+            self.max_rows = None;
+            self
+        }
+    }
+
+    /// Show at most this many rows of text, and shorten the rest as decided by [`Self::overflow`].
+    ///
+    /// Hovering a shortened label shows the full text.
+    /// `0` means no limit.
+    ///
+    /// By default the limit of the [`LayoutJob`](text::LayoutJob) is used (none, unless you pass in a job).
+    #[inline]
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// How to shorten the text if it has more than [`Self::max_rows`] rows,
+    /// e.g. [`TextOverflow::MiddleEllipsis`] for file paths.
+    ///
+    /// By default the mode of the [`LayoutJob`](text::LayoutJob) is used ([`TextOverflow::EndEllipsis`]).
+    #[inline]
+    pub fn overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = Some(overflow);
+        self
+    }
+
     /// Make the label respond to clicks and/or drags.
     ///
     /// By default, a label is inert and does not respond to click or drags.
@@ -91,6 +134,13 @@ impl Label {
         let should_wrap = self.wrap.unwrap_or_else(|| ui.wrap_text());
         let available_width = ui.available_width();
 
+        if let Some(max_rows) = self.max_rows {
+            text_job.job.wrap.max_rows = max_rows;
+        }
+        if let Some(overflow) = self.overflow {
+            text_job.job.wrap.overflow = overflow;
+        }
+
         if should_wrap
             && ui.layout().main_dir() == Direction::LeftToRight
             && ui.layout().main_wrap()
@@ -157,9 +207,14 @@ impl Label {
 
 impl Widget for Label {
     fn ui(self, ui: &mut Ui) -> Response {
-        let (pos, text_galley, response) = self.layout_in_ui(ui);
+        let (pos, text_galley, mut response) = self.layout_in_ui(ui);
         response.widget_info(|| WidgetInfo::labeled(WidgetType::Label, text_galley.text()));
 
+        if text_galley.galley.elided {
+            // Show the full text when hovering the shortened text:
+            response = response.on_hover_text(text_galley.text());
+        }
+
         if ui.is_rect_visible(response.rect) {
             let response_color = ui.style().interact(&response).text_color();
 
@@ -187,3 +242,14 @@ impl Widget for Label {
         response
     }
 }
+
+#[test]
+fn test_truncate_false_keeps_the_job_limit() {
+    assert_eq!(Label::new("text").truncate(false).max_rows, None);
+    assert_eq!(Label::new("text").truncate(true).max_rows, Some(1));
+    assert_eq!(
+        Label::new("text").truncate(true).truncate(false).max_rows,
+        None
+    );
+    assert_eq!(Label::new("text").max_rows(3).max_rows, Some(3));
+}
@@ -0,0 +1,305 @@
+//! Axis titles, tick labels outside the plot frame, and the secondary Y axis.
+
+use std::ops::RangeInclusive;
+
+use crate::*;
+
+use super::{
+    log_grid_spacer, transform::ScreenTransform, AxisFormatter, AxisFormatterFn, GridInput,
+    GridSpacer, GridSpacerFn, PlotBounds, MIN_LINE_SPACING_IN_POINTS,
+};
+
+/// Space between the tick labels, the axis titles and the plot frame.
+const AXIS_LABEL_PADDING: f32 = 4.0;
+
+/// A second Y axis on the right-hand side of a [`Plot`](super::Plot), with its own bounds and labels.
+///
+/// Items are plotted against it when added inside [`PlotUi::on_secondary_y_axis`](super::PlotUi::on_secondary_y_axis).
+/// It shares the X axis with the rest of the plot, and is dragged and zoomed together with it.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{Line, Plot, SecondaryYAxis, Values};
+/// let temperature = Line::new(Values::from_ys_f32(&[20.0, 21.5, 23.0]));
+/// let pressure = Line::new(Values::from_ys_f32(&[101.3, 100.8, 99.9]));
+/// Plot::new("telemetry")
+///     .x_axis_label("Time [s]")
+///     .y_axis_label("Temperature [°C]")
+///     .secondary_y_axis(SecondaryYAxis::new().label("Pressure [kPa]"))
+///     .show(ui, |plot_ui| {
+///         plot_ui.line(temperature);
+///         plot_ui.on_secondary_y_axis(|plot_ui| plot_ui.line(pressure));
+///     });
+/// # });
+/// ```
+pub struct SecondaryYAxis {
+    pub(super) label: Option<WidgetText>,
+    pub(super) formatter: AxisFormatter,
+    pub(super) grid_spacer: GridSpacer,
+    pub(super) min_auto_bounds: PlotBounds,
+}
+
+impl Default for SecondaryYAxis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecondaryYAxis {
+    pub fn new() -> Self {
+        Self {
+            label: None,
+            formatter: None,
+            grid_spacer: log_grid_spacer(10),
+            min_auto_bounds: PlotBounds::NOTHING,
+        }
+    }
+
+    /// Title of the axis, shown to the right of its tick labels.
+    pub fn label(mut self, label: impl Into<WidgetText>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Customize the tick labels, like [`Plot::y_axis_formatter`](super::Plot::y_axis_formatter).
+    pub fn formatter(
+        mut self,
+        func: impl Fn(f64, &RangeInclusive<f64>) -> String + 'static,
+    ) -> Self {
+        self.formatter = Some(Box::new(func));
+        self
+    }
+
+    /// Configure how the ticks are spaced apart, like [`Plot::y_grid_spacer`](super::Plot::y_grid_spacer).
+    pub fn grid_spacer(
+        mut self,
+        spacer: impl Fn(GridInput) -> Vec<super::GridMark> + 'static,
+    ) -> Self {
+        self.grid_spacer = Box::new(spacer);
+        self
+    }
+
+    /// Expand the automatic bounds of this axis to include the given y value.
+    pub fn include_y(mut self, y: impl Into<f64>) -> Self {
+        self.min_auto_bounds.extend_with_y(y.into());
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The label of a grid mark along an axis.
+pub(super) struct TickLabel {
+    pub value: f64,
+    pub text: String,
+
+    /// How visible the label should be (`0..=1`), depending on how far apart the marks of its size are.
+    pub strength: f32,
+}
+
+/// The labels of the grid marks along one axis of the transform.
+pub(super) fn tick_labels(
+    transform: &ScreenTransform,
+    axis: usize,
+    formatter: Option<&AxisFormatterFn>,
+    grid_spacer: &GridSpacerFn,
+) -> Vec<TickLabel> {
+    let bounds = transform.bounds();
+    let axis_range = match axis {
+        0 => bounds.range_x(),
+        1 => bounds.range_y(),
+        _ => panic!("Axis {} does not exist.", axis),
+    };
+
+    let input = GridInput {
+        bounds: (bounds.min[axis], bounds.max[axis]),
+        base_step_size: transform.dvalue_dpos()[axis] * MIN_LINE_SPACING_IN_POINTS,
+    };
+
+    grid_spacer(input)
+        .into_iter()
+        .filter_map(|step| {
+            let spacing_in_points = (transform.dpos_dvalue()[axis] * step.step_size).abs() as f32;
+            let strength = remap_clamp(spacing_in_points, 40.0..=150.0, 0.0..=1.0);
+            if strength <= 0.0 {
+                return None;
+            }
+
+            let text = if let Some(formatter) = formatter {
+                formatter(step.value, &axis_range)
+            } else {
+                emath::round_to_decimals(step.value, 5).to_string() // hack
+            };
+
+            // Custom formatters can return empty string to signal "no label at this resolution"
+            (!text.is_empty()).then(|| TickLabel {
+                value: step.value,
+                text,
+                strength,
+            })
+        })
+        .collect()
+}
+
+// ----------------------------------------------------------------------------
+
+/// Space reserved around the plot frame for the tick labels and titles of the axes.
+pub(super) struct AxisGutters {
+    left: f32,
+    right: f32,
+    top: f32,
+    bottom: f32,
+}
+
+impl AxisGutters {
+    /// Measure the gutters needed for the titles of the X, Y and secondary Y axes,
+    /// and the tick labels of the Y and secondary Y axes.
+    ///
+    /// The tick labels depend on the size of the frame, so they are usually those of the previous frame.
+    pub fn measure(
+        ui: &Ui,
+        titles: [Option<&WidgetText>; 3],
+        y_tick_labels: [&[TickLabel]; 2],
+    ) -> Self {
+        let font_id = TextStyle::Body.resolve(ui.style());
+        let row_height = ui.fonts().row_height(&font_id);
+
+        let title_height = |title: Option<&WidgetText>| {
+            title.map_or(0.0, |title| {
+                let galley =
+                    title
+                        .clone()
+                        .into_galley(ui, Some(false), f32::INFINITY, TextStyle::Body);
+                galley.size().y + AXIS_LABEL_PADDING
+            })
+        };
+        let tick_width = |labels: &[TickLabel]| {
+            let width = labels
+                .iter()
+                .map(|label| {
+                    ui.painter()
+                        .layout_no_wrap(label.text.clone(), font_id.clone(), Color32::WHITE)
+                        .size()
+                        .x
+                })
+                .fold(0.0, f32::max);
+            // Round up so that the frame doesn't jitter as the labels change:
+            (width / row_height).ceil() * row_height + AXIS_LABEL_PADDING
+        };
+
+        Self {
+            left: tick_width(y_tick_labels[0]) + title_height(titles[1]),
+            right: if y_tick_labels[1].is_empty() && titles[2].is_none() {
+                AXIS_LABEL_PADDING
+            } else {
+                tick_width(y_tick_labels[1]) + title_height(titles[2])
+            },
+            top: 0.5 * row_height,
+            bottom: row_height + AXIS_LABEL_PADDING + title_height(titles[0]),
+        }
+    }
+
+    /// The plot frame inside `rect`.
+    pub fn frame(&self, rect: Rect) -> Rect {
+        let frame = Rect::from_min_max(
+            rect.min + vec2(self.left, self.top),
+            rect.max - vec2(self.right, self.bottom),
+        );
+        if frame.is_positive() {
+            frame
+        } else {
+            rect
+        }
+    }
+}
+
+/// Paint tick labels outside the frame of the transform: below it for `axis == 0`,
+/// and to its left or right (if `right`) for `axis == 1`.
+pub(super) fn paint_tick_labels(
+    ui: &Ui,
+    painter: &Painter,
+    transform: &ScreenTransform,
+    axis: usize,
+    right: bool,
+    labels: &[TickLabel],
+) {
+    let font_id = TextStyle::Body.resolve(ui.style());
+    let frame = *transform.frame();
+    let clip_rect = painter.clip_rect();
+
+    for label in labels {
+        let color = ui.visuals().text_color().linear_multiply(label.strength);
+        let galley = ui
+            .painter()
+            .layout_no_wrap(label.text.clone(), font_id.clone(), color);
+        let size = galley.size();
+
+        let pos = if axis == 0 {
+            let x = transform
+                .position_from_value(&super::Value::new(label.value, 0.0))
+                .x;
+            pos2(
+                (x - 0.5 * size.x)
+                    .at_most(clip_rect.right() - size.x)
+                    .at_least(clip_rect.left()),
+                frame.bottom() + AXIS_LABEL_PADDING,
+            )
+        } else {
+            let y = transform
+                .position_from_value(&super::Value::new(0.0, label.value))
+                .y;
+            let x = if right {
+                frame.right() + AXIS_LABEL_PADDING
+            } else {
+                frame.left() - AXIS_LABEL_PADDING - size.x
+            };
+            pos2(x, y - 0.5 * size.y)
+        };
+
+        painter.galley(pos, galley);
+    }
+}
+
+/// Paint the title of an axis along the edge of `rect`: at the bottom for `axis == 0`,
+/// and rotated at the left or right (if `right`) for `axis == 1`.
+pub(super) fn paint_axis_title(
+    ui: &Ui,
+    painter: &Painter,
+    rect: Rect,
+    frame: Rect,
+    axis: usize,
+    right: bool,
+    title: &WidgetText,
+) {
+    let galley = title
+        .clone()
+        .into_galley(ui, Some(false), f32::INFINITY, TextStyle::Body);
+    let size = galley.size();
+    let override_text_color = (!galley.galley_has_color).then(|| ui.visuals().text_color());
+
+    let (pos, angle) = if axis == 0 {
+        (
+            pos2(frame.center().x - 0.5 * size.x, rect.bottom() - size.y),
+            0.0,
+        )
+    } else {
+        // Rotated to read from bottom to top, so the galley extends up and to the right of `pos`:
+        let x = if right {
+            rect.right() - size.y
+        } else {
+            rect.left()
+        };
+        (
+            pos2(x, frame.center().y + 0.5 * size.x),
+            -std::f32::consts::TAU / 4.0,
+        )
+    };
+
+    painter.add(epaint::TextShape {
+        pos,
+        galley: galley.galley,
+        underline: Stroke::none(),
+        override_text_color,
+        angle,
+    });
+}
@@ -0,0 +1,746 @@
+//! Render a plot to an SVG document or, with the `png` feature, a PNG image, see [`PlotUi::export`](super::PlotUi::export).
+
+use std::fmt::{self, Write as _};
+
+#[cfg(feature = "png")]
+use epaint::{tessellator, ClippedPrimitive, ColorImage, FontImage, Primitive, Vertex};
+use epaint::{
+    CircleShape, ClippedShape, CubicBezierShape, PathShape, QuadraticBezierShape, RectShape,
+    TextShape, TextureId,
+};
+
+use crate::*;
+
+/// The file format of a [`PlotExport`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A Scalable Vector Graphics document, measured in points.
+    Svg,
+
+    /// A Portable Network Graphics image, measured in physical pixels.
+    ///
+    /// The plot is rasterized on the CPU. Needs the `png` feature.
+    #[cfg(feature = "png")]
+    Png,
+}
+
+impl ExportFormat {
+    /// The usual file name extension, e.g. `"svg"`.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            #[cfg(feature = "png")]
+            Self::Png => "png",
+        }
+    }
+
+    /// The media type, e.g. `"image/svg+xml"`.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Svg => "image/svg+xml",
+            #[cfg(feature = "png")]
+            Self::Png => "image/png",
+        }
+    }
+}
+
+/// A plot rendered to a file.
+#[derive(Clone)]
+pub struct PlotExport {
+    pub format: ExportFormat,
+
+    /// Width and height, in points for [`ExportFormat::Svg`] and in pixels for [`ExportFormat::Png`].
+    pub size: [usize; 2],
+
+    /// The contents of the file.
+    pub bytes: Vec<u8>,
+}
+
+impl PlotExport {
+    /// Render the `shapes` that were painted inside `rect`.
+    pub(super) fn new(
+        #[allow(unused_variables)] ctx: &Context,
+        format: ExportFormat,
+        rect: Rect,
+        shapes: Vec<ClippedShape>,
+    ) -> Self {
+        match format {
+            ExportFormat::Svg => {
+                let mut svg = String::new();
+                write_svg(&mut svg, rect, &shapes).expect("Writing to a String can't fail");
+                Self {
+                    format,
+                    size: [
+                        rect.width().round() as usize,
+                        rect.height().round() as usize,
+                    ],
+                    bytes: svg.into_bytes(),
+                }
+            }
+            #[cfg(feature = "png")]
+            ExportFormat::Png => {
+                let image = rasterize(ctx, rect, shapes);
+                Self {
+                    format,
+                    size: image.size,
+                    bytes: encode_png(&image),
+                }
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// SVG
+
+fn write_svg(svg: &mut String, rect: Rect, shapes: &[ClippedShape]) -> fmt::Result {
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        rect.width(),
+        rect.height(),
+        rect.min.x,
+        rect.min.y,
+        rect.width(),
+        rect.height()
+    )?;
+
+    // Consecutive shapes with the same clip rectangle share a group.
+    let mut clip_rects: Vec<Rect> = Vec::new();
+    let mut current_clip_rect = None;
+    for ClippedShape(clip_rect, shape) in shapes {
+        if current_clip_rect != Some(*clip_rect) {
+            if current_clip_rect.is_some() {
+                writeln!(svg, "</g>")?;
+            }
+            let index = match clip_rects.iter().position(|r| r == clip_rect) {
+                Some(index) => index,
+                None => {
+                    writeln!(
+                        svg,
+                        r#"<clipPath id="clip{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+                        clip_rects.len(),
+                        clip_rect.min.x,
+                        clip_rect.min.y,
+                        clip_rect.width(),
+                        clip_rect.height()
+                    )?;
+                    clip_rects.push(*clip_rect);
+                    clip_rects.len() - 1
+                }
+            };
+            writeln!(svg, r#"<g clip-path="url(#clip{})">"#, index)?;
+            current_clip_rect = Some(*clip_rect);
+        }
+        write_shape(svg, shape)?;
+    }
+    if current_clip_rect.is_some() {
+        writeln!(svg, "</g>")?;
+    }
+
+    writeln!(svg, "</svg>")
+}
+
+fn write_shape(svg: &mut String, shape: &Shape) -> fmt::Result {
+    match shape {
+        Shape::Noop | Shape::Callback(_) => Ok(()),
+        Shape::Vec(shapes) => shapes.iter().try_for_each(|shape| write_shape(svg, shape)),
+        Shape::Circle(CircleShape {
+            center,
+            radius,
+            fill,
+            stroke,
+        }) => writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{}"{}{}/>"#,
+            center.x,
+            center.y,
+            radius,
+            Fill(*fill),
+            SvgStroke(*stroke)
+        ),
+        Shape::LineSegment { points, stroke } => writeln!(
+            svg,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+            points[0].x,
+            points[0].y,
+            points[1].x,
+            points[1].y,
+            SvgStroke(*stroke)
+        ),
+        Shape::Path(PathShape {
+            points,
+            closed,
+            fill,
+            stroke,
+        }) => writeln!(
+            svg,
+            r#"<{} points="{}"{}{}/>"#,
+            if *closed { "polygon" } else { "polyline" },
+            Points(points),
+            Fill(*fill),
+            SvgStroke(*stroke)
+        ),
+        Shape::Rect(RectShape {
+            rect,
+            rounding,
+            fill,
+            stroke,
+        }) => {
+            let radius = (rounding.nw + rounding.ne + rounding.sw + rounding.se) / 4.0;
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}"{}{}/>"#,
+                rect.min.x,
+                rect.min.y,
+                rect.width(),
+                rect.height(),
+                radius.min(rect.width() / 2.0).min(rect.height() / 2.0),
+                Fill(*fill),
+                SvgStroke(*stroke)
+            )
+        }
+        Shape::Text(text_shape) => write_text(svg, text_shape),
+        Shape::Mesh(mesh) => {
+            // Images aren't available to us, only the untextured meshes of the plot.
+            if mesh.texture_id != TextureId::default() {
+                return Ok(());
+            }
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+                let channel = |i: usize| {
+                    let sum = a.color[i] as u32 + b.color[i] as u32 + c.color[i] as u32;
+                    (sum / 3) as u8
+                };
+                let color = Color32::from_rgba_premultiplied(
+                    channel(0),
+                    channel(1),
+                    channel(2),
+                    channel(3),
+                );
+                // Without anti-aliasing, neighboring triangles don't show seams.
+                writeln!(
+                    svg,
+                    r#"<polygon points="{}"{} shape-rendering="crispEdges"/>"#,
+                    Points(&[a.pos, b.pos, c.pos]),
+                    Fill(color)
+                )?;
+            }
+            Ok(())
+        }
+        Shape::QuadraticBezier(QuadraticBezierShape {
+            points: [p0, p1, p2],
+            closed,
+            fill,
+            stroke,
+        }) => writeln!(
+            svg,
+            r#"<path d="M {} {} Q {} {} {} {}{}"{}{}/>"#,
+            p0.x,
+            p0.y,
+            p1.x,
+            p1.y,
+            p2.x,
+            p2.y,
+            if *closed { " Z" } else { "" },
+            Fill(*fill),
+            SvgStroke(*stroke)
+        ),
+        Shape::CubicBezier(CubicBezierShape {
+            points: [p0, p1, p2, p3],
+            closed,
+            fill,
+            stroke,
+        }) => writeln!(
+            svg,
+            r#"<path d="M {} {} C {} {} {} {} {} {}{}"{}{}/>"#,
+            p0.x,
+            p0.y,
+            p1.x,
+            p1.y,
+            p2.x,
+            p2.y,
+            p3.x,
+            p3.y,
+            if *closed { " Z" } else { "" },
+            Fill(*fill),
+            SvgStroke(*stroke)
+        ),
+    }
+}
+
+/// Each run of characters with the same format becomes a `<text>` element,
+/// with the position of every character given explicitly so that the layout matches the plot.
+fn write_text(svg: &mut String, text_shape: &TextShape) -> fmt::Result {
+    let TextShape {
+        pos,
+        galley,
+        underline,
+        override_text_color,
+        angle,
+    } = text_shape;
+
+    write!(svg, r#"<g transform="translate({} {})"#, pos.x, pos.y)?;
+    if *angle != 0.0 {
+        write!(svg, " rotate({})", angle.to_degrees())?;
+    }
+    writeln!(svg, r#"">"#)?;
+
+    for row in &galley.rows {
+        let mut glyphs = &row.glyphs[..];
+        while let Some(first) = glyphs.first() {
+            let run_len = glyphs
+                .iter()
+                .position(|glyph| glyph.section_index != first.section_index)
+                .unwrap_or(glyphs.len());
+            let (run, rest) = glyphs.split_at(run_len);
+            glyphs = rest;
+
+            let format = &galley.job.sections[first.section_index as usize].format;
+            let font_rect = run
+                .iter()
+                .fold(Rect::NOTHING, |rect, glyph| rect.union(glyph.font_rect()));
+
+            if format.background != Color32::TRANSPARENT {
+                writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+                    font_rect.min.x,
+                    font_rect.min.y,
+                    font_rect.width(),
+                    font_rect.height(),
+                    Fill(format.background)
+                )?;
+            }
+
+            let font_family = match &format.font_id.font_type {
+                FontType::Proportional => "sans-serif".to_owned(),
+                FontType::Monospace => "monospace".to_owned(),
+                FontType::Name(name) => format!("{}, sans-serif", Escaped(name)),
+            };
+            write!(
+                svg,
+                r#"<text x="{}" y="{}" dominant-baseline="central" font-family="{}" font-size="{}" font-weight="{}""#,
+                Numbers(run.iter().map(|glyph| glyph.pos.x)),
+                font_rect.center().y,
+                font_family,
+                format.font_id.size,
+                format.font_id.weight.0
+            )?;
+            if format.font_id.style == FontStyle::Italic {
+                write!(svg, r#" font-style="italic""#)?;
+            }
+            let text: String = run.iter().map(|glyph| glyph.chr).collect();
+            writeln!(
+                svg,
+                r#"{} xml:space="preserve">{}</text>"#,
+                Fill(override_text_color.unwrap_or(format.color)),
+                Escaped(&text)
+            )?;
+
+            for (stroke, y) in [
+                (format.underline, font_rect.bottom()),
+                (format.strikethrough, font_rect.center().y),
+            ] {
+                if stroke.width > 0.0 && stroke.color != Color32::TRANSPARENT {
+                    writeln!(
+                        svg,
+                        r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+                        font_rect.min.x,
+                        y,
+                        font_rect.max.x,
+                        y,
+                        SvgStroke(stroke)
+                    )?;
+                }
+            }
+        }
+
+        if underline.width > 0.0 && underline.color != Color32::TRANSPARENT {
+            writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+                row.rect.min.x,
+                row.rect.max.y,
+                row.rect.max.x,
+                row.rect.max.y,
+                SvgStroke(*underline)
+            )?;
+        }
+    }
+
+    writeln!(svg, "</g>")
+}
+
+/// The `fill` attributes of an SVG element.
+struct Fill(Color32);
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b, a] = self.0.to_srgba_unmultiplied();
+        if a == 0 {
+            return write!(f, r#" fill="none""#);
+        }
+        write!(f, r#" fill="rgb({},{},{})""#, r, g, b)?;
+        if a < 255 {
+            write!(f, r#" fill-opacity="{:.3}""#, a as f32 / 255.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `stroke` attributes of an SVG element.
+struct SvgStroke(Stroke);
+
+impl fmt::Display for SvgStroke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Stroke { width, color } = self.0;
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        if width <= 0.0 || a == 0 {
+            return Ok(());
+        }
+        write!(
+            f,
+            r#" stroke="rgb({},{},{})" stroke-width="{}""#,
+            r, g, b, width
+        )?;
+        if a < 255 {
+            write!(f, r#" stroke-opacity="{:.3}""#, a as f32 / 255.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// A list of points, as in the `points` attribute of a `<polyline>`.
+struct Points<'a>(&'a [Pos2]);
+
+impl fmt::Display for Points<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, point) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            write!(f, "{},{}", point.x, point.y)?;
+        }
+        Ok(())
+    }
+}
+
+/// A list of numbers separated by spaces.
+struct Numbers<I>(I);
+
+impl<I: Iterator<Item = f32> + Clone> fmt::Display for Numbers<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, number) in self.0.clone().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            write!(f, "{}", number)?;
+        }
+        Ok(())
+    }
+}
+
+/// Text with the characters that are special in XML replaced by entities.
+struct Escaped<'a>(&'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chr in self.0.chars() {
+            match chr {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                _ => f.write_char(chr)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// PNG
+
+/// Tessellate the shapes like a painter would, and fill the triangles on the CPU.
+///
+/// Only the font texture is available, so meshes with other textures (images) are left out.
+#[cfg(feature = "png")]
+fn rasterize(ctx: &Context, rect: Rect, shapes: Vec<ClippedShape>) -> ColorImage {
+    let pixels_per_point = ctx.pixels_per_point();
+    let texture_atlas = ctx.fonts().texture_atlas();
+    let (font_tex_size, prepared_discs, font_image) = {
+        let atlas = texture_atlas.lock();
+        (atlas.size(), atlas.prepared_discs(), atlas.image().clone())
+    };
+    let primitives = tessellator::tessellate_shapes(
+        pixels_per_point,
+        *ctx.tessellation_options(),
+        font_tex_size,
+        prepared_discs,
+        shapes,
+    );
+
+    let size = [
+        (rect.width() * pixels_per_point).round() as usize,
+        (rect.height() * pixels_per_point).round() as usize,
+    ];
+    let mut image = ColorImage::new(size, Color32::TRANSPARENT);
+    let to_pixels = |pos: Pos2| ((pos - rect.min) * pixels_per_point).to_pos2();
+
+    for ClippedPrimitive {
+        clip_rect,
+        primitive,
+    } in primitives
+    {
+        let mesh = match primitive {
+            Primitive::Mesh(mesh) if mesh.texture_id == TextureId::default() => mesh,
+            _ => continue,
+        };
+        let clip_rect = Rect::from_min_max(to_pixels(clip_rect.min), to_pixels(clip_rect.max));
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = [0, 1, 2].map(|i| {
+                let vertex = mesh.vertices[triangle[i] as usize];
+                Vertex {
+                    pos: to_pixels(vertex.pos),
+                    ..vertex
+                }
+            });
+            fill_triangle(&mut image, clip_rect, vertices, &font_image);
+        }
+    }
+
+    image
+}
+
+/// Blend the pixels whose centers are inside the triangle.
+#[cfg(feature = "png")]
+fn fill_triangle(
+    image: &mut ColorImage,
+    clip_rect: Rect,
+    mut vertices: [Vertex; 3],
+    font_image: &FontImage,
+) {
+    // Twice the signed area of the triangle `a, b, p`, which is positive if it turns clockwise on screen.
+    let edge = |a: Pos2, b: Pos2, p: Pos2| (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+    let mut area = edge(vertices[0].pos, vertices[1].pos, vertices[2].pos);
+    if area < 0.0 {
+        vertices.swap(1, 2);
+        area = -area;
+    }
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+    let [a, b, c] = vertices;
+
+    let bounds = Rect::from_min_max(a.pos.min(b.pos).min(c.pos), a.pos.max(b.pos).max(c.pos))
+        .intersect(clip_rect);
+    if !bounds.is_positive() {
+        return;
+    }
+    let [width, height] = image.size;
+    let x_range = bounds.min.x.floor().max(0.0) as usize..(bounds.max.x.ceil() as usize).min(width);
+    let y_range =
+        bounds.min.y.floor().max(0.0) as usize..(bounds.max.y.ceil() as usize).min(height);
+
+    // A pixel on the edge between two triangles only belongs to one of them,
+    // which goes along the edge in the other direction:
+    let owns = |from: Pos2, to: Pos2, weight: f32| {
+        weight > 0.0 || (weight == 0.0 && (to.y < from.y || (to.y == from.y && to.x > from.x)))
+    };
+
+    // How the texture coordinates change from one pixel to the next:
+    let gradient = |from: Pos2, to: Pos2| vec2(from.y - to.y, to.x - from.x) / area;
+    let gradients = [
+        gradient(b.pos, c.pos),
+        gradient(c.pos, a.pos),
+        gradient(a.pos, b.pos),
+    ];
+    let uv_dx = gradients[0].x * a.uv.to_vec2()
+        + gradients[1].x * b.uv.to_vec2()
+        + gradients[2].x * c.uv.to_vec2();
+    let uv_dy = gradients[0].y * a.uv.to_vec2()
+        + gradients[1].y * b.uv.to_vec2()
+        + gradients[2].y * c.uv.to_vec2();
+
+    for y in y_range {
+        for x in x_range.clone() {
+            let p = pos2(x as f32 + 0.5, y as f32 + 0.5);
+            if !clip_rect.contains(p) {
+                continue;
+            }
+            let weights = [
+                edge(b.pos, c.pos, p),
+                edge(c.pos, a.pos, p),
+                edge(a.pos, b.pos, p),
+            ];
+            if !(owns(b.pos, c.pos, weights[0])
+                && owns(c.pos, a.pos, weights[1])
+                && owns(a.pos, b.pos, weights[2]))
+            {
+                continue;
+            }
+            let [wa, wb, wc] = weights.map(|weight| weight / area);
+
+            let uv = (wa * a.uv.to_vec2() + wb * b.uv.to_vec2() + wc * c.uv.to_vec2()).to_pos2();
+            let texel = sample_font_image(font_image, uv, uv_dx, uv_dy);
+            let mut src = [0.0; 4];
+            for (i, channel) in src.iter_mut().enumerate() {
+                let color =
+                    wa * a.color[i] as f32 + wb * b.color[i] as f32 + wc * c.color[i] as f32;
+                *channel = color * texel[i] as f32 / 255.0;
+            }
+
+            let dst = &mut image.pixels[y * width + x];
+            let keep = 1.0 - src[3] / 255.0;
+            *dst = Color32::from_rgba_premultiplied(
+                (src[0] + dst[0] as f32 * keep).round() as u8,
+                (src[1] + dst[1] as f32 * keep).round() as u8,
+                (src[2] + dst[2] as f32 * keep).round() as u8,
+                (src[3] + dst[3] as f32 * keep).round() as u8,
+            );
+        }
+    }
+}
+
+/// The premultiplied color of the font texture at `uv`, like the painters sample it.
+#[cfg(feature = "png")]
+fn sample_font_image(image: &FontImage, uv: Pos2, uv_dx: Vec2, uv_dy: Vec2) -> Color32 {
+    let [width, height] = image.size;
+    let index = |uv: Pos2| {
+        let x = ((uv.x * width as f32) as usize).min(width - 1);
+        let y = ((uv.y * height as f32) as usize).min(height - 1);
+        y * width + x
+    };
+    let i = index(uv);
+    if let Some(&color) = image.colors.get(i) {
+        if color != Color32::TRANSPARENT {
+            return color;
+        }
+    }
+    let coverage = if image.distance_field {
+        // See the distance field shader of `egui_glow`:
+        let distance = image.pixels[i];
+        let change = (image.pixels[index(uv + uv_dx)] - distance).abs()
+            + (image.pixels[index(uv + uv_dy)] - distance).abs();
+        ((distance - 0.5) / change.max(0.0001) + 0.5).clamp(0.0, 1.0)
+    } else {
+        image.pixels[i].powf(1.0 / 2.2)
+    };
+    let a = (coverage * 255.0).round() as u8;
+    Color32::from_rgba_premultiplied(a, a, a, a)
+}
+
+#[cfg(feature = "png")]
+fn encode_png(image: &ColorImage) -> Vec<u8> {
+    let (size, data): ([usize; 2], Vec<u8>) = if image.pixels.is_empty() {
+        // A PNG image can't be empty, so it is a transparent pixel:
+        ([1, 1], vec![0; 4])
+    } else {
+        let data = image
+            .pixels
+            .iter()
+            .flat_map(|color| color.to_srgba_unmultiplied())
+            .collect();
+        (image.size, data)
+    };
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, size[0] as u32, size[1] as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .expect("Encoding a PNG in memory can't fail");
+    png
+}
+
+#[cfg(test)]
+#[test]
+fn test_plot_export() {
+    use super::{Line, Plot, Values};
+
+    let ctx = Context::default();
+    let raw_input = RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(200.0, 100.0))),
+        ..Default::default()
+    };
+    let mut exports = Vec::new();
+    let formats = [
+        Some(ExportFormat::Svg),
+        #[cfg(feature = "png")]
+        Some(ExportFormat::Png),
+        None,
+    ];
+    for format in formats {
+        let _ = ctx.run(raw_input.clone(), |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                Plot::new("plot").show(ui, |plot_ui| {
+                    plot_ui.line(Line::new(Values::from_ys_f32(&[0.0, 1.0, 0.5])).name("a & b"));
+                    exports.extend(plot_ui.take_export());
+                    if let Some(format) = format {
+                        plot_ui.export(format);
+                    }
+                });
+            });
+        });
+    }
+
+    assert_eq!(exports.len(), formats.len() - 1);
+    let svg = std::str::from_utf8(&exports[0].bytes).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("<polyline "));
+    assert_eq!(svg.matches("<g").count(), svg.matches("</g>").count());
+
+    #[cfg(feature = "png")]
+    {
+        let png = &exports[1];
+        assert!(png.size[0] > 0 && png.size[1] > 0);
+        let info = png::Decoder::new(png.bytes.as_slice()).read_info().unwrap();
+        assert_eq!(
+            [info.info().width as usize, info.info().height as usize],
+            png.size
+        );
+    }
+}
+
+#[cfg(all(test, feature = "png"))]
+#[test]
+fn test_png_round_trip() {
+    // Flat areas, repeated rows and noise, with some transparency:
+    let [width, height] = [37, 23];
+    let pixels = (0..width * height)
+        .map(|i| {
+            let (x, y) = (i % width, i / width);
+            match y % 3 {
+                0 => Color32::from_rgb(10, 20, 30),
+                1 => Color32::from_rgba_unmultiplied((x * 7) as u8, y as u8, 200, 128),
+                _ => {
+                    let noise = (i as u32).wrapping_mul(2_654_435_761) >> 24;
+                    Color32::from_rgba_unmultiplied(noise as u8, 255 - noise as u8, 0, 255)
+                }
+            }
+        })
+        .collect();
+    let image = ColorImage {
+        size: [width, height],
+        pixels,
+    };
+
+    let png = encode_png(&image);
+    let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+    let mut decoded = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut decoded).unwrap();
+    assert_eq!((info.width, info.height), (width as u32, height as u32));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+
+    let expected: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|color| color.to_srgba_unmultiplied())
+        .collect();
+    assert_eq!(decoded, expected);
+}
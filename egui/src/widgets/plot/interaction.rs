@@ -0,0 +1,348 @@
+//! Dragging plot items, and selecting values with a brush.
+
+use epaint::util::FloatOrd;
+
+use crate::*;
+
+use super::items::PlotItem;
+use super::{PlotBounds, ScreenTransform, Value, HOVER_RADIUS};
+
+/// What the user does with a draggable item,
+/// see [`HLine::draggable`](super::HLine::draggable), [`VLine::draggable`](super::VLine::draggable)
+/// and [`Points::draggable`](super::Points::draggable).
+///
+/// The plot doesn't move the values itself, that is up to you:
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{HLine, Plot};
+/// let mut threshold = 0.5; // Stored in your app.
+/// Plot::new("my_plot").show(ui, |plot_ui| {
+///     plot_ui
+///         .hline(HLine::new(threshold).draggable(true))
+///         .on_drag(|_, target| threshold = target.y);
+/// });
+/// # });
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlotItemResponse {
+    /// The index of the value that is close enough to the pointer to be grabbed, as of the last frame.
+    /// Lines only have the index `0`.
+    pub hovered: Option<usize>,
+
+    /// The index of the value being dragged.
+    pub dragged: Option<usize>,
+
+    /// Where the dragged value is dragged to, in plot coordinates.
+    pub drag_target: Option<Value>,
+
+    /// The drag ended this frame. [`Self::dragged`] and [`Self::drag_target`] are still set.
+    pub drag_released: bool,
+}
+
+impl PlotItemResponse {
+    /// Call `move_value` with the index of the dragged value and where it is dragged to,
+    /// if a value is being dragged.
+    pub fn on_drag(self, move_value: impl FnOnce(usize, Value)) -> Self {
+        if let (Some(index), Some(target)) = (self.dragged, self.drag_target) {
+            move_value(index, target);
+        }
+        self
+    }
+}
+
+/// A value of a draggable item close to the pointer, found while the items are added.
+pub(super) struct DragCandidate {
+    pub item: usize,
+    pub index: usize,
+    pub dist_sq: f32,
+    /// From the pointer to the value, in plot coordinates.
+    pub offset: [f64; 2],
+}
+
+/// The draggable items are told apart by the order they are added in.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct ItemDrag {
+    /// The item and the index of the value closest to the pointer, if it is close enough to grab.
+    hovered: Option<(usize, usize)>,
+
+    /// The item and the index of the value being dragged, and the offset from the pointer to the value.
+    dragged: Option<(usize, usize, [f64; 2])>,
+}
+
+impl ItemDrag {
+    pub fn is_dragging(&self) -> bool {
+        self.dragged.is_some()
+    }
+
+    pub fn cursor_icon(&self) -> Option<CursorIcon> {
+        if self.dragged.is_some() {
+            Some(CursorIcon::Grabbing)
+        } else if self.hovered.is_some() {
+            Some(CursorIcon::Grab)
+        } else {
+            None
+        }
+    }
+
+    /// The response for the `item`th draggable item, with the pointer at `pointer`.
+    pub fn response(
+        &self,
+        item: usize,
+        pointer: Option<Value>,
+        drag_released: bool,
+    ) -> PlotItemResponse {
+        let mut response = PlotItemResponse {
+            hovered: self
+                .hovered
+                .filter(|(hovered, _)| *hovered == item)
+                .map(|(_, index)| index),
+            ..Default::default()
+        };
+        if let Some((_, index, offset)) = self.dragged.filter(|(dragged, ..)| *dragged == item) {
+            response.dragged = Some(index);
+            response.drag_target =
+                pointer.map(|pointer| Value::new(pointer.x + offset[0], pointer.y + offset[1]));
+            response.drag_released = drag_released;
+        }
+        response
+    }
+
+    /// Hover the closest of the `candidates`, grab it if a drag starts, and let go when the drag ends.
+    pub fn update(&mut self, candidates: &[DragCandidate], response: &Response) {
+        let closest = candidates
+            .iter()
+            .filter(|candidate| candidate.dist_sq <= HOVER_RADIUS.powi(2))
+            .min_by_key(|candidate| candidate.dist_sq.ord());
+        self.hovered = closest.map(|candidate| (candidate.item, candidate.index));
+        if response.drag_started() && response.dragged_by(PointerButton::Primary) {
+            self.dragged =
+                closest.map(|candidate| (candidate.item, candidate.index, candidate.offset));
+        }
+        if !response.dragged() {
+            self.dragged = None;
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// What [`Plot::brush`](super::Plot::brush) selects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrushMode {
+    /// The values inside a rectangle.
+    Rect,
+
+    /// The values inside a range of x, at any y.
+    XRange,
+}
+
+/// The values selected with [`Plot::brush`](super::Plot::brush), see [`PlotUi::selection`](super::PlotUi::selection).
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlotSelection {
+    /// The selected area. With [`BrushMode::XRange`] the y range is infinite.
+    pub bounds: PlotBounds,
+
+    /// The name of each item with selected values, and the indices of those values.
+    ///
+    /// The indices count the values as they were given to the item, the values generated in the visible range
+    /// for [`Values::from_explicit_callback`](super::Values::from_explicit_callback),
+    /// or the bars and boxes of a [`BarChart`](super::BarChart) or [`BoxPlot`](super::BoxPlot).
+    pub items: Vec<(String, Vec<usize>)>,
+}
+
+impl PlotSelection {
+    /// No values are selected.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The indices of the selected values of the items with this name.
+    pub fn indices<'a>(&'a self, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.items
+            .iter()
+            .filter(move |(item_name, _)| item_name == name)
+            .flat_map(|(_, indices)| indices.iter().copied())
+    }
+}
+
+/// The brush being dragged, or the last one.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Debug, Default)]
+pub(super) struct Brush {
+    /// Where the drag started, in plot coordinates.
+    start: Option<[f64; 2]>,
+    bounds: Option<PlotBounds>,
+    pub selection: Option<PlotSelection>,
+}
+
+impl Brush {
+    /// Drag to select, click to clear.
+    pub fn update(&mut self, mode: BrushMode, response: &Response, transform: &ScreenTransform) {
+        if response.drag_started() && response.dragged_by(PointerButton::Primary) {
+            let press_origin = response.ctx.input().pointer.press_origin();
+            self.start = press_origin.map(|pos| {
+                let value = transform.value_from_position(pos);
+                [value.x, value.y]
+            });
+        }
+        if let (Some(start), Some(pos)) = (self.start, response.interact_pointer_pos()) {
+            let end = transform.value_from_position(pos);
+            let mut bounds = PlotBounds::NOTHING;
+            bounds.extend_with(&Value::new(start[0], start[1]));
+            bounds.extend_with(&end);
+            if mode == BrushMode::XRange {
+                bounds.min[1] = -f64::INFINITY;
+                bounds.max[1] = f64::INFINITY;
+            }
+            self.bounds = Some(bounds);
+        }
+        if !response.dragged() {
+            self.start = None;
+        }
+        if response.clicked() {
+            self.bounds = None;
+            self.selection = None;
+        }
+    }
+
+    /// Find the values of the `items` inside the brush.
+    pub fn select(
+        &mut self,
+        items: &[Box<dyn PlotItem>],
+        transform: &ScreenTransform,
+        secondary_transform: Option<&ScreenTransform>,
+    ) {
+        let bounds = match self.bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let items = items
+            .iter()
+            .filter_map(|item| {
+                let indices = match secondary_transform {
+                    Some(secondary) if item.secondary_y_axis() => {
+                        let mut secondary_bounds = PlotBounds::NOTHING;
+                        for corner in [bounds.min(), bounds.max()] {
+                            let pos =
+                                transform.position_from_value(&Value::new(corner[0], corner[1]));
+                            secondary_bounds.extend_with(&secondary.value_from_position(pos));
+                        }
+                        item.select(&secondary_bounds)
+                    }
+                    _ => item.select(&bounds),
+                };
+                (!indices.is_empty()).then(|| (item.name().to_owned(), indices))
+            })
+            .collect();
+        self.selection = Some(PlotSelection { bounds, items });
+    }
+
+    pub fn shape(&self, ui: &Ui, transform: &ScreenTransform) -> Option<Shape> {
+        let PlotBounds { min, max } = self.bounds?;
+        let rect = transform
+            .rect_from_values(&Value::new(min[0], min[1]), &Value::new(max[0], max[1]))
+            .intersect(*transform.frame());
+        let color = ui.visuals().selection.bg_fill;
+        Some(Shape::Vec(vec![
+            Shape::rect_filled(rect, 0.0, color.linear_multiply(0.2)),
+            Shape::rect_stroke(rect, 0.0, (1.0, color)),
+        ]))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_drag_and_brush() {
+    use super::{HLine, Line, Plot, Points, Values};
+
+    let ctx = Context::default();
+    let run = |events: Vec<Event>, threshold: &mut f64| {
+        let raw_input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(200.0, 200.0))),
+            events,
+            ..Default::default()
+        };
+        let mut result = (Pos2::ZERO, PlotItemResponse::default(), None);
+        let _ = ctx.run(raw_input, |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                Plot::new("plot")
+                    .include_y(-1.0)
+                    .include_y(1.0)
+                    .brush(BrushMode::XRange)
+                    .show(ui, |plot_ui| {
+                        let values = (0..10).map(|i| Value::new(i, 0.5)).collect();
+                        plot_ui.points(Points::new(Values::from_values(values)).name("points"));
+                        // Many more values than the plot is wide, so they are decimated:
+                        let sorted: Vec<Value> = (0..10_000)
+                            .map(|i| Value::new(i as f64 * 0.001, 0.5))
+                            .collect();
+                        plot_ui.line(Line::new(Values::from_sorted(sorted)).name("sorted"));
+                        let generated = Values::from_explicit_callback(|_| 0.5, 0.0..10.0, 50);
+                        plot_ui.line(Line::new(generated).name("generated"));
+                        let response = plot_ui
+                            .hline(HLine::new(*threshold).draggable(true))
+                            .on_drag(|_, target| *threshold = target.y);
+                        let pos = plot_ui.screen_from_plot(Value::new(4.5, *threshold));
+                        result = (pos, response, plot_ui.selection().cloned());
+                    });
+            });
+        });
+        result
+    };
+    let button = |pos, pressed| Event::PointerButton {
+        pos,
+        button: PointerButton::Primary,
+        pressed,
+        modifiers: Default::default(),
+    };
+
+    let mut threshold = 0.0;
+    run(vec![], &mut threshold);
+    let (line_pos, ..) = run(vec![], &mut threshold);
+    run(vec![Event::PointerMoved(line_pos)], &mut threshold);
+    let (_, response, _) = run(vec![button(line_pos, true)], &mut threshold);
+    assert_eq!(response.hovered, Some(0));
+    for dy in [-10.0, -20.0, -30.0] {
+        run(
+            vec![Event::PointerMoved(line_pos + vec2(0.0, dy))],
+            &mut threshold,
+        );
+    }
+    let (_, response, _) = run(
+        vec![button(line_pos + vec2(0.0, -30.0), false)],
+        &mut threshold,
+    );
+    assert!(response.drag_released);
+    assert!(threshold > 0.0, "The line was dragged up");
+
+    // Brush the middle of the points, away from the line:
+    let (middle, ..) = run(vec![Event::PointerMoved(Pos2::ZERO)], &mut threshold);
+    let start = middle + vec2(-30.0, 60.0);
+    run(vec![button(start, true)], &mut threshold);
+    for dx in [20.0, 40.0, 60.0] {
+        run(
+            vec![Event::PointerMoved(start + vec2(dx, 0.0))],
+            &mut threshold,
+        );
+    }
+    run(vec![button(start + vec2(60.0, 0.0), false)], &mut threshold);
+    let (.., selection) = run(vec![], &mut threshold);
+    let selection = selection.expect("Values were brushed");
+    let indices: Vec<usize> = selection.indices("points").collect();
+    assert!(!indices.is_empty() && indices.len() < 10);
+    assert!(indices.windows(2).all(|w| w[1] == w[0] + 1));
+
+    // All the sorted values in the brushed range count, not just the decimated ones:
+    let indices: Vec<usize> = selection.indices("sorted").collect();
+    assert!(indices.len() > 1_000);
+    assert!(indices.windows(2).all(|w| w[1] == w[0] + 1));
+    let x_range = selection.bounds.range_x();
+    assert!(indices
+        .iter()
+        .all(|&index| x_range.contains(&(index as f64 * 0.001))));
+
+    // The values of a function are generated before they are brushed:
+    assert!(selection.indices("generated").next().is_some());
+}
@@ -0,0 +1,398 @@
+use epaint::util::FloatOrd;
+
+use crate::emath::NumExt;
+use crate::epaint::{Color32, Mesh, Rgba, Shape, Stroke};
+use crate::{Pos2, Ui};
+
+use super::values::{ClosestElem, PlotGeometry};
+use super::{rulers_at_value, rulers_color, LabelFormatter, PlotConfig, PlotItem, Value, Values};
+use crate::plot::{PlotBounds, ScreenTransform};
+
+/// Where the lower edge of an [`AreaChart`] is.
+enum AreaBase {
+    /// A horizontal line at this y.
+    Constant(f64),
+
+    /// Another series, see [`AreaChart::between`].
+    Series(Values),
+
+    /// The top of the areas below, one for each value, see [`AreaChart::stack_on`].
+    Stacked(Vec<f64>),
+}
+
+/// The filled area below a series of values, between two series, or stacked on other areas.
+///
+/// The values are expected to be sorted by x.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{AreaChart, Plot, Values};
+/// let apples = AreaChart::new(Values::from_ys_f32(&[1.0, 2.0, 1.5])).name("Apples");
+/// let pears = AreaChart::new(Values::from_ys_f32(&[0.5, 1.0, 2.0]))
+///     .name("Pears")
+///     .stack_on(&[&apples]);
+/// Plot::new("fruit").show(ui, |plot_ui| {
+///     plot_ui.area_chart(apples);
+///     plot_ui.area_chart(pears);
+/// });
+/// # });
+/// ```
+pub struct AreaChart {
+    pub(crate) series: Values,
+    base: AreaBase,
+    /// The upper edge, which is the series lifted by the base when stacked.
+    upper: Vec<Value>,
+    pub(crate) stroke: Stroke,
+    fill_alpha: f32,
+    name: String,
+    highlight: bool,
+}
+
+impl AreaChart {
+    /// The area between the values and `y = 0`.
+    pub fn new(series: Values) -> Self {
+        let mut area = Self {
+            series,
+            base: AreaBase::Constant(0.0),
+            upper: Vec::new(),
+            stroke: Stroke::new(1.0, Color32::TRANSPARENT),
+            fill_alpha: 0.4,
+            name: Default::default(),
+            highlight: false,
+        };
+        area.upper = area.upper_edge();
+        area
+    }
+
+    /// The area between two series, e.g. a confidence band.
+    ///
+    /// Only the x range both series cover is filled. Where `lower` is above `upper`, the area is filled all the same.
+    pub fn between(upper: Values, lower: Values) -> Self {
+        let mut area = Self::new(upper);
+        area.base = AreaBase::Series(lower);
+        area
+    }
+
+    /// Fill down to a horizontal line at `y_reference` instead of `y = 0`.
+    pub fn base(mut self, y_reference: impl Into<f64>) -> Self {
+        self.base = AreaBase::Constant(y_reference.into());
+        self.upper = self.upper_edge();
+        self
+    }
+
+    /// Stacks the area on top of other areas.
+    /// Positive values are stacked on top of other positive values.
+    /// Negative values are stacked below other negative values.
+    ///
+    /// The other areas are interpolated between their values, so they need not have the same x values.
+    /// Only values given as they are, e.g. with [`Values::from_values`], can be stacked.
+    /// The hover label shows the value of this area, not the top of the stack.
+    pub fn stack_on(mut self, others: &[&AreaChart]) -> Self {
+        let edges: Vec<_> = others
+            .iter()
+            .map(|other| (other.upper_edge(), other.lower_edge()))
+            .collect();
+        let base = self
+            .series
+            .values
+            .iter()
+            .map(|value| {
+                let ys = edges.iter().flat_map(|(upper, lower)| {
+                    [interpolate(upper, value.x), interpolate(lower, value.x)]
+                        .into_iter()
+                        .flatten()
+                });
+                if value.y.is_sign_positive() {
+                    ys.max_by_key(|y| y.ord()).unwrap_or(0.0).at_least(0.0)
+                } else {
+                    ys.min_by_key(|y| y.ord()).unwrap_or(0.0).at_most(0.0)
+                }
+            })
+            .collect();
+        self.base = AreaBase::Stacked(base);
+        self.upper = self.upper_edge();
+        self
+    }
+
+    /// Highlight this area in the plot by making it more opaque.
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Stroke of the upper edge, and of the lower edge of [`Self::between`].
+    pub fn stroke(mut self, stroke: impl Into<Stroke>) -> Self {
+        self.stroke = stroke.into();
+        self
+    }
+
+    /// Stroke width. Zero means the edges aren't drawn.
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.stroke.width = width.into();
+        self
+    }
+
+    /// Color of the edges and the fill. Default is `Color32::TRANSPARENT` which means a color will be auto-assigned.
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.stroke.color = color.into();
+        self
+    }
+
+    /// Opacity of the fill, from 0 to 1. Default is 0.4.
+    pub fn fill_alpha(mut self, alpha: impl Into<f32>) -> Self {
+        self.fill_alpha = alpha.into();
+        self
+    }
+
+    /// Name of this area.
+    ///
+    /// This name will show up in the plot legend, if legends are turned on.
+    ///
+    /// Multiple plot items may share the same name, in which case they will also share an entry in
+    /// the legend.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn upper_edge(&self) -> Vec<Value> {
+        match &self.base {
+            AreaBase::Stacked(base) => self
+                .series
+                .values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    Value::new(value.x, value.y + base.get(index).copied().unwrap_or(0.0))
+                })
+                .collect(),
+            AreaBase::Constant(_) | AreaBase::Series(_) => self.series.values.clone(),
+        }
+    }
+
+    fn lower_edge(&self) -> Vec<Value> {
+        match &self.base {
+            AreaBase::Constant(y) => {
+                match (self.series.values.first(), self.series.values.last()) {
+                    (Some(first), Some(last)) => {
+                        vec![Value::new(first.x, *y), Value::new(last.x, *y)]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            AreaBase::Series(lower) => lower.values.clone(),
+            AreaBase::Stacked(base) => self
+                .series
+                .values
+                .iter()
+                .zip(base)
+                .map(|(value, y)| Value::new(value.x, *y))
+                .collect(),
+        }
+    }
+}
+
+/// The y of the edge at `x`, linearly interpolated between its values, if `x` is within them.
+fn interpolate(edge: &[Value], x: f64) -> Option<f64> {
+    let index = edge.partition_point(|value| value.x < x);
+    let next = edge.get(index)?;
+    if next.x == x {
+        Some(next.y)
+    } else {
+        let previous = edge.get(index.checked_sub(1)?)?;
+        let t = (x - previous.x) / (next.x - previous.x);
+        Some(previous.y + t * (next.y - previous.y))
+    }
+}
+
+/// Triangles between the two edges, at the x values of both, where both are defined.
+fn fill_between(
+    upper: &[Value],
+    lower: &[Value],
+    transform: &ScreenTransform,
+    color: Color32,
+) -> Mesh {
+    let mut mesh = Mesh::default();
+    let (start, end) = match (upper.first(), upper.last(), lower.first(), lower.last()) {
+        (Some(a), Some(b), Some(c), Some(d)) => (a.x.max(c.x), b.x.min(d.x)),
+        _ => return mesh,
+    };
+    let mut xs: Vec<f64> = upper
+        .iter()
+        .chain(lower)
+        .map(|value| value.x)
+        .filter(|x| (start..=end).contains(x))
+        .collect();
+    xs.sort_by_key(|x| x.ord());
+    xs.dedup();
+
+    let edges_at = |x: f64| Some((interpolate(upper, x)?, interpolate(lower, x)?));
+    let pos = |x: f64, y: f64| transform.position_from_value(&Value::new(x, y));
+    for w in xs.windows(2) {
+        let (x0, x1) = (w[0], w[1]);
+        let ((u0, l0), (u1, l1)) = match (edges_at(x0), edges_at(x1)) {
+            (Some(edges0), Some(edges1)) => (edges0, edges1),
+            _ => continue,
+        };
+        let i = mesh.vertices.len() as u32;
+        mesh.colored_vertex(pos(x0, u0), color);
+        mesh.colored_vertex(pos(x0, l0), color);
+        let (d0, d1) = (u0 - l0, u1 - l1);
+        if d0 * d1 < 0.0 {
+            // The edges cross in between:
+            let t = d0 / (d0 - d1);
+            mesh.colored_vertex(pos(x0 + t * (x1 - x0), u0 + t * (u1 - u0)), color);
+            mesh.colored_vertex(pos(x1, u1), color);
+            mesh.colored_vertex(pos(x1, l1), color);
+            mesh.add_triangle(i, i + 1, i + 2);
+            mesh.add_triangle(i + 2, i + 3, i + 4);
+        } else {
+            mesh.colored_vertex(pos(x1, u1), color);
+            mesh.colored_vertex(pos(x1, l1), color);
+            mesh.add_triangle(i, i + 1, i + 2);
+            mesh.add_triangle(i + 1, i + 2, i + 3);
+        }
+    }
+    mesh
+}
+
+impl PlotItem for AreaChart {
+    fn get_shapes(&self, _ui: &mut Ui, transform: &ScreenTransform, shapes: &mut Vec<Shape>) {
+        let mut fill_alpha = self.fill_alpha;
+        let mut stroke = self.stroke;
+        if self.highlight {
+            fill_alpha = (2.0 * fill_alpha).at_most(1.0);
+            stroke.width *= 2.0;
+        }
+        let fill_color = Rgba::from(stroke.color)
+            .to_opaque()
+            .multiply(fill_alpha)
+            .into();
+        let lower = self.lower_edge();
+        shapes.push(Shape::Mesh(fill_between(
+            &self.upper,
+            &lower,
+            transform,
+            fill_color,
+        )));
+
+        if stroke.width > 0.0 {
+            let line = |edge: &[Value]| -> Vec<Pos2> {
+                edge.iter()
+                    .map(|value| transform.position_from_value(value))
+                    .collect()
+            };
+            shapes.push(Shape::line(line(&self.upper), stroke));
+            if matches!(self.base, AreaBase::Series(_)) {
+                shapes.push(Shape::line(line(&lower), stroke));
+            }
+        }
+    }
+
+    fn initialize(&mut self, transform: &ScreenTransform) {
+        let x_range = transform.bounds().range_x();
+        self.series.generate_points(x_range.clone());
+        if let AreaBase::Series(lower) = &mut self.base {
+            lower.generate_points(x_range);
+        }
+        self.upper = self.upper_edge();
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn color(&self) -> Color32 {
+        self.stroke.color
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::Points(&self.upper)
+    }
+
+    fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        self.series.select(bounds)
+    }
+
+    fn get_bounds(&self) -> PlotBounds {
+        let mut bounds = self.series.get_bounds();
+        match &self.base {
+            AreaBase::Constant(y) => bounds.extend_with_y(*y),
+            AreaBase::Series(lower) => bounds.merge(&lower.get_bounds()),
+            AreaBase::Stacked(_) => {
+                for value in self.upper.iter().chain(&self.lower_edge()) {
+                    bounds.extend_with(value);
+                }
+            }
+        }
+        bounds
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        // The marker sits on the top of the stack, but the label shows the value of this area:
+        let pointer = plot.transform.position_from_value(&self.upper[elem.index]);
+        shapes.push(Shape::circle_filled(pointer, 3.0, rulers_color(plot.ui)));
+        let value = self.series.values[elem.index];
+        rulers_at_value(pointer, value, self.name(), plot, shapes, label_formatter);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_stacked_areas() {
+    let below = AreaChart::new(Values::from_values(vec![
+        Value::new(0.0, 1.0),
+        Value::new(2.0, 3.0),
+    ]));
+    let area = AreaChart::new(Values::from_values(vec![
+        Value::new(0.0, 1.0),
+        Value::new(1.0, 1.0),
+        Value::new(3.0, -1.0),
+    ]))
+    .stack_on(&[&below]);
+    // Interpolated between the values below, and from zero past them or when negative:
+    assert_eq!(
+        area.upper,
+        vec![
+            Value::new(0.0, 2.0),
+            Value::new(1.0, 3.0),
+            Value::new(3.0, -1.0)
+        ]
+    );
+    assert_eq!(interpolate(&below.upper, 1.5), Some(2.5));
+    assert_eq!(interpolate(&below.upper, 2.5), None);
+
+    // Crossing edges make two triangles that meet in between:
+    let transform = ScreenTransform::new(
+        crate::Rect::from_min_size(Pos2::ZERO, crate::vec2(100.0, 100.0)),
+        PlotBounds {
+            min: [0.0, -1.0],
+            max: [2.0, 1.0],
+        },
+        false,
+        false,
+    );
+    let upper = [Value::new(0.0, 1.0), Value::new(2.0, -1.0)];
+    let lower = [Value::new(0.0, -1.0), Value::new(2.0, 1.0)];
+    let mesh = fill_between(&upper, &lower, &transform, Color32::WHITE);
+    assert_eq!(mesh.indices.len(), 2 * 3);
+    assert!(mesh
+        .vertices
+        .iter()
+        .any(|vertex| vertex.pos == crate::pos2(50.0, 50.0)));
+}
@@ -0,0 +1,194 @@
+use std::ops::{Range, RangeInclusive};
+
+use super::Value;
+
+/// How a [`Line`](super::Line) or [`Points`](super::Points) with many more values than there are
+/// columns in the plot is reduced to fewer values before it is painted.
+///
+/// Only values that are sorted by x are decimated, and only those in the visible x range are read.
+///
+/// [`Line`](super::Line)s use [`Self::MinMax`] by default, [`Points`](super::Points) paint every value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decimation {
+    /// Paint every value.
+    None,
+
+    /// Keep the lowest and highest value in each column of the plot, one point wide.
+    /// Spikes and the envelope of noisy data look the same as without decimation.
+    MinMax,
+
+    /// Largest-Triangle-Three-Buckets: keep the value in each bucket that spans the largest triangle
+    /// with its neighbors, which keeps the overall shape of smooth data.
+    Lttb,
+}
+
+impl Default for Decimation {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl Decimation {
+    /// Should this many values be decimated for a plot this many columns wide?
+    pub(super) fn is_needed(self, len: usize, columns: usize) -> bool {
+        self != Self::None && len > 2 * columns
+    }
+
+    /// Reduce the values at `indices` to about two per column,
+    /// where the columns evenly divide `x_range`.
+    pub(super) fn decimate(
+        self,
+        value: &dyn Fn(usize) -> Value,
+        indices: Range<usize>,
+        x_range: &RangeInclusive<f64>,
+        columns: usize,
+    ) -> Vec<Value> {
+        match self {
+            Self::None => indices.map(value).collect(),
+            Self::MinMax => min_max(value, indices, x_range, columns),
+            Self::Lttb => largest_triangle_three_buckets(value, indices, 2 * columns),
+        }
+    }
+}
+
+fn min_max(
+    value: &dyn Fn(usize) -> Value,
+    indices: Range<usize>,
+    x_range: &RangeInclusive<f64>,
+    columns: usize,
+) -> Vec<Value> {
+    let mut decimated = Vec::with_capacity(2 * columns + 4);
+    let x_min = *x_range.start();
+    let columns_per_x = columns as f64 / (x_range.end() - x_min);
+
+    // The lowest and highest values of the current column, in the order they came in.
+    let flush = |extremes: [(usize, Value); 2], decimated: &mut Vec<Value>| {
+        let [min, max] = extremes;
+        if min.0 == max.0 {
+            decimated.push(min.1);
+        } else if min.0 < max.0 {
+            decimated.extend([min.1, max.1]);
+        } else {
+            decimated.extend([max.1, min.1]);
+        }
+    };
+
+    let mut current: Option<(i64, [(usize, Value); 2])> = None;
+    for index in indices {
+        let v = value(index);
+        // Values outside the range (at most one on either side) get a column of their own:
+        let column = ((v.x - x_min) * columns_per_x)
+            .floor()
+            .clamp(-1.0, columns as f64) as i64;
+        match &mut current {
+            Some((current_column, [min, max])) if *current_column == column => {
+                if v.y < min.1.y {
+                    *min = (index, v);
+                }
+                if v.y > max.1.y {
+                    *max = (index, v);
+                }
+            }
+            _ => {
+                if let Some((_, extremes)) = current {
+                    flush(extremes, &mut decimated);
+                }
+                current = Some((column, [(index, v); 2]));
+            }
+        }
+    }
+    if let Some((_, extremes)) = current {
+        flush(extremes, &mut decimated);
+    }
+    decimated
+}
+
+/// See Sveinn Steinarsson, "Downsampling Time Series for Visual Representation", 2013.
+fn largest_triangle_three_buckets(
+    value: &dyn Fn(usize) -> Value,
+    indices: Range<usize>,
+    threshold: usize,
+) -> Vec<Value> {
+    let len = indices.len();
+    if len <= threshold || threshold < 3 {
+        return indices.map(value).collect();
+    }
+
+    let start = indices.start;
+    // The first and last values are always kept, the others are split into `threshold - 2` buckets.
+    let bucket_size = (len - 2) as f64 / (threshold - 2) as f64;
+    let bucket = |i: usize| {
+        let begin = (i as f64 * bucket_size) as usize + 1;
+        let end = (((i + 1) as f64 * bucket_size) as usize + 1).min(len - 1);
+        start + begin..start + end
+    };
+
+    let mut decimated = Vec::with_capacity(threshold);
+    let mut previous = value(start);
+    decimated.push(previous);
+
+    for i in 0..threshold - 2 {
+        // The third corner of the triangle is the average of the next bucket:
+        let next = if i + 1 < threshold - 2 {
+            bucket(i + 1)
+        } else {
+            start + len - 1..start + len
+        };
+        let (mut sum_x, mut sum_y) = (0.0, 0.0);
+        for index in next.clone() {
+            let v = value(index);
+            sum_x += v.x;
+            sum_y += v.y;
+        }
+        let count = next.len().max(1) as f64;
+        let average = Value::new(sum_x / count, sum_y / count);
+
+        let mut selected = None;
+        let mut max_area = -1.0;
+        for index in bucket(i) {
+            let v = value(index);
+            let area = ((previous.x - average.x) * (v.y - previous.y)
+                - (previous.x - v.x) * (average.y - previous.y))
+                .abs();
+            if area > max_area {
+                max_area = area;
+                selected = Some(v);
+            }
+        }
+        if let Some(v) = selected {
+            decimated.push(v);
+            previous = v;
+        }
+    }
+
+    decimated.push(value(start + len - 1));
+    decimated
+}
+
+#[cfg(test)]
+#[test]
+fn test_decimation() {
+    let values: Vec<Value> = (0..10_000)
+        .map(|i| {
+            let y = if i == 5_001 { 100.0 } else { (i % 7) as f64 };
+            Value::new(i as f64, y)
+        })
+        .collect();
+    let value = |index: usize| values[index];
+    let x_range = 0.0..=10_000.0;
+
+    let min_max = Decimation::MinMax.decimate(&value, 0..values.len(), &x_range, 100);
+    assert!(min_max.len() <= 200);
+    assert!(min_max.windows(2).all(|w| w[0].x <= w[1].x));
+    assert!(min_max.contains(&values[5_001]), "Spikes are kept");
+
+    let lttb = Decimation::Lttb.decimate(&value, 0..values.len(), &x_range, 100);
+    assert_eq!(lttb.len(), 200);
+    assert_eq!(lttb[0], values[0]);
+    assert_eq!(lttb[199], values[9_999]);
+    assert!(lttb.windows(2).all(|w| w[0].x < w[1].x));
+    assert!(lttb.contains(&values[5_001]), "Spikes are kept");
+
+    assert!(!Decimation::MinMax.is_needed(200, 100));
+    assert!(!Decimation::None.is_needed(10_000, 100));
+}
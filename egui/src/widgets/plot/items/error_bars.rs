@@ -0,0 +1,226 @@
+use crate::emath::NumExt;
+use crate::epaint::{Color32, Shape, Stroke};
+use crate::{pos2, vec2};
+
+use super::{PlotConfig, Value, Values};
+use crate::plot::{PlotBounds, ScreenTransform};
+
+/// Error bars around the values of a [`Line`](super::Line) or [`Points`](super::Points),
+/// see [`Points::error_bars`](super::Points::error_bars).
+///
+/// There is one error for each value, in the same order. Values without an error get no bar.
+///
+/// The errors follow the values when only some of them are visible, e.g. of [`Values::from_sorted`].
+/// Values generated by a function, or decimated ones (see [`Line::decimation`](super::Line::decimation)),
+/// get no bars.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{ErrorBars, Plot, Points, Values};
+/// let points = Points::new(Values::from_ys_f32(&[1.0, 2.0, 1.5]))
+///     .error_bars(ErrorBars::new().y([0.1, 0.3, 0.2]).x_asymmetric([(0.1, 0.2); 3]));
+/// Plot::new("measurements").show(ui, |plot_ui| plot_ui.points(points));
+/// # });
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorBars {
+    /// How far each bar reaches below and above the value.
+    y: Vec<[f64; 2]>,
+    /// How far each bar reaches left and right of the value.
+    x: Vec<[f64; 2]>,
+    cap_width: f32,
+    width: f32,
+}
+
+impl Default for ErrorBars {
+    fn default() -> Self {
+        Self {
+            y: Vec::new(),
+            x: Vec::new(),
+            cap_width: 6.0,
+            width: 1.0,
+        }
+    }
+}
+
+impl ErrorBars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The same error below and above each value.
+    pub fn y(mut self, errors: impl IntoIterator<Item = f64>) -> Self {
+        self.y = errors.into_iter().map(|error| [error, error]).collect();
+        self
+    }
+
+    /// Different errors below and above each value, as `(below, above)`.
+    pub fn y_asymmetric(mut self, errors: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        self.y = errors
+            .into_iter()
+            .map(|(below, above)| [below, above])
+            .collect();
+        self
+    }
+
+    /// The same error left and right of each value.
+    pub fn x(mut self, errors: impl IntoIterator<Item = f64>) -> Self {
+        self.x = errors.into_iter().map(|error| [error, error]).collect();
+        self
+    }
+
+    /// Different errors left and right of each value, as `(left, right)`.
+    pub fn x_asymmetric(mut self, errors: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        self.x = errors
+            .into_iter()
+            .map(|(left, right)| [left, right])
+            .collect();
+        self
+    }
+
+    /// Width of the caps at the ends of the bars, in points. Zero means no caps. Default is 6.
+    pub fn cap_width(mut self, cap_width: impl Into<f32>) -> Self {
+        self.cap_width = cap_width.into();
+        self
+    }
+
+    /// Stroke width of the bars. Default is 1.
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub(super) fn get_shapes(
+        &self,
+        series: &Values,
+        transform: &ScreenTransform,
+        color: Color32,
+        highlight: bool,
+        shapes: &mut Vec<Shape>,
+    ) {
+        let mut stroke = Stroke::new(self.width, color);
+        if highlight {
+            stroke.width *= 2.0;
+        }
+        let half_cap = self.cap_width / 2.0;
+        let values = series.values.iter().enumerate().filter_map(|(i, value)| {
+            let index = series.series_index(i)?;
+            Some((value, self.y.get(index), self.x.get(index)))
+        });
+        for (value, y_errors, x_errors) in values {
+            if let Some([below, above]) = y_errors {
+                let low = transform.position_from_value(&Value::new(value.x, value.y - below));
+                let high = transform.position_from_value(&Value::new(value.x, value.y + above));
+                shapes.push(Shape::line_segment([low, high], stroke));
+                if half_cap > 0.0 {
+                    for end in [low, high] {
+                        let cap = vec2(half_cap, 0.0);
+                        shapes.push(Shape::line_segment([end - cap, end + cap], stroke));
+                    }
+                }
+            }
+            if let Some([left, right]) = x_errors {
+                let left = transform.position_from_value(&Value::new(value.x - left, value.y));
+                let right = transform.position_from_value(&Value::new(value.x + right, value.y));
+                shapes.push(Shape::line_segment([left, right], stroke));
+                if half_cap > 0.0 {
+                    for end in [left, right] {
+                        shapes.push(Shape::line_segment(
+                            [pos2(end.x, end.y - half_cap), pos2(end.x, end.y + half_cap)],
+                            stroke,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Make room for the bars.
+    pub(super) fn extend_bounds(&self, series: &Values, bounds: &mut PlotBounds) {
+        for (index, [below, above]) in self.y.iter().enumerate() {
+            if let Some(value) = series.series_value(index) {
+                bounds.extend_with_y(value.y - below);
+                bounds.extend_with_y(value.y + above);
+            }
+        }
+        for (index, [left, right]) in self.x.iter().enumerate() {
+            if let Some(value) = series.series_value(index) {
+                bounds.extend_with_x(value.x - left);
+                bounds.extend_with_x(value.x + right);
+            }
+        }
+    }
+
+    /// The errors of the value at `index` of the series, to add to its hover label.
+    pub(super) fn hover_text(&self, index: usize, plot: &PlotConfig<'_>) -> String {
+        let scale = plot.transform.dvalue_dpos();
+        let format = |axis: &str, errors: Option<&[f64; 2]>, scale: f64| {
+            let decimals = ((-scale.abs().log10()).ceil().at_least(0.0) as usize).at_most(6);
+            match errors {
+                Some([low, high]) if low == high => {
+                    format!("\n{} error = ±{:.*}", axis, decimals, low)
+                }
+                Some([low, high]) => format!(
+                    "\n{} error = -{:.*} / +{:.*}",
+                    axis, decimals, low, decimals, high
+                ),
+                None => String::new(),
+            }
+        };
+        let mut text = String::new();
+        if plot.show_x {
+            text += &format("x", self.x.get(index), scale[0]);
+        }
+        if plot.show_y {
+            text += &format("y", self.y.get(index), scale[1]);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_error_bars_of_visible_values() {
+    use crate::{Pos2, Rect};
+
+    // The error of each value is its x, and only some of the values are visible:
+    let values: Vec<Value> = (0..100).map(|i| Value::new(i as f64, 500.0)).collect();
+    let mut series = Values::from_sorted(values);
+    let error_bars = ErrorBars::new().y((0..100).map(f64::from)).cap_width(0.0);
+
+    let mut bounds = PlotBounds::NOTHING;
+    error_bars.extend_bounds(&series, &mut bounds);
+    assert_eq!(
+        (bounds.min()[1], bounds.max()[1]),
+        (500.0 - 99.0, 500.0 + 99.0)
+    );
+
+    let bounds = PlotBounds {
+        min: [50.0, 0.0],
+        max: [60.0, 1000.0],
+    };
+    let frame = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0));
+    let transform = ScreenTransform::new(frame, bounds, false, false);
+    series.generate_points(bounds.range_x());
+    let mut shapes = vec![];
+    error_bars.get_shapes(&series, &transform, Color32::WHITE, false, &mut shapes);
+
+    assert_eq!(shapes.len(), series.values.len());
+    for (shape, value) in shapes.iter().zip(&series.values) {
+        let [low, high] = match shape {
+            Shape::LineSegment { points, .. } => {
+                points.map(|pos| transform.value_from_position(pos))
+            }
+            _ => panic!("Expected a line segment"),
+        };
+        assert!((low.y - (500.0 - value.x)).abs() < 0.01);
+        assert!((high.y - (500.0 + value.x)).abs() < 0.01);
+    }
+
+    // Generated values have no errors of their own:
+    let mut series = Values::from_explicit_callback(|_| 500.0, 0.0..100.0, 10);
+    series.generate_points(bounds.range_x());
+    let mut shapes = vec![];
+    error_bars.get_shapes(&series, &transform, Color32::WHITE, false, &mut shapes);
+    assert!(shapes.is_empty());
+}
@@ -0,0 +1,468 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::emath::{self, NumExt};
+use crate::epaint::{Color32, Mesh, Rect, Shape, Stroke};
+use crate::{Pos2, Ui};
+
+use super::values::{ClosestElem, PlotGeometry};
+use super::{hover_text, rulers_with_text, LabelFormatter, PlotConfig, PlotItem, Value};
+use crate::plot::{PlotBounds, ScreenTransform, HOVER_RADIUS};
+
+/// How the values of a [`Heatmap`] are mapped to colors.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Colormap {
+    /// Dark blue to yellow, perceptually uniform. The default.
+    Viridis,
+
+    /// Black to yellow through red, perceptually uniform.
+    Inferno,
+
+    /// Black to light yellow through purple, perceptually uniform.
+    Magma,
+
+    /// Blue to yellow through pink, perceptually uniform.
+    Plasma,
+
+    /// Black to white.
+    Grayscale,
+
+    /// Evenly spaced colors, from the lowest to the highest value.
+    Custom(Vec<Color32>),
+}
+
+impl Default for Colormap {
+    fn default() -> Self {
+        Self::Viridis
+    }
+}
+
+impl Colormap {
+    /// Get all the colormaps, except [`Colormap::Custom`].
+    pub fn all() -> impl ExactSizeIterator<Item = Colormap> {
+        [
+            Self::Viridis,
+            Self::Inferno,
+            Self::Magma,
+            Self::Plasma,
+            Self::Grayscale,
+        ]
+        .into_iter()
+    }
+
+    /// The color at `t`, from `0.0` for the lowest value to `1.0` for the highest.
+    pub fn color_at(&self, t: f32) -> Color32 {
+        let stops = self.stops();
+        if stops.is_empty() || t.is_nan() {
+            return Color32::TRANSPARENT;
+        }
+
+        let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let i = (t.floor() as usize).min(stops.len().saturating_sub(2));
+        match (stops.get(i), stops.get(i + 1)) {
+            (Some(&a), Some(&b)) => {
+                let f = t - i as f32;
+                let channel = |a: u8, b: u8| emath::lerp(a as f32..=b as f32, f).round() as u8;
+                Color32::from_rgba_premultiplied(
+                    channel(a.r(), b.r()),
+                    channel(a.g(), b.g()),
+                    channel(a.b(), b.b()),
+                    channel(a.a(), b.a()),
+                )
+            }
+            (Some(&a), None) => a,
+            _ => Color32::TRANSPARENT,
+        }
+    }
+
+    fn stops(&self) -> &[Color32] {
+        match self {
+            Self::Viridis => &VIRIDIS,
+            Self::Inferno => &INFERNO,
+            Self::Magma => &MAGMA,
+            Self::Plasma => &PLASMA,
+            Self::Grayscale => &[Color32::BLACK, Color32::WHITE],
+            Self::Custom(colors) => colors,
+        }
+    }
+}
+
+// Sampled from the colormaps of matplotlib, by Nathaniel J. Smith, Stefan van der Walt and Eric Firing.
+
+const VIRIDIS: [Color32; 10] = [
+    Color32::from_rgb(0x44, 0x01, 0x54),
+    Color32::from_rgb(0x48, 0x28, 0x78),
+    Color32::from_rgb(0x3e, 0x49, 0x89),
+    Color32::from_rgb(0x31, 0x68, 0x8e),
+    Color32::from_rgb(0x26, 0x82, 0x8e),
+    Color32::from_rgb(0x1f, 0x9e, 0x89),
+    Color32::from_rgb(0x35, 0xb7, 0x79),
+    Color32::from_rgb(0x6e, 0xce, 0x58),
+    Color32::from_rgb(0xb5, 0xde, 0x2b),
+    Color32::from_rgb(0xfd, 0xe7, 0x25),
+];
+
+const INFERNO: [Color32; 10] = [
+    Color32::from_rgb(0x00, 0x00, 0x04),
+    Color32::from_rgb(0x1b, 0x0c, 0x41),
+    Color32::from_rgb(0x4a, 0x0c, 0x6b),
+    Color32::from_rgb(0x78, 0x1c, 0x6d),
+    Color32::from_rgb(0xa5, 0x2c, 0x60),
+    Color32::from_rgb(0xcf, 0x44, 0x46),
+    Color32::from_rgb(0xed, 0x69, 0x25),
+    Color32::from_rgb(0xfb, 0x9b, 0x06),
+    Color32::from_rgb(0xf7, 0xd1, 0x3d),
+    Color32::from_rgb(0xfc, 0xff, 0xa4),
+];
+
+const MAGMA: [Color32; 10] = [
+    Color32::from_rgb(0x00, 0x00, 0x04),
+    Color32::from_rgb(0x18, 0x0f, 0x3d),
+    Color32::from_rgb(0x44, 0x0f, 0x76),
+    Color32::from_rgb(0x72, 0x1f, 0x81),
+    Color32::from_rgb(0x9e, 0x2f, 0x7f),
+    Color32::from_rgb(0xcd, 0x40, 0x71),
+    Color32::from_rgb(0xf1, 0x60, 0x5d),
+    Color32::from_rgb(0xfd, 0x96, 0x68),
+    Color32::from_rgb(0xfe, 0xca, 0x8d),
+    Color32::from_rgb(0xfc, 0xfd, 0xbf),
+];
+
+const PLASMA: [Color32; 10] = [
+    Color32::from_rgb(0x0d, 0x08, 0x87),
+    Color32::from_rgb(0x46, 0x03, 0x9f),
+    Color32::from_rgb(0x72, 0x01, 0xa8),
+    Color32::from_rgb(0x9c, 0x17, 0x9e),
+    Color32::from_rgb(0xbd, 0x37, 0x86),
+    Color32::from_rgb(0xd8, 0x57, 0x6b),
+    Color32::from_rgb(0xed, 0x79, 0x53),
+    Color32::from_rgb(0xfb, 0x9f, 0x3a),
+    Color32::from_rgb(0xfd, 0xca, 0x26),
+    Color32::from_rgb(0xf0, 0xf9, 0x21),
+];
+
+// ----------------------------------------------------------------------------
+
+type HeatmapFormatter = dyn Fn(&str, &Value, f64) -> String;
+
+/// A grid of cells colored by their values, e.g. a matrix or a 2D histogram.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{Colormap, Heatmap, Legend, Plot};
+/// let values: Vec<f64> = (0..100).map(|i| ((i % 10) * (i / 10)) as f64).collect();
+/// let heatmap = Heatmap::new(values, 10)
+///     .colormap(Colormap::Inferno)
+///     .name("Products");
+/// Plot::new("heatmap")
+///     .legend(Legend::default())
+///     .show(ui, |plot_ui| plot_ui.heatmap(heatmap));
+/// # });
+/// ```
+pub struct Heatmap {
+    /// Row by row, starting with the bottom one.
+    pub(super) values: Vec<f64>,
+    pub(super) columns: usize,
+    /// The lower left corner of the first cell.
+    pub(super) origin: Value,
+    pub(super) cell_size: [f64; 2],
+    pub(super) colormap: Colormap,
+    pub(super) range: Option<RangeInclusive<f64>>,
+    pub(super) name: String,
+    pub(super) highlight: bool,
+    pub(super) label_formatter: Option<Box<HeatmapFormatter>>,
+}
+
+impl Heatmap {
+    /// A grid of cells with the given values, row by row, starting with the bottom one.
+    ///
+    /// The cells are one unit wide and high, with the first one at the origin.
+    /// Values that are not finite are left out. If the last row is incomplete, it is left out too.
+    pub fn new(values: Vec<f64>, columns: usize) -> Self {
+        Self {
+            values,
+            columns: columns.at_least(1),
+            origin: Value::new(0.0, 0.0),
+            cell_size: [1.0, 1.0],
+            colormap: Colormap::default(),
+            range: None,
+            name: Default::default(),
+            highlight: false,
+            label_formatter: None,
+        }
+    }
+
+    /// A 2D histogram: the number of points in each of `columns` by `rows` cells,
+    /// which together span the bounds of the points.
+    pub fn density(points: impl IntoIterator<Item = Value>, columns: usize, rows: usize) -> Self {
+        let (columns, rows) = (columns.at_least(1), rows.at_least(1));
+        let points: Vec<Value> = points
+            .into_iter()
+            .filter(|point| point.x.is_finite() && point.y.is_finite())
+            .collect();
+
+        let mut bounds = PlotBounds::NOTHING;
+        for point in &points {
+            bounds.extend_with(point);
+        }
+        if !bounds.is_finite() {
+            return Self::new(vec![0.0; columns * rows], columns);
+        }
+        // Make sure that all points fall into a cell, even if they are all on a line:
+        if bounds.width() <= 0.0 {
+            bounds.expand_x(0.5);
+        }
+        if bounds.height() <= 0.0 {
+            bounds.expand_y(0.5);
+        }
+
+        let cell_size = [
+            bounds.width() / columns as f64,
+            bounds.height() / rows as f64,
+        ];
+        let mut counts = vec![0.0; columns * rows];
+        for point in &points {
+            let column = ((point.x - bounds.min[0]) / cell_size[0]) as usize;
+            let row = ((point.y - bounds.min[1]) / cell_size[1]) as usize;
+            counts[row.at_most(rows - 1) * columns + column.at_most(columns - 1)] += 1.0;
+        }
+
+        Self::new(counts, columns)
+            .origin(Value::new(bounds.min[0], bounds.min[1]))
+            .cell_size(cell_size[0], cell_size[1])
+    }
+
+    /// Where the lower left corner of the first cell is. Default: the origin.
+    pub fn origin(mut self, origin: Value) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// The width and height of each cell, in plot units. Default: `1.0` by `1.0`.
+    pub fn cell_size(mut self, width: impl Into<f64>, height: impl Into<f64>) -> Self {
+        self.cell_size = [width.into(), height.into()];
+        self
+    }
+
+    /// How the values are mapped to colors. Default: [`Colormap::Viridis`].
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// The values mapped to the lowest and highest color of the colormap.
+    /// Values outside of it get the color at its end. Default: the range of the values.
+    pub fn range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Highlight the heatmap in the plot with an outline.
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Name of this heatmap.
+    ///
+    /// This name will show up in the plot legend, if legends are turned on,
+    /// together with a colorbar.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Provide a function to customize the on-hover label of a cell,
+    /// from the name of the heatmap, the center of the cell and its value.
+    ///
+    /// By default, the value is shown below the label of [`Plot::label_formatter`](crate::plot::Plot::label_formatter).
+    pub fn label_formatter(
+        mut self,
+        label_formatter: impl Fn(&str, &Value, f64) -> String + 'static,
+    ) -> Self {
+        self.label_formatter = Some(Box::new(label_formatter));
+        self
+    }
+
+    fn rows(&self) -> usize {
+        self.values.len() / self.columns
+    }
+
+    /// Returns true if there isn't a single row of values.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rows() == 0
+    }
+
+    /// The values mapped to the ends of the colormap.
+    pub(super) fn value_range(&self) -> RangeInclusive<f64> {
+        if let Some(range) = &self.range {
+            return range.clone();
+        }
+        let (min, max) = self
+            .values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+        if min <= max {
+            min..=max
+        } else {
+            0.0..=1.0
+        }
+    }
+
+    fn color(&self, value: f64, range: &RangeInclusive<f64>) -> Color32 {
+        let (min, max) = (*range.start(), *range.end());
+        let t = if max > min {
+            (value - min) / (max - min)
+        } else {
+            0.5
+        };
+        self.colormap.color_at(t as f32)
+    }
+
+    fn cell_rect(&self, transform: &ScreenTransform, column: usize, row: usize) -> Rect {
+        let min = Value::new(
+            self.origin.x + column as f64 * self.cell_size[0],
+            self.origin.y + row as f64 * self.cell_size[1],
+        );
+        let max = Value::new(min.x + self.cell_size[0], min.y + self.cell_size[1]);
+        transform.rect_from_values(&min, &max)
+    }
+
+    /// The cells which are at least partly inside the bounds, along one axis.
+    fn visible_cells(&self, bounds: &PlotBounds, axis: usize, count: usize) -> Range<usize> {
+        let origin = [self.origin.x, self.origin.y][axis];
+        let cell = |value: f64| (value - origin) / self.cell_size[axis];
+        let start = cell(bounds.min[axis]).floor().at_least(0.0);
+        let end = cell(bounds.max[axis]).ceil().at_least(0.0);
+        (start as usize).at_most(count)..(end as usize).at_most(count)
+    }
+}
+
+impl PlotItem for Heatmap {
+    fn get_shapes(&self, ui: &mut Ui, transform: &ScreenTransform, shapes: &mut Vec<Shape>) {
+        let range = self.value_range();
+        let rows = self.rows();
+
+        let mut mesh = Mesh::default();
+        for row in self.visible_cells(transform.bounds(), 1, rows) {
+            for column in self.visible_cells(transform.bounds(), 0, self.columns) {
+                let value = self.values[row * self.columns + column];
+                if value.is_finite() {
+                    let rect = self.cell_rect(transform, column, row);
+                    mesh.add_colored_rect(rect, self.color(value, &range));
+                }
+            }
+        }
+        shapes.push(Shape::mesh(mesh));
+
+        if self.highlight && rows > 0 {
+            let min = self.cell_rect(transform, 0, 0);
+            let max = self.cell_rect(transform, self.columns - 1, rows - 1);
+            let stroke = Stroke::new(2.0, ui.visuals().text_color());
+            shapes.push(Shape::rect_stroke(min.union(max), 0.0, stroke));
+        }
+    }
+
+    fn initialize(&mut self, _transform: &ScreenTransform) {}
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn color(&self) -> Color32 {
+        self.colormap.color_at(0.5)
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::Rects
+    }
+
+    fn get_bounds(&self) -> PlotBounds {
+        let mut bounds = PlotBounds::NOTHING;
+        let rows = self.rows();
+        if rows > 0 {
+            bounds.extend_with(&self.origin);
+            bounds.extend_with(&Value::new(
+                self.origin.x + self.columns as f64 * self.cell_size[0],
+                self.origin.y + rows as f64 * self.cell_size[1],
+            ));
+        }
+        bounds
+    }
+
+    fn colorbar(&self) -> Option<(&Colormap, RangeInclusive<f64>)> {
+        Some((&self.colormap, self.value_range()))
+    }
+
+    fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        let value = transform.value_from_position(point);
+        let column = ((value.x - self.origin.x) / self.cell_size[0]).floor();
+        let row = ((value.y - self.origin.y) / self.cell_size[1]).floor();
+        let inside = (0.0..self.columns as f64).contains(&column)
+            && (0.0..self.rows() as f64).contains(&row);
+        inside.then(|| ClosestElem {
+            index: row as usize * self.columns + column as usize,
+            // The heatmap covers an area, so prefer any other item near the pointer:
+            dist_sq: HOVER_RADIUS * HOVER_RADIUS,
+        })
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        let (column, row) = (elem.index % self.columns, elem.index / self.columns);
+        let value = self.values[elem.index];
+        if !value.is_finite() {
+            return;
+        }
+
+        let rect = self.cell_rect(plot.transform, column, row);
+        let stroke = Stroke::new(2.0, plot.ui.visuals().text_color());
+        shapes.push(Shape::rect_stroke(rect, 0.0, stroke));
+
+        let center = plot.transform.value_from_position(rect.center());
+        let text = if let Some(custom_label) = &self.label_formatter {
+            custom_label(&self.name, &center, value)
+        } else {
+            format!(
+                "{}\nvalue = {}",
+                hover_text(center, &self.name, plot, label_formatter),
+                emath::round_to_decimals(value, 5)
+            )
+        };
+        rulers_with_text(rect.center(), plot, text, shapes);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_heatmap() {
+    assert_eq!(Colormap::Grayscale.color_at(0.0), Color32::BLACK);
+    assert_eq!(Colormap::Grayscale.color_at(2.0), Color32::WHITE);
+    assert_eq!(Colormap::Viridis.color_at(1.0), VIRIDIS[9]);
+    assert_eq!(
+        Colormap::Custom(vec![Color32::BLACK, Color32::WHITE]).color_at(0.5),
+        Color32::from_gray(128)
+    );
+
+    let points = [(0.0, 0.0), (0.1, 0.1), (1.9, 0.0), (2.0, 1.0)];
+    let density = Heatmap::density(points.map(|(x, y)| Value::new(x, y)), 2, 2);
+    assert_eq!(density.values, [2.0, 1.0, 0.0, 1.0]);
+    assert_eq!(density.cell_size, [1.0, 0.5]);
+    assert_eq!(density.value_range(), 0.0..=2.0);
+}
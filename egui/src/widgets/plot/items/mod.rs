@@ -2,6 +2,7 @@
 
 use std::ops::RangeInclusive;
 
+use epaint::ahash::AHashSet;
 use epaint::util::FloatOrd;
 use epaint::Mesh;
 
@@ -11,12 +12,22 @@ use super::{LabelFormatter, PlotBounds, ScreenTransform};
 use rect_elem::*;
 use values::{ClosestElem, PlotGeometry};
 
+pub use area_chart::AreaChart;
 pub use bar::Bar;
 pub use box_elem::{BoxElem, BoxSpread};
-pub use values::{LineStyle, MarkerShape, Orientation, Value, Values};
+pub use decimation::Decimation;
+pub use error_bars::ErrorBars;
+pub use heatmap::{Colormap, Heatmap};
+pub use pie_chart::{PieChart, Slice};
+pub use values::{LineStyle, MarkerShape, Orientation, StreamingValues, Value, Values};
 
+mod area_chart;
 mod bar;
 mod box_elem;
+mod decimation;
+mod error_bars;
+mod heatmap;
+mod pie_chart;
 mod rect_elem;
 mod values;
 
@@ -28,12 +39,14 @@ pub(super) struct PlotConfig<'a> {
     pub transform: &'a ScreenTransform,
     pub show_x: bool,
     pub show_y: bool,
+    /// Show x as a date and time, see [`super::Plot::time_x_axis`].
+    pub time_x_axis: bool,
 }
 
 /// Trait shared by things that can be drawn in the plot.
 pub(super) trait PlotItem {
     fn get_shapes(&self, ui: &mut Ui, transform: &ScreenTransform, shapes: &mut Vec<Shape>);
-    fn initialize(&mut self, x_range: RangeInclusive<f64>);
+    fn initialize(&mut self, transform: &ScreenTransform);
     fn name(&self) -> &str;
     fn color(&self) -> Color32;
     fn highlight(&mut self);
@@ -41,6 +54,45 @@ pub(super) trait PlotItem {
     fn geometry(&self) -> PlotGeometry<'_>;
     fn get_bounds(&self) -> PlotBounds;
 
+    /// Is this plotted against the secondary Y axis?
+    fn secondary_y_axis(&self) -> bool {
+        false
+    }
+
+    /// The colormap and the range of values it spans, to show in the legend.
+    fn colorbar(&self) -> Option<(&Colormap, RangeInclusive<f64>)> {
+        None
+    }
+
+    /// The names and colors of the legend entries of this item.
+    /// Most items have one, but e.g. a [`PieChart`] has one for each slice.
+    fn legend_entries(&self) -> Vec<(&str, Color32)> {
+        vec![(self.name(), self.color())]
+    }
+
+    /// Highlight the parts of this item that belong to the hovered legend entry.
+    fn highlight_entry(&mut self, name: &str) {
+        if self.name() == name {
+            self.highlight();
+        }
+    }
+
+    /// Hide the parts of this item whose legend entries are unchecked.
+    /// Returns `false` if nothing is left to show.
+    fn hide_entries(&mut self, hidden: &AHashSet<String>) -> bool {
+        !hidden.contains(self.name())
+    }
+
+    /// The indices of the values inside `bounds`, for [`super::Plot::brush`].
+    fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        match self.geometry() {
+            PlotGeometry::Points(points) => (0..points.len())
+                .filter(|&index| bounds.contains(&points[index]))
+                .collect(),
+            PlotGeometry::None | PlotGeometry::Rects => Vec::new(),
+        }
+    }
+
     fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
         match self.geometry() {
             PlotGeometry::None => None,
@@ -78,18 +130,86 @@ pub(super) trait PlotItem {
             }
         };
 
-        let line_color = if plot.ui.visuals().dark_mode {
-            Color32::from_gray(100).additive()
-        } else {
-            Color32::from_black_alpha(180)
-        };
-
         // this method is only called, if the value is in the result set of find_closest()
         let value = points[elem.index];
-        let pointer = plot.transform.position_from_value(&value);
-        shapes.push(Shape::circle_filled(pointer, 3.0, line_color));
+        mark_hovered_value(value, self.name(), None, shapes, plot, label_formatter);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// An item that is plotted against the secondary Y axis, see [`super::PlotUi::on_secondary_y_axis`].
+pub(super) struct OnSecondaryYAxis(pub Box<dyn PlotItem>);
+
+impl PlotItem for OnSecondaryYAxis {
+    fn get_shapes(&self, ui: &mut Ui, transform: &ScreenTransform, shapes: &mut Vec<Shape>) {
+        self.0.get_shapes(ui, transform, shapes);
+    }
 
-        rulers_at_value(pointer, value, self.name(), plot, shapes, label_formatter);
+    fn initialize(&mut self, transform: &ScreenTransform) {
+        self.0.initialize(transform);
+    }
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn color(&self) -> Color32 {
+        self.0.color()
+    }
+
+    fn highlight(&mut self) {
+        self.0.highlight();
+    }
+
+    fn highlighted(&self) -> bool {
+        self.0.highlighted()
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        self.0.geometry()
+    }
+
+    fn get_bounds(&self) -> PlotBounds {
+        self.0.get_bounds()
+    }
+
+    fn secondary_y_axis(&self) -> bool {
+        true
+    }
+
+    fn colorbar(&self) -> Option<(&Colormap, RangeInclusive<f64>)> {
+        self.0.colorbar()
+    }
+
+    fn legend_entries(&self) -> Vec<(&str, Color32)> {
+        self.0.legend_entries()
+    }
+
+    fn highlight_entry(&mut self, name: &str) {
+        self.0.highlight_entry(name);
+    }
+
+    fn hide_entries(&mut self, hidden: &AHashSet<String>) -> bool {
+        self.0.hide_entries(hidden)
+    }
+
+    fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        self.0.select(bounds)
+    }
+
+    fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        self.0.find_closest(point, transform)
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        self.0.on_hover(elem, shapes, plot, label_formatter);
     }
 }
 
@@ -103,6 +223,7 @@ pub struct HLine {
     pub(super) name: String,
     pub(super) highlight: bool,
     pub(super) style: LineStyle,
+    pub(super) draggable: bool,
 }
 
 impl HLine {
@@ -113,6 +234,7 @@ impl HLine {
             name: String::default(),
             highlight: false,
             style: LineStyle::Solid,
+            draggable: false,
         }
     }
 
@@ -157,6 +279,13 @@ impl HLine {
         self.name = name.to_string();
         self
     }
+
+    /// Let the user drag this line up and down, see [`PlotUi::hline`](super::PlotUi::hline).
+    /// Default: `false`.
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
 }
 
 impl PlotItem for HLine {
@@ -175,7 +304,7 @@ impl PlotItem for HLine {
         style.style_line(points, *stroke, *highlight, shapes);
     }
 
-    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {}
+    fn initialize(&mut self, _transform: &ScreenTransform) {}
 
     fn name(&self) -> &str {
         &self.name
@@ -203,6 +332,32 @@ impl PlotItem for HLine {
         bounds.max[1] = self.y;
         bounds
     }
+
+    fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        self.draggable.then(|| {
+            let pos = transform.position_from_value(&Value::new(0.0, self.y));
+            ClosestElem {
+                index: 0,
+                dist_sq: (point.y - pos.y).powi(2),
+            }
+        })
+    }
+
+    fn on_hover(
+        &self,
+        _elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        _label_formatter: &LabelFormatter,
+    ) {
+        // Show that the line can be grabbed.
+        let transform = plot.transform;
+        let points = vec![
+            transform.position_from_value(&Value::new(transform.bounds().min[0], self.y)),
+            transform.position_from_value(&Value::new(transform.bounds().max[0], self.y)),
+        ];
+        self.style.style_line(points, self.stroke, true, shapes);
+    }
 }
 
 /// A vertical line in a plot, filling the full width
@@ -213,6 +368,7 @@ pub struct VLine {
     pub(super) name: String,
     pub(super) highlight: bool,
     pub(super) style: LineStyle,
+    pub(super) draggable: bool,
 }
 
 impl VLine {
@@ -223,6 +379,7 @@ impl VLine {
             name: String::default(),
             highlight: false,
             style: LineStyle::Solid,
+            draggable: false,
         }
     }
 
@@ -267,6 +424,13 @@ impl VLine {
         self.name = name.to_string();
         self
     }
+
+    /// Let the user drag this line left and right, see [`PlotUi::vline`](super::PlotUi::vline).
+    /// Default: `false`.
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
 }
 
 impl PlotItem for VLine {
@@ -285,7 +449,7 @@ impl PlotItem for VLine {
         style.style_line(points, *stroke, *highlight, shapes);
     }
 
-    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {}
+    fn initialize(&mut self, _transform: &ScreenTransform) {}
 
     fn name(&self) -> &str {
         &self.name
@@ -313,6 +477,32 @@ impl PlotItem for VLine {
         bounds.max[0] = self.x;
         bounds
     }
+
+    fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        self.draggable.then(|| {
+            let pos = transform.position_from_value(&Value::new(self.x, 0.0));
+            ClosestElem {
+                index: 0,
+                dist_sq: (point.x - pos.x).powi(2),
+            }
+        })
+    }
+
+    fn on_hover(
+        &self,
+        _elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        _label_formatter: &LabelFormatter,
+    ) {
+        // Show that the line can be grabbed.
+        let transform = plot.transform;
+        let points = vec![
+            transform.position_from_value(&Value::new(self.x, transform.bounds().min[1])),
+            transform.position_from_value(&Value::new(self.x, transform.bounds().max[1])),
+        ];
+        self.style.style_line(points, self.stroke, true, shapes);
+    }
 }
 
 /// A series of values forming a path.
@@ -323,6 +513,8 @@ pub struct Line {
     pub(super) highlight: bool,
     pub(super) fill: Option<f32>,
     pub(super) style: LineStyle,
+    pub(super) decimation: Decimation,
+    pub(super) error_bars: Option<ErrorBars>,
 }
 
 impl Line {
@@ -334,6 +526,8 @@ impl Line {
             highlight: false,
             fill: None,
             style: LineStyle::Solid,
+            decimation: Decimation::MinMax,
+            error_bars: None,
         }
     }
 
@@ -373,6 +567,24 @@ impl Line {
         self
     }
 
+    /// How to reduce the number of values when there are many more than the plot is wide.
+    /// Default is `Decimation::MinMax`.
+    pub fn decimation(mut self, decimation: Decimation) -> Self {
+        self.decimation = decimation;
+        self
+    }
+
+    /// Draw error bars around the values, and show the errors when a value is hovered.
+    ///
+    /// Values given as they are or sorted, e.g. with [`Values::from_values`] or [`Values::from_sorted`],
+    /// get error bars, but not those generated by a function.
+    /// The line is not decimated, so that the errors match the values.
+    pub fn error_bars(mut self, error_bars: ErrorBars) -> Self {
+        self.error_bars = Some(error_bars);
+        self.decimation = Decimation::None;
+        self
+    }
+
     /// Name of this line.
     ///
     /// This name will show up in the plot legend, if legends are turned on.
@@ -401,6 +613,7 @@ impl PlotItem for Line {
             highlight,
             mut fill,
             style,
+            error_bars,
             ..
         } = self;
 
@@ -451,10 +664,14 @@ impl PlotItem for Line {
             shapes.push(Shape::Mesh(mesh));
         }
         style.style_line(values_tf, *stroke, *highlight, shapes);
+        if let Some(error_bars) = error_bars {
+            error_bars.get_shapes(series, transform, stroke.color, *highlight, shapes);
+        }
     }
 
-    fn initialize(&mut self, x_range: RangeInclusive<f64>) {
-        self.series.generate_points(x_range);
+    fn initialize(&mut self, transform: &ScreenTransform) {
+        self.series
+            .generate_decimated_points(transform, self.decimation);
     }
 
     fn name(&self) -> &str {
@@ -477,8 +694,33 @@ impl PlotItem for Line {
         PlotGeometry::Points(&self.series.values)
     }
 
+    fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        self.series.select(bounds)
+    }
+
     fn get_bounds(&self) -> PlotBounds {
-        self.series.get_bounds()
+        let mut bounds = self.series.get_bounds();
+        if let Some(error_bars) = &self.error_bars {
+            error_bars.extend_bounds(&self.series, &mut bounds);
+        }
+        bounds
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        let value = self.series.values[elem.index];
+        let index = self.series.series_index(elem.index);
+        let errors = self
+            .error_bars
+            .as_ref()
+            .zip(index)
+            .map(|(error_bars, index)| error_bars.hover_text(index, plot));
+        mark_hovered_value(value, self.name(), errors, shapes, plot, label_formatter);
     }
 }
 
@@ -583,8 +825,8 @@ impl PlotItem for Polygon {
         style.style_line(values_tf, *stroke, *highlight, shapes);
     }
 
-    fn initialize(&mut self, x_range: RangeInclusive<f64>) {
-        self.series.generate_points(x_range);
+    fn initialize(&mut self, transform: &ScreenTransform) {
+        self.series.generate_points(transform.bounds().range_x());
     }
 
     fn name(&self) -> &str {
@@ -699,7 +941,7 @@ impl PlotItem for Text {
         }
     }
 
-    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {}
+    fn initialize(&mut self, _transform: &ScreenTransform) {}
 
     fn name(&self) -> &str {
         self.name.as_str()
@@ -741,6 +983,9 @@ pub struct Points {
     pub(super) name: String,
     pub(super) highlight: bool,
     pub(super) stems: Option<f32>,
+    pub(super) decimation: Decimation,
+    pub(super) draggable: bool,
+    pub(super) error_bars: Option<ErrorBars>,
 }
 
 impl Points {
@@ -754,6 +999,9 @@ impl Points {
             name: Default::default(),
             highlight: false,
             stems: None,
+            decimation: Decimation::default(),
+            draggable: false,
+            error_bars: None,
         }
     }
 
@@ -793,6 +1041,13 @@ impl Points {
         self
     }
 
+    /// How to reduce the number of points when there are many more than the plot is wide.
+    /// Default is `Decimation::None`: every point is painted, so no outlier is lost.
+    pub fn decimation(mut self, decimation: Decimation) -> Self {
+        self.decimation = decimation;
+        self
+    }
+
     /// Name of this set of points.
     ///
     /// This name will show up in the plot legend, if legends are turned on.
@@ -804,6 +1059,30 @@ impl Points {
         self.name = name.to_string();
         self
     }
+
+    /// Let the user drag the points around one at a time, see [`PlotUi::points`](super::PlotUi::points).
+    ///
+    /// Only values given as they are, e.g. with [`Values::from_values`], can be grabbed.
+    /// Draggable points are not decimated, so that the indices match the values.
+    /// Default: `false`.
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        if draggable {
+            self.decimation = Decimation::None;
+        }
+        self
+    }
+
+    /// Draw error bars around the points, and show the errors when a point is hovered.
+    ///
+    /// Values given as they are or sorted, e.g. with [`Values::from_values`] or [`Values::from_sorted`],
+    /// get error bars, but not those generated by a function.
+    /// The points are not decimated, so that the errors match the values.
+    pub fn error_bars(mut self, error_bars: ErrorBars) -> Self {
+        self.error_bars = Some(error_bars);
+        self.decimation = Decimation::None;
+        self
+    }
 }
 
 impl PlotItem for Points {
@@ -820,6 +1099,7 @@ impl PlotItem for Points {
             mut radius,
             highlight,
             stems,
+            error_bars,
             ..
         } = self;
 
@@ -840,6 +1120,10 @@ impl PlotItem for Points {
         let y_reference =
             stems.map(|y| transform.position_from_value(&Value::new(0.0, y)).y as f32);
 
+        if let Some(error_bars) = error_bars {
+            error_bars.get_shapes(series, transform, *color, *highlight, shapes);
+        }
+
         series
             .values
             .iter()
@@ -935,8 +1219,9 @@ impl PlotItem for Points {
             });
     }
 
-    fn initialize(&mut self, x_range: RangeInclusive<f64>) {
-        self.series.generate_points(x_range);
+    fn initialize(&mut self, transform: &ScreenTransform) {
+        self.series
+            .generate_decimated_points(transform, self.decimation);
     }
 
     fn name(&self) -> &str {
@@ -959,8 +1244,33 @@ impl PlotItem for Points {
         PlotGeometry::Points(&self.series.values)
     }
 
+    fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        self.series.select(bounds)
+    }
+
     fn get_bounds(&self) -> PlotBounds {
-        self.series.get_bounds()
+        let mut bounds = self.series.get_bounds();
+        if let Some(error_bars) = &self.error_bars {
+            error_bars.extend_bounds(&self.series, &mut bounds);
+        }
+        bounds
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        label_formatter: &LabelFormatter,
+    ) {
+        let value = self.series.values[elem.index];
+        let index = self.series.series_index(elem.index);
+        let errors = self
+            .error_bars
+            .as_ref()
+            .zip(index)
+            .map(|(error_bars, index)| error_bars.hover_text(index, plot));
+        mark_hovered_value(value, self.name(), errors, shapes, plot, label_formatter);
     }
 }
 
@@ -1048,7 +1358,7 @@ impl PlotItem for Arrows {
             });
     }
 
-    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {
+    fn initialize(&mut self, _transform: &ScreenTransform) {
         self.origins
             .generate_points(f64::NEG_INFINITY..=f64::INFINITY);
         self.tips.generate_points(f64::NEG_INFINITY..=f64::INFINITY);
@@ -1187,7 +1497,7 @@ impl PlotItem for PlotImage {
         }
     }
 
-    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {}
+    fn initialize(&mut self, _transform: &ScreenTransform) {}
 
     fn name(&self) -> &str {
         self.name.as_str()
@@ -1346,7 +1656,7 @@ impl PlotItem for BarChart {
         }
     }
 
-    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {
+    fn initialize(&mut self, _transform: &ScreenTransform) {
         // nothing to do
     }
 
@@ -1378,6 +1688,10 @@ impl PlotItem for BarChart {
         bounds
     }
 
+    fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        select_rects(&self.bars, bounds)
+    }
+
     fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
         find_closest_rect(&self.bars, point, transform)
     }
@@ -1488,7 +1802,7 @@ impl PlotItem for BoxPlot {
         }
     }
 
-    fn initialize(&mut self, _x_range: RangeInclusive<f64>) {
+    fn initialize(&mut self, _transform: &ScreenTransform) {
         // nothing to do
     }
 
@@ -1520,6 +1834,10 @@ impl PlotItem for BoxPlot {
         bounds
     }
 
+    fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        select_rects(&self.boxes, bounds)
+    }
+
     fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
         find_closest_rect(&self.boxes, point, transform)
     }
@@ -1652,39 +1970,76 @@ pub(super) fn rulers_at_value(
     shapes: &mut Vec<Shape>,
     label_formatter: &LabelFormatter,
 ) {
-    let line_color = rulers_color(plot.ui);
-    if plot.show_x {
-        shapes.push(vertical_line(pointer, plot.transform, line_color));
-    }
-    if plot.show_y {
-        shapes.push(horizontal_line(pointer, plot.transform, line_color));
-    }
+    let text = hover_text(value, name, plot, label_formatter);
+    rulers_with_text(pointer, plot, text, shapes);
+}
 
+/// A dot on the hovered value, and rulers and a label through it, with `extra_text` added to the label.
+fn mark_hovered_value(
+    value: Value,
+    name: &str,
+    extra_text: Option<String>,
+    shapes: &mut Vec<Shape>,
+    plot: &PlotConfig<'_>,
+    label_formatter: &LabelFormatter,
+) {
+    let pointer = plot.transform.position_from_value(&value);
+    shapes.push(Shape::circle_filled(pointer, 3.0, rulers_color(plot.ui)));
+    let mut text = hover_text(value, name, plot, label_formatter);
+    text += &extra_text.unwrap_or_default();
+    rulers_with_text(pointer, plot, text, shapes);
+}
+
+/// The label of a hovered value, using the label formatter of the plot if it has one.
+pub(super) fn hover_text(
+    value: Value,
+    name: &str,
+    plot: &PlotConfig<'_>,
+    label_formatter: &LabelFormatter,
+) -> String {
     let mut prefix = String::new();
 
     if !name.is_empty() {
         prefix = format!("{}\n", name);
     }
 
-    let text = {
+    {
         let scale = plot.transform.dvalue_dpos();
         let x_decimals = ((-scale[0].abs().log10()).ceil().at_least(0.0) as usize).at_most(6);
         let y_decimals = ((-scale[1].abs().log10()).ceil().at_least(0.0) as usize).at_most(6);
+        let x = if plot.time_x_axis {
+            super::format_timestamp(value.x, scale[0].abs())
+        } else {
+            format!("{:.*}", x_decimals, value.x)
+        };
         if let Some(custom_label) = label_formatter {
             custom_label(name, &value)
         } else if plot.show_x && plot.show_y {
-            format!(
-                "{}x = {:.*}\ny = {:.*}",
-                prefix, x_decimals, value.x, y_decimals, value.y
-            )
+            format!("{}x = {}\ny = {:.*}", prefix, x, y_decimals, value.y)
         } else if plot.show_x {
-            format!("{}x = {:.*}", prefix, x_decimals, value.x)
+            format!("{}x = {}", prefix, x)
         } else if plot.show_y {
             format!("{}y = {:.*}", prefix, y_decimals, value.y)
         } else {
             unreachable!()
         }
-    };
+    }
+}
+
+/// Rulers through the pointer, and a label next to it.
+pub(super) fn rulers_with_text(
+    pointer: Pos2,
+    plot: &PlotConfig<'_>,
+    text: String,
+    shapes: &mut Vec<Shape>,
+) {
+    let line_color = rulers_color(plot.ui);
+    if plot.show_x {
+        shapes.push(vertical_line(pointer, plot.transform, line_color));
+    }
+    if plot.show_y {
+        shapes.push(horizontal_line(pointer, plot.transform, line_color));
+    }
 
     let font_id = TextStyle::Body.resolve(plot.ui.style());
 
@@ -1698,6 +2053,19 @@ pub(super) fn rulers_at_value(
     ));
 }
 
+/// The indices of the rectangles that overlap `bounds`.
+fn select_rects<'a, T>(rects: impl IntoIterator<Item = &'a T>, bounds: &PlotBounds) -> Vec<usize>
+where
+    T: 'a + RectElement,
+{
+    rects
+        .into_iter()
+        .enumerate()
+        .filter(|(_, rect)| rect.bounds().intersects(bounds))
+        .map(|(index, _)| index)
+        .collect()
+}
+
 fn find_closest_rect<'a, T>(
     rects: impl IntoIterator<Item = &'a T>,
     point: Pos2,
@@ -1717,3 +2085,18 @@ where
         })
         .min_by_key(|e| e.dist_sq.ord())
 }
+
+#[cfg(test)]
+#[test]
+fn test_hline_and_vline_bounds() {
+    // Only the axis the line is at, not the one it runs along:
+    let bounds = HLine::new(1000.0).get_bounds();
+    assert_eq!((bounds.min[1], bounds.max[1]), (1000.0, 1000.0));
+    assert_eq!(bounds.min[0], f64::INFINITY);
+    assert_eq!(bounds.max[0], f64::NEG_INFINITY);
+
+    let bounds = VLine::new(-5.0).get_bounds();
+    assert_eq!((bounds.min[0], bounds.max[0]), (-5.0, -5.0));
+    assert_eq!(bounds.min[1], f64::INFINITY);
+    assert_eq!(bounds.max[1], f64::NEG_INFINITY);
+}
@@ -0,0 +1,477 @@
+use std::f64::consts::TAU;
+
+use epaint::ahash::AHashSet;
+
+use crate::emath::{self, Align, Align2};
+use crate::epaint::{Color32, Mesh, Shape, Stroke};
+use crate::{vec2, Pos2, TextStyle, Ui};
+
+use super::values::{ClosestElem, PlotGeometry};
+use super::{highlighted_color, LabelFormatter, PlotConfig, PlotItem, Value};
+use crate::plot::{PlotBounds, ScreenTransform};
+
+/// One slice of a [`PieChart`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slice {
+    /// Name of the slice, shown next to it and in the legend.
+    pub name: String,
+
+    /// Size of the slice, relative to the sum of all slices. Negative values count as zero.
+    pub value: f64,
+
+    /// How far the slice is pulled out of the pie, as a fraction of the radius.
+    pub explode: f64,
+
+    /// Line width and color
+    pub stroke: Stroke,
+
+    /// Fill color
+    pub fill: Color32,
+}
+
+impl Slice {
+    /// Create a slice of this size.
+    pub fn new(value: f64) -> Self {
+        Self {
+            name: Default::default(),
+            value,
+            explode: 0.0,
+            stroke: Stroke::new(1.0, Color32::TRANSPARENT),
+            fill: Color32::TRANSPARENT,
+        }
+    }
+
+    /// Name of this slice.
+    ///
+    /// This name will show up next to the slice and in the plot legend, if legends are turned on.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Set the stroke and fill color. Default is `Color32::TRANSPARENT` which means a color will be auto-assigned.
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        let color = color.into();
+        self.fill = color.linear_multiply(0.5);
+        self.stroke.color = color;
+        self
+    }
+
+    /// Add a custom stroke.
+    pub fn stroke(mut self, stroke: impl Into<Stroke>) -> Self {
+        self.stroke = stroke.into();
+        self
+    }
+
+    /// Add a custom fill color.
+    pub fn fill(mut self, color: impl Into<Color32>) -> Self {
+        self.fill = color.into();
+        self
+    }
+
+    /// Pull the slice out of the pie by this fraction of the radius.
+    pub fn explode(mut self, explode: f64) -> Self {
+        self.explode = explode;
+        self
+    }
+}
+
+type SliceFormatterFn = dyn Fn(&Slice, &PieChart) -> String;
+
+/// A pie chart, or a donut chart with [`Self::donut`], made of [`Slice`]s.
+///
+/// Each named slice has its own entry in the legend.
+/// The pie is round in plot coordinates, so use [`Plot::data_aspect`](super::super::Plot::data_aspect)
+/// to keep it round on screen:
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{Legend, PieChart, Plot, Slice};
+/// let pie = PieChart::new(vec![
+///     Slice::new(3.0).name("Rent"),
+///     Slice::new(1.0).name("Food"),
+///     Slice::new(0.5).name("Fun").explode(0.1),
+/// ])
+/// .donut(0.5);
+/// Plot::new("budget")
+///     .data_aspect(1.0)
+///     .legend(Legend::default())
+///     .show(ui, |plot_ui| plot_ui.pie_chart(pie));
+/// # });
+/// ```
+pub struct PieChart {
+    pub(crate) slices: Vec<Slice>,
+    center: Value,
+    radius: f64,
+    hole: f64,
+    start_angle: f64,
+    labels: bool,
+    name: String,
+    /// A custom element formatter
+    element_formatter: Option<Box<SliceFormatterFn>>,
+    highlight: bool,
+    /// The slices of the hovered legend entry.
+    highlighted_name: Option<String>,
+}
+
+impl PieChart {
+    /// Create a pie chart with radius 1 around the origin, starting at the top and going clockwise.
+    pub fn new(slices: Vec<Slice>) -> Self {
+        Self {
+            slices,
+            center: Value::new(0.0, 0.0),
+            radius: 1.0,
+            hole: 0.0,
+            start_angle: TAU / 4.0,
+            labels: true,
+            name: String::new(),
+            element_formatter: None,
+            highlight: false,
+            highlighted_name: None,
+        }
+    }
+
+    /// Center of the pie, in plot coordinates. Default is the origin.
+    pub fn center(mut self, center: Value) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Radius of the pie, in plot coordinates. Default is 1.
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Cut a hole in the middle of the pie, this fraction of the radius wide. Default is 0.
+    pub fn donut(mut self, hole: f64) -> Self {
+        self.hole = hole.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Where the first slice starts, in radians counterclockwise from the positive x axis.
+    /// Default is the top, `TAU / 4`.
+    pub fn start_angle(mut self, angle: f64) -> Self {
+        self.start_angle = angle;
+        self
+    }
+
+    /// Show the names of the slices next to them. Default is `true`.
+    pub fn labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Name of this chart, shown when a slice is hovered.
+    ///
+    /// The legend shows the names of the slices. Only a chart without named slices has an entry of its own.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Add a custom way to format a slice when it is hovered.
+    pub fn element_formatter(mut self, formatter: Box<SliceFormatterFn>) -> Self {
+        self.element_formatter = Some(formatter);
+        self
+    }
+
+    /// The share of the slice in the whole pie, from 0 to 1.
+    pub fn fraction(&self, slice: &Slice) -> f64 {
+        let total: f64 = self.slices.iter().map(|slice| slice.value.max(0.0)).sum();
+        if total > 0.0 {
+            slice.value.max(0.0) / total
+        } else {
+            0.0
+        }
+    }
+
+    /// The angles each slice starts and ends at, going clockwise.
+    fn angles(&self) -> Vec<(f64, f64)> {
+        let mut angle = self.start_angle;
+        self.slices
+            .iter()
+            .map(|slice| {
+                let start = angle;
+                angle -= TAU * self.fraction(slice);
+                (start, angle)
+            })
+            .collect()
+    }
+
+    /// The point at `angle` and `radius` from the center of a slice, in plot coordinates.
+    fn point(&self, slice: &Slice, (start, end): (f64, f64), angle: f64, radius: f64) -> Value {
+        let middle = 0.5 * (start + end);
+        let offset = slice.explode * self.radius;
+        Value::new(
+            self.center.x + offset * middle.cos() + radius * angle.cos(),
+            self.center.y + offset * middle.sin() + radius * angle.sin(),
+        )
+    }
+
+    fn is_highlighted(&self, slice: &Slice) -> bool {
+        self.highlight || self.highlighted_name.as_deref() == Some(slice.name.as_str())
+    }
+
+    fn add_slice_shapes(
+        &self,
+        index: usize,
+        angles: (f64, f64),
+        transform: &ScreenTransform,
+        highlighted: bool,
+        shapes: &mut Vec<Shape>,
+    ) {
+        let slice = &self.slices[index];
+        let (stroke, fill) = if highlighted {
+            highlighted_color(slice.stroke, slice.fill)
+        } else {
+            (slice.stroke, slice.fill)
+        };
+        let (start, end) = angles;
+        let steps = ((start - end) / TAU * 128.0).ceil().max(1.0) as usize;
+        let inner_radius = self.hole * self.radius;
+
+        let mut mesh = Mesh::default();
+        let mut outer = Vec::with_capacity(steps + 1);
+        let mut inner = Vec::with_capacity(steps + 1);
+        for step in 0..=steps {
+            let angle = emath::lerp(start..=end, step as f64 / steps as f64);
+            let outer_pos = self.point(slice, angles, angle, self.radius);
+            let inner_pos = self.point(slice, angles, angle, inner_radius);
+            outer.push(transform.position_from_value(&outer_pos));
+            inner.push(transform.position_from_value(&inner_pos));
+            mesh.colored_vertex(*outer.last().unwrap(), fill);
+            mesh.colored_vertex(*inner.last().unwrap(), fill);
+            if step > 0 {
+                let i = 2 * step as u32;
+                mesh.add_triangle(i - 2, i - 1, i);
+                mesh.add_triangle(i - 1, i, i + 1);
+            }
+        }
+        shapes.push(Shape::mesh(mesh));
+
+        if stroke.width > 0.0 {
+            let mut outline = outer;
+            if self.hole > 0.0 {
+                outline.extend(inner.into_iter().rev());
+            } else {
+                outline.push(inner[0]);
+            }
+            shapes.push(Shape::closed_line(outline, stroke));
+        }
+    }
+
+    /// The name of the slice next to it, away from the center.
+    fn add_label(
+        &self,
+        ui: &Ui,
+        index: usize,
+        angles: (f64, f64),
+        transform: &ScreenTransform,
+        shapes: &mut Vec<Shape>,
+    ) {
+        let slice = &self.slices[index];
+        if slice.name.is_empty() || angles.0 == angles.1 {
+            return;
+        }
+        let middle = 0.5 * (angles.0 + angles.1);
+        let pos = self.point(slice, angles, middle, 1.05 * self.radius);
+        let side = |cos: f64| {
+            if cos > 0.3 {
+                Align::Min
+            } else if cos < -0.3 {
+                Align::Max
+            } else {
+                Align::Center
+            }
+        };
+        // The y axis of the screen points down:
+        let align = Align2([side(middle.cos()), side(-middle.sin())]);
+        shapes.push(Shape::text(
+            &ui.fonts(),
+            transform.position_from_value(&pos),
+            align,
+            &slice.name,
+            TextStyle::Body.resolve(ui.style()),
+            ui.visuals().text_color(),
+        ));
+    }
+
+    fn default_element_format(&self, slice: &Slice) -> String {
+        let mut text = String::new();
+        for name in [&self.name, &slice.name] {
+            if !name.is_empty() {
+                text += name;
+                text += "\n";
+            }
+        }
+        text += &format!(
+            "{} ({:.1}%)",
+            emath::round_to_decimals(slice.value, 3),
+            100.0 * self.fraction(slice)
+        );
+        text
+    }
+}
+
+impl PlotItem for PieChart {
+    fn get_shapes(&self, ui: &mut Ui, transform: &ScreenTransform, shapes: &mut Vec<Shape>) {
+        for (index, angles) in self.angles().into_iter().enumerate() {
+            let highlighted = self.is_highlighted(&self.slices[index]);
+            self.add_slice_shapes(index, angles, transform, highlighted, shapes);
+            if self.labels {
+                self.add_label(ui, index, angles, transform, shapes);
+            }
+        }
+    }
+
+    fn initialize(&mut self, _transform: &ScreenTransform) {
+        // nothing to do
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn color(&self) -> Color32 {
+        Color32::TRANSPARENT
+    }
+
+    fn highlight(&mut self) {
+        self.highlight = true;
+    }
+
+    fn highlighted(&self) -> bool {
+        self.highlight
+    }
+
+    fn geometry(&self) -> PlotGeometry<'_> {
+        PlotGeometry::Rects
+    }
+
+    fn get_bounds(&self) -> PlotBounds {
+        let explode = self
+            .slices
+            .iter()
+            .map(|slice| slice.explode)
+            .fold(0.0, f64::max);
+        // Leave some room for the labels around the pie:
+        let labels = if self.labels { 0.4 } else { 0.0 };
+        let mut bounds = PlotBounds::NOTHING;
+        bounds.extend_with(&self.center);
+        bounds.expand_x(self.radius * (1.0 + explode + labels));
+        bounds.expand_y(self.radius * (1.0 + explode + labels));
+        bounds
+    }
+
+    fn legend_entries(&self) -> Vec<(&str, Color32)> {
+        let entries: Vec<_> = self
+            .slices
+            .iter()
+            .filter(|slice| !slice.name.is_empty())
+            .map(|slice| (slice.name.as_str(), slice.stroke.color))
+            .collect();
+        if entries.is_empty() {
+            vec![(self.name(), self.color())]
+        } else {
+            entries
+        }
+    }
+
+    fn highlight_entry(&mut self, name: &str) {
+        if self.slices.iter().any(|slice| slice.name == name) {
+            self.highlighted_name = Some(name.to_owned());
+        } else if self.name == name {
+            self.highlight = true;
+        }
+    }
+
+    fn hide_entries(&mut self, hidden: &AHashSet<String>) -> bool {
+        self.slices.retain(|slice| !hidden.contains(&slice.name));
+        !self.slices.is_empty() && !hidden.contains(&self.name)
+    }
+
+    fn find_closest(&self, point: Pos2, transform: &ScreenTransform) -> Option<ClosestElem> {
+        let value = transform.value_from_position(point);
+        let inner_radius = self.hole * self.radius;
+        self.angles()
+            .into_iter()
+            .enumerate()
+            .find(|&(index, (start, end))| {
+                let center = self.point(&self.slices[index], (start, end), 0.0, 0.0);
+                let (dx, dy) = (value.x - center.x, value.y - center.y);
+                let radius = dx.hypot(dy);
+                // The angle of the point, in the turn that ends at the start of the slice:
+                let angle = start - (start - dy.atan2(dx)).rem_euclid(TAU);
+                (inner_radius..=self.radius).contains(&radius) && angle >= end && start > end
+            })
+            .map(|(index, _)| ClosestElem {
+                index,
+                dist_sq: 0.0,
+            })
+    }
+
+    fn on_hover(
+        &self,
+        elem: ClosestElem,
+        shapes: &mut Vec<Shape>,
+        plot: &PlotConfig<'_>,
+        _: &LabelFormatter,
+    ) {
+        let slice = &self.slices[elem.index];
+        let angles = self.angles()[elem.index];
+        self.add_slice_shapes(elem.index, angles, plot.transform, true, shapes);
+
+        let text = match &self.element_formatter {
+            Some(formatter) => formatter(slice, self),
+            None => self.default_element_format(slice),
+        };
+        let middle = 0.5 * (angles.0 + angles.1);
+        let radius = 0.5 * (1.0 + self.hole) * self.radius;
+        let pos = self.point(slice, angles, middle, radius);
+        shapes.push(Shape::text(
+            &plot.ui.fonts(),
+            plot.transform.position_from_value(&pos) + vec2(3.0, -2.0),
+            Align2::LEFT_BOTTOM,
+            text,
+            TextStyle::Body.resolve(plot.ui.style()),
+            plot.ui.visuals().text_color(),
+        ));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_pie_chart() {
+    let mut pie = PieChart::new(vec![
+        Slice::new(1.0).name("a"),
+        Slice::new(2.0).name("b"),
+        Slice::new(1.0).name("c").explode(0.5),
+    ])
+    .donut(0.5);
+    let transform = ScreenTransform::new(
+        crate::Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0)),
+        pie.get_bounds(),
+        false,
+        false,
+    );
+    let closest = |pie: &PieChart, value: Value| {
+        pie.find_closest(transform.position_from_value(&value), &transform)
+            .map(|elem| pie.slices[elem.index].name.clone())
+    };
+
+    // Clockwise from the top: "a" takes the top right quarter, "b" the bottom half.
+    assert_eq!(closest(&pie, Value::new(0.5, 0.5)), Some("a".to_owned()));
+    assert_eq!(closest(&pie, Value::new(0.0, -0.75)), Some("b".to_owned()));
+    assert_eq!(closest(&pie, Value::new(0.0, 0.0)), None, "The hole");
+    // "c" is pulled out to the top left:
+    assert_eq!(closest(&pie, Value::new(-1.0, 1.0)), Some("c".to_owned()));
+    assert_eq!(pie.legend_entries().len(), 3);
+
+    // Hiding a slice makes room for the others:
+    let hidden = std::iter::once("b".to_owned()).collect();
+    assert!(pie.hide_entries(&hidden));
+    assert_eq!(pie.fraction(&pie.slices[0]), 0.5);
+    assert_eq!(closest(&pie, Value::new(0.1, -0.75)), Some("a".to_owned()));
+}
@@ -1,7 +1,10 @@
 use epaint::{Pos2, Shape, Stroke, Vec2};
-use std::ops::{Bound, RangeBounds, RangeInclusive};
+use std::collections::VecDeque;
+use std::ops::{Bound, Range, RangeBounds, RangeInclusive};
+use std::sync::Arc;
 
-use crate::plot::transform::PlotBounds;
+use super::decimation::Decimation;
+use crate::plot::transform::{PlotBounds, ScreenTransform};
 
 /// A value in the value-space of the plot.
 ///
@@ -142,8 +145,11 @@ impl Default for Orientation {
 
 #[derive(Default)]
 pub struct Values {
-    pub(super) values: Vec<Value>,
+    pub(crate) values: Vec<Value>,
     generator: Option<ExplicitGenerator>,
+    sorted_source: Option<SortedSource>,
+    /// The index in the whole series of `values[0]`, if `values` are consecutive values of it.
+    first_index: Option<usize>,
 }
 
 impl Values {
@@ -151,9 +157,65 @@ impl Values {
         Self {
             values,
             generator: None,
+            sorted_source: None,
+            first_index: Some(0),
         }
     }
 
+    /// Values that are sorted by x, shared with the caller instead of being copied.
+    ///
+    /// Keep the [`Arc`] around between frames: only the visible values are read,
+    /// and for a [`Line`](super::Line) or [`Points`](super::Points) only a few per column of the plot,
+    /// see [`Decimation`]. Give the [`Self::y_range`] if you know it, so that the automatic plot bounds
+    /// don't read all values.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui::plot::{Line, Plot, Value, Values};
+    /// use std::sync::Arc;
+    /// let samples: Arc<[Value]> = (0..100_000)
+    ///     .map(|i| Value::new(i as f64, (i as f64 * 0.01).sin()))
+    ///     .collect();
+    /// Plot::new("sensor").show(ui, |plot_ui| {
+    ///     plot_ui.line(Line::new(Values::from_sorted(samples.clone()).y_range(-1.0..=1.0)));
+    /// });
+    /// # });
+    /// ```
+    pub fn from_sorted(values: impl Into<Arc<[Value]>>) -> Self {
+        let values: Arc<[Value]> = values.into();
+        let len = values.len();
+        Self::from_sorted_callback(move |index| values[index], len)
+    }
+
+    /// Values that are read on demand with `function(index)` for `index` in `0..len`.
+    ///
+    /// The values must be sorted by x. Like [`Self::from_sorted`], only the visible ones are read,
+    /// except when the plot bounds are set automatically and [`Self::y_range`] isn't given,
+    /// which reads all of them to find their y range.
+    pub fn from_sorted_callback(function: impl Fn(usize) -> Value + 'static, len: usize) -> Self {
+        Self {
+            values: Vec::new(),
+            generator: None,
+            sorted_source: Some(SortedSource {
+                function: Box::new(function),
+                len,
+                y_range: None,
+            }),
+            first_index: None,
+        }
+    }
+
+    /// The smallest and largest y of the values from [`Self::from_sorted`] or [`Self::from_sorted_callback`].
+    ///
+    /// The automatic plot bounds take the x range from the first and last value, and the y range from this,
+    /// so that large data sets don't have to be read every frame. Ignored for other values.
+    pub fn y_range(mut self, y_range: RangeInclusive<f64>) -> Self {
+        if let Some(source) = &mut self.sorted_source {
+            source.y_range = Some((*y_range.start(), *y_range.end()));
+        }
+        self
+    }
+
     pub fn from_values_iter(iter: impl Iterator<Item = Value>) -> Self {
         Self::from_values(iter.collect())
     }
@@ -183,6 +245,8 @@ impl Values {
         Self {
             values: Vec::new(),
             generator: Some(generator),
+            sorted_source: None,
+            first_index: None,
         }
     }
 
@@ -232,13 +296,24 @@ impl Values {
 
     /// Returns true if there are no data points available and there is no function to generate any.
     pub(crate) fn is_empty(&self) -> bool {
-        self.generator.is_none() && self.values.is_empty()
+        self.generator.is_none()
+            && self
+                .sorted_source
+                .as_ref()
+                .map_or(true, |source| source.len == 0)
+            && self.values.is_empty()
     }
 
     /// If initialized with a generator function, this will generate `n` evenly spaced points in the
-    /// given range.
+    /// given range. If initialized with sorted values, this will read the ones in the given range.
+    ///
+    /// The source is kept, so that [`Self::select`] can count all of its values.
     pub(super) fn generate_points(&mut self, x_range: RangeInclusive<f64>) {
-        if let Some(generator) = self.generator.take() {
+        if let Some(source) = &self.sorted_source {
+            let indices = source.visible_indices(&x_range);
+            self.first_index = Some(indices.start);
+            self.values = indices.map(|index| (source.function)(index)).collect();
+        } else if let Some(generator) = &self.generator {
             if let Some(intersection) = Self::range_intersection(&x_range, &generator.x_range) {
                 let increment =
                     (intersection.end() - intersection.start()) / (generator.points - 1) as f64;
@@ -253,6 +328,49 @@ impl Values {
         }
     }
 
+    /// Like [`Self::generate_points`] for the visible x range of the transform,
+    /// but then reduces the values to a few per column of the plot, if they are sorted by x.
+    pub(super) fn generate_decimated_points(
+        &mut self,
+        transform: &ScreenTransform,
+        decimation: Decimation,
+    ) {
+        let x_range = transform.bounds().range_x();
+        let columns = transform.frame().width().ceil().max(1.0) as usize;
+
+        if let Some(source) = &self.sorted_source {
+            let indices = source.visible_indices(&x_range);
+            if decimation.is_needed(indices.len(), columns) {
+                self.values = decimation.decimate(&source.function, indices, &x_range, columns);
+                self.first_index = None;
+                return;
+            }
+        }
+
+        self.generate_points(x_range.clone());
+        let is_sorted = self.values.windows(2).all(|w| w[0].x <= w[1].x);
+        if self.sorted_source.is_none()
+            && decimation.is_needed(self.values.len(), columns)
+            && is_sorted
+        {
+            let values = std::mem::take(&mut self.values);
+            self.values =
+                decimation.decimate(&|index| values[index], 0..values.len(), &x_range, columns);
+            self.first_index = None;
+            // Keep all values for `Self::select`:
+            let len = values.len();
+            let mut bounds = PlotBounds::NOTHING;
+            values
+                .iter()
+                .for_each(|value| bounds.extend_with_y(value.y));
+            self.sorted_source = Some(SortedSource {
+                function: Box::new(move |index| values[index]),
+                len,
+                y_range: Some((bounds.min()[1], bounds.max()[1])),
+            });
+        }
+    }
+
     /// Returns the intersection of two ranges if they intersect.
     fn range_intersection(
         range1: &RangeInclusive<f64>,
@@ -263,8 +381,62 @@ impl Values {
         (start < end).then(|| start..=end)
     }
 
+    /// The index in the whole series of `self.values[index]`.
+    ///
+    /// `None` if the values were generated by a function or decimated, so they aren't values of the series.
+    pub(super) fn series_index(&self, index: usize) -> Option<usize> {
+        self.first_index.map(|first_index| first_index + index)
+    }
+
+    /// The value at `index` of the whole series, whether it is visible or not.
+    ///
+    /// `None` for values generated by a function, which don't have a fixed series.
+    pub(super) fn series_value(&self, index: usize) -> Option<Value> {
+        if let Some(source) = &self.sorted_source {
+            (index < source.len).then(|| (source.function)(index))
+        } else if self.first_index == Some(0) {
+            self.values.get(index).copied()
+        } else {
+            None
+        }
+    }
+
+    /// The indices of the values inside `bounds`, counting all values rather than the decimated ones.
+    ///
+    /// For values from a generator function, these are the indices of the generated values.
+    pub(super) fn select(&self, bounds: &PlotBounds) -> Vec<usize> {
+        if let Some(source) = &self.sorted_source {
+            source
+                .visible_indices(&bounds.range_x())
+                .filter(|&index| bounds.contains(&(source.function)(index)))
+                .collect()
+        } else {
+            (0..self.values.len())
+                .filter(|&index| bounds.contains(&self.values[index]))
+                .collect()
+        }
+    }
+
     pub(super) fn get_bounds(&self) -> PlotBounds {
-        if self.values.is_empty() {
+        if let Some(source) = &self.sorted_source {
+            let mut bounds = PlotBounds::NOTHING;
+            if let Some(last) = source.len.checked_sub(1) {
+                bounds.extend_with_x((source.function)(0).x);
+                bounds.extend_with_x((source.function)(last).x);
+                match source.y_range {
+                    Some((min, max)) => {
+                        bounds.extend_with_y(min);
+                        bounds.extend_with_y(max);
+                    }
+                    None => {
+                        for index in 0..source.len {
+                            bounds.extend_with_y((source.function)(index).y);
+                        }
+                    }
+                }
+            }
+            bounds
+        } else if self.values.is_empty() {
             if let Some(generator) = &self.generator {
                 generator.estimate_bounds()
             } else {
@@ -282,6 +454,160 @@ impl Values {
 
 // ----------------------------------------------------------------------------
 
+/// A ring buffer of values sorted by x, for data that streams in, e.g. from a sensor.
+///
+/// Once it is full, pushing a value drops the oldest one. Keep it around between frames and
+/// plot it with [`Self::values`], which shares the buffer instead of copying it.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::plot::{Line, Plot, StreamingValues, Value};
+/// let mut samples = StreamingValues::new(10_000);
+/// // Every frame:
+/// let time = ui.input().time;
+/// samples.push(Value::new(time, time.sin()));
+/// Plot::new("live").follow_x(10.0).show(ui, |plot_ui| {
+///     plot_ui.line(Line::new(samples.values()));
+/// });
+/// # });
+/// ```
+#[derive(Clone, Default)]
+pub struct StreamingValues {
+    /// Shared with the [`Values`] of the last frame, and only copied on write if they are still around.
+    buffer: Arc<Vec<Value>>,
+    capacity: usize,
+    /// The index of the oldest value in `buffer`, once it is full.
+    start: usize,
+    /// How many values have been pushed since the buffer was made or cleared.
+    pushed: u64,
+    /// The values that are smaller than all values pushed after them, oldest first,
+    /// as the number of their push and their y. The first is the smallest in the buffer.
+    y_min: VecDeque<(u64, f64)>,
+    /// Like `y_min`, for the largest value.
+    y_max: VecDeque<(u64, f64)>,
+}
+
+impl StreamingValues {
+    /// A buffer that holds up to `capacity` values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: Arc::new(Vec::with_capacity(capacity)),
+            capacity,
+            start: 0,
+            pushed: 0,
+            y_min: VecDeque::new(),
+            y_max: VecDeque::new(),
+        }
+    }
+
+    /// The maximum number of values.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Add a value, dropping the oldest one if the buffer is full.
+    ///
+    /// The value should not have a smaller x than the ones before it.
+    pub fn push(&mut self, value: Value) {
+        if self.capacity == 0 {
+            return;
+        }
+        let buffer = Arc::make_mut(&mut self.buffer);
+        if buffer.len() < self.capacity {
+            buffer.push(value);
+        } else {
+            buffer[self.start] = value;
+            self.start = (self.start + 1) % self.capacity;
+        }
+
+        let number = self.pushed;
+        self.pushed += 1;
+        if !value.y.is_nan() {
+            while self.y_min.back().map_or(false, |&(_, y)| y >= value.y) {
+                self.y_min.pop_back();
+            }
+            self.y_min.push_back((number, value.y));
+            while self.y_max.back().map_or(false, |&(_, y)| y <= value.y) {
+                self.y_max.pop_back();
+            }
+            self.y_max.push_back((number, value.y));
+        }
+        // Forget the ones that have been dropped from the buffer:
+        let oldest = self.pushed.saturating_sub(self.capacity as u64);
+        for extremes in [&mut self.y_min, &mut self.y_max] {
+            while extremes
+                .front()
+                .map_or(false, |&(number, _)| number < oldest)
+            {
+                extremes.pop_front();
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        Arc::make_mut(&mut self.buffer).clear();
+        self.start = 0;
+        self.pushed = 0;
+        self.y_min.clear();
+        self.y_max.clear();
+    }
+
+    /// The value at `index`, from oldest to newest.
+    pub fn get(&self, index: usize) -> Option<Value> {
+        (index < self.len()).then(|| self.buffer[(self.start + index) % self.len()])
+    }
+
+    /// The newest value.
+    pub fn last(&self) -> Option<Value> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// The values from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = Value> + '_ {
+        let (newer, older) = self.buffer.split_at(self.start);
+        older.iter().chain(newer).copied()
+    }
+
+    /// The smallest and largest y of the values, kept up to date as they are pushed.
+    pub fn y_range(&self) -> Option<RangeInclusive<f64>> {
+        let (_, min) = self.y_min.front()?;
+        let (_, max) = self.y_max.front()?;
+        Some(*min..=*max)
+    }
+
+    /// The values to plot, sharing the buffer.
+    ///
+    /// They come with their [`Values::y_range`], so the automatic plot bounds don't read them all.
+    pub fn values(&self) -> Values {
+        let buffer = self.buffer.clone();
+        let start = self.start;
+        let len = buffer.len();
+        let values = Values::from_sorted_callback(move |index| buffer[(start + index) % len], len);
+        match self.y_range() {
+            Some(y_range) => values.y_range(y_range),
+            None => values,
+        }
+    }
+}
+
+impl Extend<Value> for StreamingValues {
+    fn extend<T: IntoIterator<Item = Value>>(&mut self, iter: T) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum MarkerShape {
     Circle,
@@ -357,6 +683,40 @@ impl ExplicitGenerator {
 
 // ----------------------------------------------------------------------------
 
+/// Values sorted by x, which are read on demand.
+struct SortedSource {
+    function: Box<dyn Fn(usize) -> Value>,
+    len: usize,
+    /// The smallest and largest y of the values, if known, so that they aren't all read for the bounds.
+    y_range: Option<(f64, f64)>,
+}
+
+impl SortedSource {
+    /// The indices of the values in the x range, and of the closest value on either side of it,
+    /// so that a line through them continues out of view.
+    fn visible_indices(&self, x_range: &RangeInclusive<f64>) -> Range<usize> {
+        let start = self.partition_point(|value| value.x < *x_range.start());
+        let end = self.partition_point(|value| value.x <= *x_range.end());
+        start.saturating_sub(1)..(end + 1).min(self.len)
+    }
+
+    /// The index of the first value for which `pred` is false, assuming it is true for all values before it.
+    fn partition_point(&self, pred: impl Fn(&Value) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if pred(&(self.function)(mid)) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 /// Result of [`super::PlotItem::find_closest()`] search, identifies an element inside the item for immediate use
 pub(crate) struct ClosestElem {
     /// Position of hovered-over value (or bar/box-plot/...) in PlotItem
@@ -365,3 +725,94 @@ pub(crate) struct ClosestElem {
     /// Squared distance from the mouse cursor (needed to compare against other PlotItems, which might be nearer)
     pub dist_sq: f32,
 }
+
+#[cfg(test)]
+#[test]
+fn test_streaming_values() {
+    let mut stream = StreamingValues::new(3);
+    stream.extend((0..5).map(|i| Value::new(i, i * 10)));
+    assert_eq!(stream.len(), 3);
+    let xs: Vec<f64> = stream.iter().map(|v| v.x).collect();
+    assert_eq!(xs, [2.0, 3.0, 4.0]);
+    assert_eq!(stream.last(), Some(Value::new(4, 40)));
+
+    let mut values = stream.values();
+    // Writing after plotting copies the buffer instead of changing the plotted values:
+    stream.push(Value::new(5, 50));
+    values.generate_points(2.5..=10.0);
+    assert_eq!(
+        values.values,
+        [Value::new(2, 20), Value::new(3, 30), Value::new(4, 40)]
+    );
+    assert_eq!(stream.get(0), Some(Value::new(3, 30)));
+}
+
+#[cfg(test)]
+#[test]
+fn test_sorted_bounds_without_reading_all_values() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let reads = Rc::new(Cell::new(0));
+    let counted = |reads: &Rc<Cell<usize>>| {
+        let reads = reads.clone();
+        move |index: usize| {
+            reads.set(reads.get() + 1);
+            Value::new(index as f64, (index as f64 * 0.1).sin())
+        }
+    };
+
+    let values = Values::from_sorted_callback(counted(&reads), 1_000_000).y_range(-1.0..=1.0);
+    let bounds = values.get_bounds();
+    assert_eq!(bounds.min(), [0.0, -1.0]);
+    assert_eq!(bounds.max(), [999_999.0, 1.0]);
+    assert_eq!(reads.get(), 2, "only the first and the last value");
+
+    // Without the y range all values are read:
+    reads.set(0);
+    let bounds = Values::from_sorted_callback(counted(&reads), 1_000).get_bounds();
+    assert_eq!(reads.get(), 1_002);
+    assert!(bounds.min()[1] < -0.99 && bounds.max()[1] > 0.99);
+
+    assert!(!Values::from_sorted_callback(counted(&reads), 0)
+        .get_bounds()
+        .is_valid());
+}
+
+#[cfg(test)]
+#[test]
+fn test_streaming_y_range() {
+    let mut stream = StreamingValues::new(3);
+    assert_eq!(stream.y_range(), None);
+
+    // Rising, so the smallest value drops out with every push:
+    for (i, y) in [1.0, 2.0, 3.0, 4.0, 5.0].into_iter().enumerate() {
+        stream.push(Value::new(i as f64, y));
+    }
+    assert_eq!(stream.y_range(), Some(3.0..=5.0));
+
+    for (i, y) in [-1.0, 0.0, f64::NAN, 2.0].into_iter().enumerate() {
+        stream.push(Value::new((5 + i) as f64, y));
+    }
+    assert_eq!(stream.y_range(), Some(0.0..=2.0));
+
+    // Brute force against random values:
+    let mut stream = StreamingValues::new(10);
+    let mut y = 0.0_f64;
+    for i in 0..1_000 {
+        y = (y * 7.3 + 1.1) % 17.0 - 3.0;
+        stream.push(Value::new(i as f64, y));
+        let (min, max) = stream
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (min.min(value.y), max.max(value.y))
+            });
+        assert_eq!(stream.y_range(), Some(min..=max));
+    }
+
+    let bounds = stream.values().get_bounds();
+    assert_eq!(bounds.min(), [990.0, *stream.y_range().unwrap().start()]);
+
+    stream.clear();
+    assert_eq!(stream.y_range(), None);
+}
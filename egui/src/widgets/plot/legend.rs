@@ -1,10 +1,10 @@
-use std::{collections::BTreeMap, string::String};
+use std::{collections::BTreeMap, ops::RangeInclusive, string::String};
 
 use epaint::ahash::AHashSet;
 
 use crate::*;
 
-use super::items::PlotItem;
+use super::items::{Colormap, PlotItem};
 
 /// Where to place the plot legend.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -69,14 +69,21 @@ impl Legend {
 #[derive(Clone)]
 struct LegendEntry {
     color: Color32,
+    /// Shown below the name, for items colored by value.
+    colorbar: Option<(Colormap, RangeInclusive<f64>)>,
     checked: bool,
     hovered: bool,
 }
 
 impl LegendEntry {
-    fn new(color: Color32, checked: bool) -> Self {
+    fn new(
+        color: Color32,
+        colorbar: Option<(Colormap, RangeInclusive<f64>)>,
+        checked: bool,
+    ) -> Self {
         Self {
             color,
+            colorbar,
             checked,
             hovered: false,
         }
@@ -85,6 +92,7 @@ impl LegendEntry {
     fn ui(&mut self, ui: &mut Ui, text: String, text_style: &TextStyle) -> Response {
         let Self {
             color,
+            colorbar,
             checked,
             hovered,
         } = self;
@@ -93,14 +101,21 @@ impl LegendEntry {
 
         let galley = ui
             .fonts()
-            .layout_delayed_color(text, font_id, f32::INFINITY);
+            .layout_delayed_color(text, font_id.clone(), f32::INFINITY);
 
         let icon_size = galley.size().y;
         let icon_spacing = icon_size / 5.0;
         let total_extra = vec2(icon_size + icon_spacing, 0.0);
 
-        let desired_size = total_extra + galley.size();
+        let mut desired_size = total_extra + galley.size();
+        // The colorbar goes below the name, with its lowest and highest values below it:
+        let colorbar_height = 0.5 * icon_size;
+        if colorbar.is_some() {
+            desired_size.x = desired_size.x.at_least(6.0 * icon_size);
+            desired_size.y += 2.0 * icon_spacing + colorbar_height + galley.size().y;
+        }
         let (rect, response) = ui.allocate_exact_size(desired_size, Sense::click());
+        let row_center_y = rect.top() + 0.5 * galley.size().y;
 
         response
             .widget_info(|| WidgetInfo::selected(WidgetType::Checkbox, *checked, galley.text()));
@@ -113,7 +128,7 @@ impl LegendEntry {
         } else {
             rect.left() + icon_size / 2.0
         };
-        let icon_position = pos2(icon_position_x, rect.center().y);
+        let icon_position = pos2(icon_position_x, row_center_y);
         let icon_rect = Rect::from_center_size(icon_position, vec2(icon_size, icon_size));
 
         let painter = ui.painter();
@@ -144,7 +159,33 @@ impl LegendEntry {
             rect.left() + icon_size + icon_spacing
         };
 
-        let text_position = pos2(text_position_x, rect.center().y - 0.5 * galley.size().y);
+        if let Some((colormap, range)) = colorbar {
+            let bar_rect = Rect::from_min_size(
+                pos2(rect.left(), rect.top() + galley.size().y + icon_spacing),
+                vec2(rect.width(), colorbar_height),
+            );
+            painter.add(colorbar_mesh(bar_rect, colormap));
+            painter.rect_stroke(bar_rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke);
+
+            let label_y = bar_rect.bottom() + icon_spacing;
+            let label = |value: f64| emath::round_to_decimals(value, 3).to_string();
+            painter.text(
+                pos2(bar_rect.left(), label_y),
+                Align2::LEFT_TOP,
+                label(*range.start()),
+                font_id.clone(),
+                visuals.text_color(),
+            );
+            painter.text(
+                pos2(bar_rect.right(), label_y),
+                Align2::RIGHT_TOP,
+                label(*range.end()),
+                font_id,
+                visuals.text_color(),
+            );
+        }
+
+        let text_position = pos2(text_position_x, row_center_y - 0.5 * galley.size().y);
         painter.galley_with_color(text_position, galley, visuals.text_color());
 
         *checked ^= response.clicked_by(PointerButton::Primary);
@@ -154,6 +195,25 @@ impl LegendEntry {
     }
 }
 
+/// A horizontal gradient through the colormap, from its lowest value on the left.
+fn colorbar_mesh(rect: Rect, colormap: &Colormap) -> Shape {
+    const SEGMENTS: u32 = 32;
+    let mut mesh = epaint::Mesh::default();
+    for i in 0..=SEGMENTS {
+        let t = i as f32 / SEGMENTS as f32;
+        let x = emath::lerp(rect.x_range(), t);
+        let color = colormap.color_at(t);
+        mesh.colored_vertex(pos2(x, rect.top()), color);
+        mesh.colored_vertex(pos2(x, rect.bottom()), color);
+        if i > 0 {
+            let j = 2 * i;
+            mesh.add_triangle(j - 2, j - 1, j);
+            mesh.add_triangle(j - 1, j, j + 1);
+        }
+    }
+    Shape::mesh(mesh)
+}
+
 #[derive(Clone)]
 pub(super) struct LegendWidget {
     rect: Rect,
@@ -173,24 +233,29 @@ impl LegendWidget {
         // Collect the legend entries. If multiple items have the same name, they share a
         // checkbox. If their colors don't match, we pick a neutral color for the checkbox.
         let mut entries: BTreeMap<String, LegendEntry> = BTreeMap::new();
-        items
-            .iter()
-            .filter(|item| !item.name().is_empty())
-            .for_each(|item| {
+        for item in items {
+            for (name, color) in item.legend_entries() {
+                if name.is_empty() {
+                    continue;
+                }
                 entries
-                    .entry(item.name().to_string())
+                    .entry(name.to_string())
                     .and_modify(|entry| {
-                        if entry.color != item.color() {
+                        if entry.color != color {
                             // Multiple items with different colors
                             entry.color = Color32::TRANSPARENT;
                         }
                     })
                     .or_insert_with(|| {
-                        let color = item.color();
-                        let checked = !hidden_items.contains(item.name());
-                        LegendEntry::new(color, checked)
+                        let colorbar = item
+                            .colorbar()
+                            .filter(|_| name == item.name())
+                            .map(|(colormap, range)| (colormap.clone(), range));
+                        let checked = !hidden_items.contains(name);
+                        LegendEntry::new(color, colorbar, checked)
                     });
-            });
+            }
+        }
         (!entries.is_empty()).then(|| Self {
             rect,
             entries,
@@ -7,19 +7,31 @@ use epaint::ahash::AHashSet;
 use epaint::color::Hsva;
 use epaint::util::FloatOrd;
 
+use axis::AxisGutters;
+use interaction::{Brush, DragCandidate, ItemDrag};
 use items::PlotItem;
 use legend::LegendWidget;
 use transform::ScreenTransform;
 
+pub use axis::SecondaryYAxis;
+pub use export::{ExportFormat, PlotExport};
+pub use interaction::{BrushMode, PlotItemResponse, PlotSelection};
+
 pub use items::{
-    Arrows, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, HLine, Line, LineStyle, MarkerShape,
-    Orientation, PlotImage, Points, Polygon, Text, VLine, Value, Values,
+    AreaChart, Arrows, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, Colormap, Decimation, ErrorBars,
+    HLine, Heatmap, Line, LineStyle, MarkerShape, Orientation, PieChart, PlotImage, Points,
+    Polygon, Slice, StreamingValues, Text, VLine, Value, Values,
 };
 pub use legend::{Corner, Legend};
+pub use time_axis::{format_timestamp, time_axis_formatter, time_grid_spacer};
 pub use transform::PlotBounds;
 
+mod axis;
+mod export;
+mod interaction;
 mod items;
 mod legend;
+mod time_axis;
 mod transform;
 
 type LabelFormatterFn = dyn Fn(&str, &Value) -> String;
@@ -52,6 +64,21 @@ impl CoordinatesFormatter {
         }
     }
 
+    /// Show the x coordinate as a date and time (see [`Plot::time_x_axis`]),
+    /// and the y coordinate with a fixed number of decimal places.
+    pub fn time_x(num_decimals: usize) -> Self {
+        Self {
+            function: Box::new(move |value, bounds| {
+                format!(
+                    "x: {}\ny: {:.d$}",
+                    format_timestamp(value.x, bounds.width() / 1000.0),
+                    value.y,
+                    d = num_decimals
+                )
+            }),
+        }
+    }
+
     fn format(&self, value: &Value, bounds: &PlotBounds) -> String {
         (self.function)(value, bounds)
     }
@@ -67,6 +94,9 @@ impl Default for CoordinatesFormatter {
 
 const MIN_LINE_SPACING_IN_POINTS: f64 = 6.0; // TODO(emilk): large enough for a wide label
 
+/// How close the pointer has to be to an item to hover it.
+const HOVER_RADIUS: f32 = 16.0;
+
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[derive(Clone)]
 struct AutoBounds {
@@ -99,8 +129,11 @@ struct PlotMemory {
     hidden_items: AHashSet<String>,
     min_auto_bounds: PlotBounds,
     last_screen_transform: ScreenTransform,
+    last_secondary_transform: Option<ScreenTransform>,
     /// Allows to remember the first click position when performing a boxed zoom
     last_click_pos_for_zoom: Option<Pos2>,
+    item_drag: ItemDrag,
+    brush: Brush,
 }
 
 impl PlotMemory {
@@ -196,6 +229,7 @@ pub struct Plot {
     allow_drag: bool,
     allow_scroll: bool,
     min_auto_bounds: PlotBounds,
+    follow_x: Option<f64>,
     margin_fraction: Vec2,
     allow_boxed_zoom: bool,
     boxed_zoom_pointer_button: PointerButton,
@@ -212,10 +246,16 @@ pub struct Plot {
     label_formatter: LabelFormatter,
     coordinates_formatter: Option<(Corner, CoordinatesFormatter)>,
     axis_formatters: [AxisFormatter; 2],
+    axis_labels: [Option<WidgetText>; 2],
+    axis_gutters: bool,
+    secondary_y_axis: Option<SecondaryYAxis>,
+    time_x_axis: bool,
     legend_config: Option<Legend>,
     show_background: bool,
     show_axes: [bool; 2],
     grid_spacers: [GridSpacer; 2],
+    export_menu: bool,
+    brush_mode: Option<BrushMode>,
 }
 
 impl Plot {
@@ -230,6 +270,7 @@ impl Plot {
             allow_drag: true,
             allow_scroll: true,
             min_auto_bounds: PlotBounds::NOTHING,
+            follow_x: None,
             margin_fraction: Vec2::splat(0.05),
             allow_boxed_zoom: true,
             boxed_zoom_pointer_button: PointerButton::Secondary,
@@ -246,10 +287,16 @@ impl Plot {
             label_formatter: None,
             coordinates_formatter: None,
             axis_formatters: [None, None], // [None; 2] requires Copy
+            axis_labels: [None, None],
+            axis_gutters: false,
+            secondary_y_axis: None,
+            time_x_axis: false,
             legend_config: None,
             show_background: true,
             show_axes: [true; 2],
             grid_spacers: [log_grid_spacer(10), log_grid_spacer(10)],
+            export_menu: false,
+            brush_mode: None,
         }
     }
 
@@ -355,6 +402,30 @@ impl Plot {
         self
     }
 
+    /// Scroll the X axis to follow the latest values, showing the last `width` of it,
+    /// like an oscilloscope. The Y axis is fitted to all values as usual.
+    ///
+    /// Dragging or zooming the plot pauses following, and double-clicking it resumes.
+    /// See also [`PlotUi::set_following`].
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui::plot::{Line, Plot, StreamingValues, Value};
+    /// let mut samples = StreamingValues::new(1000);
+    /// for i in 0..2000 {
+    ///     let t = i as f64 * 0.01;
+    ///     samples.push(Value::new(t, t.sin()));
+    /// }
+    /// Plot::new("scope").follow_x(5.0).show(ui, |plot_ui| {
+    ///     plot_ui.line(Line::new(samples.values()));
+    /// });
+    /// # });
+    /// ```
+    pub fn follow_x(mut self, width: impl Into<f64>) -> Self {
+        self.follow_x = Some(width.into());
+        self
+    }
+
     /// Provide a function to customize the on-hovel label for the x and y axis
     ///
     /// ```
@@ -424,6 +495,64 @@ impl Plot {
         self
     }
 
+    /// Treat the X values as time stamps: seconds since 1970-01-01 00:00:00 UTC.
+    ///
+    /// The grid marks are put at round dates and times (see [`time_grid_spacer`]) and labeled
+    /// with [`time_axis_formatter`]. Hovering shows the X value as a date and time, see also
+    /// [`CoordinatesFormatter::time_x`] and [`format_timestamp`].
+    ///
+    /// Call [`Self::x_axis_formatter`] or [`Self::x_grid_spacer`] after this to replace either.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui::plot::{Line, Plot, Value, Values};
+    /// let start = 1_651_536_000.0; // 2022-05-03
+    /// let hourly = (0..48).map(|hour| Value::new(start + 3600.0 * hour as f64, hour as f64));
+    /// Plot::new("my_plot")
+    ///     .time_x_axis()
+    ///     .show(ui, |plot_ui| plot_ui.line(Line::new(Values::from_values_iter(hourly))));
+    /// # });
+    /// ```
+    pub fn time_x_axis(mut self) -> Self {
+        self.time_x_axis = true;
+        self.axis_formatters[0] = Some(Box::new(time_axis_formatter));
+        self.grid_spacers[0] = time_grid_spacer();
+        self
+    }
+
+    /// Title of the X axis, shown below the plot.
+    ///
+    /// This moves the tick labels outside the plot, see [`Self::axis_gutters`].
+    pub fn x_axis_label(mut self, label: impl Into<WidgetText>) -> Self {
+        self.axis_labels[0] = Some(label.into());
+        self
+    }
+
+    /// Title of the Y axis, shown to the left of the plot.
+    ///
+    /// This moves the tick labels outside the plot, see [`Self::axis_gutters`].
+    pub fn y_axis_label(mut self, label: impl Into<WidgetText>) -> Self {
+        self.axis_labels[1] = Some(label.into());
+        self
+    }
+
+    /// Show the tick labels of the axes in margins outside the plot area, instead of inside it.
+    ///
+    /// This is always done if an axis has a title, or with a [`Self::secondary_y_axis`].
+    /// Default: `false`.
+    pub fn axis_gutters(mut self, on: bool) -> Self {
+        self.axis_gutters = on;
+        self
+    }
+
+    /// Add a second Y axis on the right-hand side of the plot, with its own bounds and labels.
+    ///
+    /// Add items to it with [`PlotUi::on_secondary_y_axis`].
+    pub fn secondary_y_axis(mut self, axis: SecondaryYAxis) -> Self {
+        self.secondary_y_axis = Some(axis);
+        self
+    }
+
     /// Configure how the grid in the background is spaced apart along the X axis.
     ///
     /// Default is a log-10 grid, i.e. every plot unit is divided into 10 other units.
@@ -503,6 +632,26 @@ impl Plot {
         self
     }
 
+    /// Add "Export as SVG" and, with the `png` feature, "Export as PNG" to a context menu of the plot,
+    /// see [`PlotUi::export`].
+    ///
+    /// The exported file is handed to the application with [`PlotUi::take_export`],
+    /// which decides where to save it.
+    /// Default: `false`.
+    pub fn export_menu(mut self, on: bool) -> Self {
+        self.export_menu = on;
+        self
+    }
+
+    /// Select values by dragging a brush with the primary pointer button, instead of moving the plot.
+    /// A click clears the selection.
+    ///
+    /// The selected values are available from [`PlotUi::selection`].
+    pub fn brush(mut self, mode: BrushMode) -> Self {
+        self.brush_mode = Some(mode);
+        self
+    }
+
     /// Add a [`LinkedAxisGroup`] so that this plot will share the bounds with other plots that have this
     /// group assigned. A plot cannot belong to more than one group.
     pub fn link_axis(mut self, group: LinkedAxisGroup) -> Self {
@@ -530,6 +679,7 @@ impl Plot {
             allow_boxed_zoom,
             boxed_zoom_pointer_button: boxed_zoom_pointer,
             min_auto_bounds,
+            follow_x,
             margin_fraction,
             width,
             height,
@@ -541,11 +691,17 @@ impl Plot {
             label_formatter,
             coordinates_formatter,
             axis_formatters,
+            axis_labels,
+            axis_gutters,
+            secondary_y_axis,
+            time_x_axis,
             legend_config,
             show_background,
             show_axes,
             linked_axes,
             grid_spacers,
+            export_menu,
+            brush_mode,
         } = self;
 
         // Determine the size of the plot in the UI
@@ -578,8 +734,12 @@ impl Plot {
         // Load or initialize the memory.
         let plot_id = ui.make_persistent_id(id_source);
         ui.ctx().check_for_id_clash(plot_id, rect, "Plot");
+        let initial_auto_bounds = AutoBounds {
+            x: follow_x.is_some() || !min_auto_bounds.is_valid(),
+            y: !min_auto_bounds.is_valid(),
+        };
         let mut memory = PlotMemory::load(ui.ctx(), plot_id).unwrap_or_else(|| PlotMemory {
-            auto_bounds: (!min_auto_bounds.is_valid()).into(),
+            auto_bounds: initial_auto_bounds.clone(),
             hovered_entry: None,
             hidden_items: Default::default(),
             min_auto_bounds,
@@ -589,13 +749,16 @@ impl Plot {
                 center_x_axis,
                 center_y_axis,
             ),
+            last_secondary_transform: None,
             last_click_pos_for_zoom: None,
+            item_drag: Default::default(),
+            brush: Default::default(),
         });
 
         // If the min bounds changed, recalculate everything.
         if min_auto_bounds != memory.min_auto_bounds {
             memory = PlotMemory {
-                auto_bounds: (!min_auto_bounds.is_valid()).into(),
+                auto_bounds: initial_auto_bounds,
                 hovered_entry: None,
                 min_auto_bounds,
                 ..memory
@@ -608,15 +771,72 @@ impl Plot {
             mut hovered_entry,
             mut hidden_items,
             last_screen_transform,
+            last_secondary_transform,
             mut last_click_pos_for_zoom,
+            mut item_drag,
+            mut brush,
             ..
         } = memory;
 
+        // Make room for the axis labels around the plot area, measured as they were last frame.
+        let show_gutters =
+            axis_gutters || axis_labels.iter().any(Option::is_some) || secondary_y_axis.is_some();
+        let frame = if show_gutters {
+            let y_labels = if show_axes[1] {
+                axis::tick_labels(
+                    &last_screen_transform,
+                    1,
+                    axis_formatters[1].as_deref(),
+                    &*grid_spacers[1],
+                )
+            } else {
+                vec![]
+            };
+            let secondary_labels = match (&secondary_y_axis, &last_secondary_transform) {
+                (Some(axis), Some(transform)) => {
+                    axis::tick_labels(transform, 1, axis.formatter.as_deref(), &*axis.grid_spacer)
+                }
+                _ => vec![],
+            };
+            let titles = [
+                axis_labels[0].as_ref(),
+                axis_labels[1].as_ref(),
+                secondary_y_axis
+                    .as_ref()
+                    .and_then(|axis| axis.label.as_ref()),
+            ];
+            AxisGutters::measure(ui, titles, [&y_labels, &secondary_labels]).frame(rect)
+        } else {
+            rect
+        };
+
+        // Export requested from the context menu last frame, and the result of an earlier export.
+        let export_request_id = plot_id.with("export_request");
+        let export_id = plot_id.with("export");
+        let (export_request, export) = {
+            let mut data = ui.data();
+            let request = data.get_temp::<ExportFormat>(export_request_id);
+            let export = data.get_temp::<PlotExport>(export_id);
+            data.remove::<ExportFormat>(export_request_id);
+            data.remove::<PlotExport>(export_id);
+            (request, export)
+        };
+
         // Call the plot build function.
         let mut plot_ui = PlotUi {
             items: Vec::new(),
             next_auto_color_idx: 0,
             last_screen_transform,
+            last_secondary_transform,
+            has_secondary_y_axis: secondary_y_axis.is_some(),
+            adding_to_secondary_y_axis: false,
+            item_drag,
+            drag_candidates: Vec::new(),
+            next_draggable_item: 0,
+            selection: brush.selection.clone(),
+            following: follow_x.map(|_| auto_bounds.x),
+            export_request,
+            export,
             response,
             ctx: ui.ctx().clone(),
         };
@@ -625,13 +845,27 @@ impl Plot {
             mut items,
             mut response,
             last_screen_transform,
+            following,
+            export_request,
+            drag_candidates,
+            last_secondary_transform,
             ..
         } = plot_ui;
+        item_drag.update(&drag_candidates, &response);
+        if let Some(following) = following {
+            auto_bounds.x = following;
+        }
+
+        // Everything painted from here on is part of an export.
+        let export_start = export_request.map(|format| {
+            let start = ui.ctx().graphics().list(ui.layer_id()).next_idx();
+            (format, start)
+        });
 
         // Background
         if show_background {
-            ui.painter().with_clip_rect(rect).add(epaint::RectShape {
-                rect,
+            ui.painter().with_clip_rect(frame).add(epaint::RectShape {
+                rect: frame,
                 rounding: Rounding::same(2.0),
                 fill: ui.visuals().extreme_bg_color,
                 stroke: ui.visuals().widgets.noninteractive.bg_stroke,
@@ -640,20 +874,22 @@ impl Plot {
 
         // --- Legend ---
         let legend = legend_config
-            .and_then(|config| LegendWidget::try_new(rect, config, &items, &hidden_items));
+            .and_then(|config| LegendWidget::try_new(frame, config, &items, &hidden_items));
         // Don't show hover cursor when hovering over legend.
         if hovered_entry.is_some() {
             show_x = false;
             show_y = false;
         }
         // Remove the deselected items.
-        items.retain(|item| !hidden_items.contains(item.name()));
+        items = items
+            .into_iter()
+            .filter_map(|mut item| item.hide_entries(&hidden_items).then(|| item))
+            .collect();
         // Highlight the hovered items.
         if let Some(hovered_name) = &hovered_entry {
             items
                 .iter_mut()
-                .filter(|entry| entry.name() == hovered_name)
-                .for_each(|entry| entry.highlight());
+                .for_each(|item| item.highlight_entry(hovered_name));
         }
         // Move highlighted items to front.
         items.sort_by_key(|item| item.highlighted());
@@ -693,18 +929,26 @@ impl Plot {
             }
 
             for item in &items {
-                // bounds.merge(&item.get_bounds());
-
+                let item_bounds = item.get_bounds();
                 if auto_bounds.x {
-                    bounds.merge_x(&item.get_bounds());
+                    bounds.merge_x(&item_bounds);
                 }
-                if auto_bounds.y {
-                    bounds.merge_y(&item.get_bounds());
+                if auto_bounds.y && !item.secondary_y_axis() {
+                    bounds.merge_y(&item_bounds);
                 }
             }
 
-            if auto_bounds.x {
-                bounds.add_relative_margin_x(margin_fraction);
+            match follow_x {
+                Some(width) if auto_bounds.x => {
+                    if bounds.max[0].is_finite() {
+                        bounds.min[0] = bounds.max[0] - width;
+                    }
+                }
+                _ => {
+                    if auto_bounds.x {
+                        bounds.add_relative_margin_x(margin_fraction);
+                    }
+                }
             }
 
             if auto_bounds.y {
@@ -712,7 +956,7 @@ impl Plot {
             }
         }
 
-        let mut transform = ScreenTransform::new(rect, bounds, center_x_axis, center_y_axis);
+        let mut transform = ScreenTransform::new(frame, bounds, center_x_axis, center_y_axis);
 
         // Enforce equal aspect ratio.
         if let Some(data_aspect) = data_aspect {
@@ -722,10 +966,33 @@ impl Plot {
             transform.set_aspect(data_aspect as f64, preserve_y);
         }
 
+        // The secondary Y axis shares the X axis, but has its own Y bounds.
+        let mut secondary_transform = secondary_y_axis.as_ref().map(|axis| {
+            let mut bounds = last_secondary_transform
+                .as_ref()
+                .map_or(PlotBounds::NOTHING, |transform| *transform.bounds());
+            bounds.set_x(transform.bounds());
+            if auto_bounds.y || !bounds.is_valid() {
+                bounds.set_y(&axis.min_auto_bounds);
+                for item in items.iter().filter(|item| item.secondary_y_axis()) {
+                    bounds.merge_y(&item.get_bounds());
+                }
+                bounds.add_relative_margin_y(margin_fraction);
+            }
+            ScreenTransform::new(frame, bounds, center_x_axis, center_y_axis)
+        });
+
         // Dragging
-        if allow_drag && response.dragged_by(PointerButton::Primary) {
+        if allow_drag
+            && brush_mode.is_none()
+            && !item_drag.is_dragging()
+            && response.dragged_by(PointerButton::Primary)
+        {
             response = response.on_hover_cursor(CursorIcon::Grabbing);
             transform.translate_bounds(-response.drag_delta());
+            if let Some(secondary_transform) = &mut secondary_transform {
+                secondary_transform.translate_bounds(-response.drag_delta());
+            }
             auto_bounds = false.into();
         }
 
@@ -759,6 +1026,14 @@ impl Plot {
                 }
                 // when the click is release perform the zoom
                 if response.drag_released() {
+                    let secondary_bounds = secondary_transform.as_ref().map(|secondary| {
+                        let box_start_pos = secondary.value_from_position(box_start_pos);
+                        let box_end_pos = secondary.value_from_position(box_end_pos);
+                        PlotBounds {
+                            min: [box_start_pos.x, box_end_pos.y],
+                            max: [box_end_pos.x, box_start_pos.y],
+                        }
+                    });
                     let box_start_pos = transform.value_from_position(box_start_pos);
                     let box_end_pos = transform.value_from_position(box_end_pos);
                     let new_bounds = PlotBounds {
@@ -767,6 +1042,11 @@ impl Plot {
                     };
                     if new_bounds.is_valid() {
                         *transform.bounds_mut() = new_bounds;
+                        if let (Some(secondary), Some(secondary_bounds)) =
+                            (&mut secondary_transform, secondary_bounds)
+                        {
+                            *secondary.bounds_mut() = secondary_bounds;
+                        }
                         auto_bounds = false.into();
                     } else {
                         auto_bounds = true.into();
@@ -786,6 +1066,9 @@ impl Plot {
                 };
                 if zoom_factor != Vec2::splat(1.0) {
                     transform.zoom(zoom_factor, hover_pos);
+                    if let Some(secondary_transform) = &mut secondary_transform {
+                        secondary_transform.zoom(zoom_factor, hover_pos);
+                    }
                     auto_bounds = false.into();
                 }
             }
@@ -793,16 +1076,28 @@ impl Plot {
                 let scroll_delta = ui.input().scroll_delta;
                 if scroll_delta != Vec2::ZERO {
                     transform.translate_bounds(-scroll_delta);
+                    if let Some(secondary_transform) = &mut secondary_transform {
+                        secondary_transform.translate_bounds(-scroll_delta);
+                    }
                     auto_bounds = false.into();
                 }
             }
         }
 
+        if let Some(secondary_transform) = &mut secondary_transform {
+            secondary_transform.bounds_mut().set_x(transform.bounds());
+        }
+
+        if let Some(mode) = brush_mode.filter(|_| !item_drag.is_dragging()) {
+            brush.update(mode, &response, &transform);
+        }
         // Initialize values from functions.
         for item in &mut items {
-            item.initialize(transform.bounds().range_x());
+            item.initialize(&transform);
         }
 
+        brush.select(&items, &transform, secondary_transform.as_ref());
+
         let prepared = PreparedPlot {
             items,
             show_x,
@@ -810,15 +1105,26 @@ impl Plot {
             label_formatter,
             coordinates_formatter,
             axis_formatters,
+            axis_labels,
+            show_gutters,
             show_axes,
+            time_x_axis,
+            rect,
             transform: transform.clone(),
+            secondary_transform: secondary_transform.clone(),
+            secondary_y_axis,
             grid_spacers,
+            exporting: export_start.is_some(),
         };
         prepared.ui(ui, &response);
 
-        if let Some(boxed_zoom_rect) = boxed_zoom_rect {
-            ui.painter().with_clip_rect(rect).add(boxed_zoom_rect.0);
-            ui.painter().with_clip_rect(rect).add(boxed_zoom_rect.1);
+        if let Some(brush) = brush.shape(ui, &transform) {
+            ui.painter().with_clip_rect(frame).add(brush);
+        }
+
+        if let Some(boxed_zoom_rect) = boxed_zoom_rect.filter(|_| export_start.is_none()) {
+            ui.painter().with_clip_rect(frame).add(boxed_zoom_rect.0);
+            ui.painter().with_clip_rect(frame).add(boxed_zoom_rect.1);
         }
 
         if let Some(mut legend) = legend {
@@ -827,6 +1133,18 @@ impl Plot {
             hovered_entry = legend.get_hovered_entry_name();
         }
 
+        if let Some((format, start)) = export_start {
+            let shapes = ui
+                .ctx()
+                .graphics()
+                .list(ui.layer_id())
+                .shapes_since(start)
+                .to_vec();
+            let export = PlotExport::new(ui.ctx(), format, rect, shapes);
+            ui.data().insert_temp(export_id, export);
+            ui.ctx().request_repaint();
+        }
+
         if let Some(group) = linked_axes.as_ref() {
             group.set(*transform.bounds());
         }
@@ -837,11 +1155,36 @@ impl Plot {
             hidden_items,
             min_auto_bounds,
             last_screen_transform: transform,
+            last_secondary_transform: secondary_transform,
             last_click_pos_for_zoom,
+            item_drag,
+            brush,
         };
         memory.store(ui.ctx(), plot_id);
 
-        let response = if show_x || show_y {
+        let response = if export_menu {
+            response.context_menu(|ui| {
+                let formats = [
+                    ExportFormat::Svg,
+                    #[cfg(feature = "png")]
+                    ExportFormat::Png,
+                ];
+                for format in formats {
+                    let text = format!("Export as {}", format.extension().to_uppercase());
+                    if ui.button(text).clicked() {
+                        ui.data().insert_temp(export_request_id, format);
+                        ui.ctx().request_repaint();
+                        ui.close_menu();
+                    }
+                }
+            })
+        } else {
+            response
+        };
+
+        let response = if let Some(cursor_icon) = item_drag.cursor_icon() {
+            response.on_hover_cursor(cursor_icon)
+        } else if show_x || show_y {
             response.on_hover_cursor(CursorIcon::Crosshair)
         } else {
             response
@@ -857,11 +1200,63 @@ pub struct PlotUi {
     items: Vec<Box<dyn PlotItem>>,
     next_auto_color_idx: usize,
     last_screen_transform: ScreenTransform,
+    last_secondary_transform: Option<ScreenTransform>,
+    has_secondary_y_axis: bool,
+    /// Inside [`Self::on_secondary_y_axis`]?
+    adding_to_secondary_y_axis: bool,
+    /// Whether the X axis is following the latest values, if [`Plot::follow_x`] is set.
+    following: Option<bool>,
+    export_request: Option<ExportFormat>,
+    export: Option<PlotExport>,
+    item_drag: ItemDrag,
+    drag_candidates: Vec<DragCandidate>,
+    next_draggable_item: usize,
+    selection: Option<PlotSelection>,
     response: Response,
     ctx: Context,
 }
 
 impl PlotUi {
+    /// Find the value of a draggable item that the pointer could grab, and whether it is dragged.
+    ///
+    /// `grab` returns the value at an index, given where the pointer is.
+    fn interact(
+        &mut self,
+        item: &dyn PlotItem,
+        grab: impl Fn(usize, Value) -> Value,
+    ) -> PlotItemResponse {
+        let item_idx = self.next_draggable_item;
+        self.next_draggable_item += 1;
+
+        let transform = match &self.last_secondary_transform {
+            Some(secondary_transform) if self.adding_to_secondary_y_axis => secondary_transform,
+            _ => &self.last_screen_transform,
+        };
+        let candidate = self.response.hover_pos().and_then(|pointer| {
+            let closest = item.find_closest(pointer, transform)?;
+            let pointer = transform.value_from_position(pointer);
+            let value = grab(closest.index, pointer);
+            Some(DragCandidate {
+                item: item_idx,
+                index: closest.index,
+                dist_sq: closest.dist_sq,
+                offset: [value.x - pointer.x, value.y - pointer.y],
+            })
+        });
+        let pointer = self
+            .ctx
+            .input()
+            .pointer
+            .latest_pos()
+            .map(|pos| transform.value_from_position(pos));
+        let response = self
+            .item_drag
+            .response(item_idx, pointer, self.response.drag_released());
+
+        self.drag_candidates.extend(candidate);
+        response
+    }
+
     fn auto_color(&mut self) -> Color32 {
         let i = self.next_auto_color_idx;
         self.next_auto_color_idx += 1;
@@ -881,6 +1276,37 @@ impl PlotUi {
         *self.last_screen_transform.bounds()
     }
 
+    /// Returns `true` if the X axis is following the latest values, see [`Plot::follow_x`].
+    pub fn is_following(&self) -> bool {
+        self.following == Some(true)
+    }
+
+    /// Pause or resume following the latest values, see [`Plot::follow_x`].
+    /// Does nothing if the plot isn't set to follow them.
+    pub fn set_following(&mut self, following: bool) {
+        if let Some(current) = &mut self.following {
+            *current = following;
+        }
+    }
+
+    /// Render the plot as it is painted this frame, with its axes, grid and legend,
+    /// to an SVG document or, with the `png` feature, a PNG image. Hover labels are left out.
+    ///
+    /// The result can be taken with [`Self::take_export`] on the next frame.
+    pub fn export(&mut self, format: ExportFormat) {
+        self.export_request = Some(format);
+    }
+
+    /// The plot rendered last frame, by [`Self::export`] or from the [`Plot::export_menu`].
+    pub fn take_export(&mut self) -> Option<PlotExport> {
+        self.export.take()
+    }
+
+    /// The values selected with the brush as of the last frame, see [`Plot::brush`].
+    pub fn selection(&self) -> Option<&PlotSelection> {
+        self.selection.as_ref()
+    }
+
     /// Returns `true` if the plot area is currently hovered.
     pub fn plot_hovered(&self) -> bool {
         self.response.hovered()
@@ -952,16 +1378,24 @@ impl PlotUi {
     }
 
     /// Add data points.
-    pub fn points(&mut self, mut points: Points) {
+    ///
+    /// If they are [`Points::draggable`], the response tells which one is dragged and where to.
+    pub fn points(&mut self, mut points: Points) -> PlotItemResponse {
         if points.series.is_empty() {
-            return;
+            return PlotItemResponse::default();
         };
 
         // Give the points an automatic color if no color has been assigned.
         if points.color == Color32::TRANSPARENT {
             points.color = self.auto_color();
         }
+        let response = if points.draggable {
+            self.interact(&points, |index, _| points.series.values[index])
+        } else {
+            PlotItemResponse::default()
+        };
         self.items.push(Box::new(points));
+        response
     }
 
     /// Add arrows.
@@ -985,21 +1419,37 @@ impl PlotUi {
     /// Add a horizontal line.
     /// Can be useful e.g. to show min/max bounds or similar.
     /// Always fills the full width of the plot.
-    pub fn hline(&mut self, mut hline: HLine) {
+    ///
+    /// If it is [`HLine::draggable`], the response tells where it is dragged to.
+    pub fn hline(&mut self, mut hline: HLine) -> PlotItemResponse {
         if hline.stroke.color == Color32::TRANSPARENT {
             hline.stroke.color = self.auto_color();
         }
+        let response = if hline.draggable {
+            self.interact(&hline, |_, pointer| Value::new(pointer.x, hline.y))
+        } else {
+            PlotItemResponse::default()
+        };
         self.items.push(Box::new(hline));
+        response
     }
 
     /// Add a vertical line.
     /// Can be useful e.g. to show min/max bounds or similar.
     /// Always fills the full height of the plot.
-    pub fn vline(&mut self, mut vline: VLine) {
+    ///
+    /// If it is [`VLine::draggable`], the response tells where it is dragged to.
+    pub fn vline(&mut self, mut vline: VLine) -> PlotItemResponse {
         if vline.stroke.color == Color32::TRANSPARENT {
             vline.stroke.color = self.auto_color();
         }
+        let response = if vline.draggable {
+            self.interact(&vline, |_, pointer| Value::new(vline.x, pointer.y))
+        } else {
+            PlotItemResponse::default()
+        };
         self.items.push(Box::new(vline));
+        response
     }
 
     /// Add a box plot diagram.
@@ -1027,6 +1477,76 @@ impl PlotUi {
         }
         self.items.push(Box::new(chart));
     }
+
+    /// Add a filled area, see [`AreaChart`].
+    pub fn area_chart(&mut self, mut area: AreaChart) {
+        if area.series.is_empty() {
+            return;
+        };
+
+        // Give the area an automatic color if no color has been assigned.
+        if area.stroke.color == Color32::TRANSPARENT {
+            area.stroke.color = self.auto_color();
+        }
+        self.items.push(Box::new(area));
+    }
+
+    /// Add a pie chart. Each slice without a color gets an automatic one.
+    pub fn pie_chart(&mut self, mut chart: PieChart) {
+        if chart.slices.is_empty() {
+            return;
+        }
+
+        for slice in &mut chart.slices {
+            if slice.fill == Color32::TRANSPARENT && slice.stroke.color == Color32::TRANSPARENT {
+                *slice = slice.clone().color(self.auto_color());
+            }
+        }
+        self.items.push(Box::new(chart));
+    }
+
+    /// Add a heatmap.
+    pub fn heatmap(&mut self, heatmap: Heatmap) {
+        if heatmap.is_empty() {
+            return;
+        }
+        self.items.push(Box::new(heatmap));
+    }
+
+    /// Add items that are plotted against the [`Plot::secondary_y_axis`].
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// # use egui::plot::{Line, Plot, SecondaryYAxis, Values};
+    /// Plot::new("my_plot")
+    ///     .secondary_y_axis(SecondaryYAxis::new())
+    ///     .show(ui, |plot_ui| {
+    ///         plot_ui.line(Line::new(Values::from_ys_f32(&[1.0, 2.0, 3.0])));
+    ///         plot_ui.on_secondary_y_axis(|plot_ui| {
+    ///             plot_ui.line(Line::new(Values::from_ys_f32(&[300.0, 200.0, 100.0])));
+    ///         });
+    ///     });
+    /// # });
+    /// ```
+    ///
+    /// If the plot has no secondary Y axis, the items are plotted against the usual one.
+    pub fn on_secondary_y_axis<R>(&mut self, add_items: impl FnOnce(&mut Self) -> R) -> R {
+        let first_new_item = self.items.len();
+        self.adding_to_secondary_y_axis = self.has_secondary_y_axis;
+        let inner = add_items(self);
+        self.adding_to_secondary_y_axis = false;
+        if self.has_secondary_y_axis {
+            let new_items = self.items.split_off(first_new_item);
+            self.items.extend(new_items.into_iter().map(|item| {
+                if item.secondary_y_axis() {
+                    item
+                } else {
+                    Box::new(items::OnSecondaryYAxis(item))
+                }
+            }));
+        }
+        inner
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -1110,12 +1630,30 @@ struct PreparedPlot {
     label_formatter: LabelFormatter,
     coordinates_formatter: Option<(Corner, CoordinatesFormatter)>,
     axis_formatters: [AxisFormatter; 2],
+    axis_labels: [Option<WidgetText>; 2],
+    /// Paint the axis labels outside the plot frame?
+    show_gutters: bool,
     show_axes: [bool; 2],
+    time_x_axis: bool,
+    /// The plot frame and the axis labels around it.
+    rect: Rect,
     transform: ScreenTransform,
+    secondary_transform: Option<ScreenTransform>,
+    secondary_y_axis: Option<SecondaryYAxis>,
     grid_spacers: [GridSpacer; 2],
+    /// Leave out everything that depends on the pointer.
+    exporting: bool,
 }
 
 impl PreparedPlot {
+    /// The transform of the Y axis that the item is plotted against.
+    fn item_transform(&self, item: &dyn PlotItem) -> &ScreenTransform {
+        match &self.secondary_transform {
+            Some(secondary_transform) if item.secondary_y_axis() => secondary_transform,
+            _ => &self.transform,
+        }
+    }
+
     fn ui(self, ui: &mut Ui, response: &Response) {
         let mut shapes = Vec::new();
 
@@ -1130,18 +1668,26 @@ impl PreparedPlot {
         let mut plot_ui = ui.child_ui(*transform.frame(), Layout::default());
         plot_ui.set_clip_rect(*transform.frame());
         for item in &self.items {
-            item.get_shapes(&mut plot_ui, transform, &mut shapes);
+            item.get_shapes(&mut plot_ui, self.item_transform(&**item), &mut shapes);
         }
 
-        if let Some(pointer) = response.hover_pos() {
+        let hover_pos = response
+            .hover_pos()
+            .filter(|pointer| !self.exporting && transform.frame().contains(*pointer));
+
+        if let Some(pointer) = hover_pos {
             self.hover(ui, pointer, &mut shapes);
         }
 
         let painter = ui.painter().with_clip_rect(*transform.frame());
         painter.extend(shapes);
 
+        if self.show_gutters {
+            self.paint_gutters(ui);
+        }
+
         if let Some((corner, formatter)) = self.coordinates_formatter.as_ref() {
-            if let Some(pointer) = response.hover_pos() {
+            if let Some(pointer) = hover_pos {
                 let font_id = TextStyle::Monospace.resolve(ui.style());
                 let coordinate = transform.value_from_position(pointer);
                 let text = formatter.format(&coordinate, transform.bounds());
@@ -1157,21 +1703,52 @@ impl PreparedPlot {
         }
     }
 
+    /// Paint the tick labels and titles of the axes around the plot frame.
+    fn paint_gutters(&self, ui: &Ui) {
+        let painter = ui.painter().with_clip_rect(self.rect);
+        let transform = &self.transform;
+        let frame = *transform.frame();
+
+        for axis in 0..2 {
+            if self.show_axes[axis] {
+                let labels = axis::tick_labels(
+                    transform,
+                    axis,
+                    self.axis_formatters[axis].as_deref(),
+                    &*self.grid_spacers[axis],
+                );
+                axis::paint_tick_labels(ui, &painter, transform, axis, false, &labels);
+            }
+            if let Some(title) = &self.axis_labels[axis] {
+                axis::paint_axis_title(ui, &painter, self.rect, frame, axis, false, title);
+            }
+        }
+
+        if let (Some(secondary_y_axis), Some(secondary_transform)) =
+            (&self.secondary_y_axis, &self.secondary_transform)
+        {
+            let labels = axis::tick_labels(
+                secondary_transform,
+                1,
+                secondary_y_axis.formatter.as_deref(),
+                &*secondary_y_axis.grid_spacer,
+            );
+            axis::paint_tick_labels(ui, &painter, secondary_transform, 1, true, &labels);
+            if let Some(title) = &secondary_y_axis.label {
+                axis::paint_axis_title(ui, &painter, self.rect, frame, 1, true, title);
+            }
+        }
+    }
+
     fn paint_axis(&self, ui: &Ui, axis: usize, shapes: &mut Vec<Shape>) {
         let Self {
             transform,
             axis_formatters,
             grid_spacers,
+            show_gutters,
             ..
         } = self;
 
-        let bounds = transform.bounds();
-        let axis_range = match axis {
-            0 => bounds.range_x(),
-            1 => bounds.range_y(),
-            _ => panic!("Axis {} does not exist.", axis),
-        };
-
         let font_id = TextStyle::Body.resolve(ui.style());
 
         // Where on the cross-dimension to show the label values
@@ -1211,32 +1788,40 @@ impl PreparedPlot {
                 p1[1 - axis] = transform.frame().max[1 - axis];
                 shapes.push(Shape::line_segment([p0, p1], Stroke::new(1.0, line_color)));
             }
+        }
 
-            let text_alpha = remap_clamp(spacing_in_points, 40.0..=150.0, 0.0..=0.4);
-
-            if text_alpha > 0.0 {
-                let color = color_from_alpha(ui, text_alpha);
+        if *show_gutters {
+            // The labels are painted outside the plot frame instead.
+            return;
+        }
 
-                let text: String = if let Some(formatter) = axis_formatters[axis].as_deref() {
-                    formatter(value_main, &axis_range)
-                } else {
-                    emath::round_to_decimals(value_main, 5).to_string() // hack
-                };
+        let labels = axis::tick_labels(
+            transform,
+            axis,
+            axis_formatters[axis].as_deref(),
+            &*grid_spacers[axis],
+        );
+        for label in labels {
+            let value = if axis == 0 {
+                Value::new(label.value, value_cross)
+            } else {
+                Value::new(value_cross, label.value)
+            };
+            let pos_in_gui = transform.position_from_value(&value);
 
-                // Custom formatters can return empty string to signal "no label at this resolution"
-                if !text.is_empty() {
-                    let galley = ui.painter().layout_no_wrap(text, font_id.clone(), color);
+            let color = color_from_alpha(ui, 0.4 * label.strength);
+            let galley = ui
+                .painter()
+                .layout_no_wrap(label.text, font_id.clone(), color);
 
-                    let mut text_pos = pos_in_gui + vec2(1.0, -galley.size().y);
+            let mut text_pos = pos_in_gui + vec2(1.0, -galley.size().y);
 
-                    // Make sure we see the labels, even if the axis is off-screen:
-                    text_pos[1 - axis] = text_pos[1 - axis]
-                        .at_most(transform.frame().max[1 - axis] - galley.size()[1 - axis] - 2.0)
-                        .at_least(transform.frame().min[1 - axis] + 1.0);
+            // Make sure we see the labels, even if the axis is off-screen:
+            text_pos[1 - axis] = text_pos[1 - axis]
+                .at_most(transform.frame().max[1 - axis] - galley.size()[1 - axis] - 2.0)
+                .at_least(transform.frame().min[1 - axis] + 1.0);
 
-                    shapes.push(Shape::galley(text_pos, galley));
-                }
-            }
+            shapes.push(Shape::galley(text_pos, galley));
         }
 
         fn color_from_alpha(ui: &Ui, alpha: f32) -> Color32 {
@@ -1262,11 +1847,11 @@ impl PreparedPlot {
             return;
         }
 
-        let interact_radius_sq: f32 = (16.0f32).powi(2);
+        let interact_radius_sq: f32 = HOVER_RADIUS.powi(2);
 
         let candidates = items.iter().filter_map(|item| {
             let item = &**item;
-            let closest = item.find_closest(pointer, transform);
+            let closest = item.find_closest(pointer, self.item_transform(item));
 
             Some(item).zip(closest)
         });
@@ -1275,16 +1860,23 @@ impl PreparedPlot {
             .min_by_key(|(_, elem)| elem.dist_sq.ord())
             .filter(|(_, elem)| elem.dist_sq <= interact_radius_sq);
 
-        let plot = items::PlotConfig {
-            ui,
-            transform,
-            show_x: *show_x,
-            show_y: *show_y,
-        };
-
         if let Some((item, elem)) = closest {
+            let plot = items::PlotConfig {
+                ui,
+                transform: self.item_transform(item),
+                show_x: *show_x,
+                show_y: *show_y,
+                time_x_axis: self.time_x_axis,
+            };
             item.on_hover(elem, shapes, &plot, label_formatter);
         } else {
+            let plot = items::PlotConfig {
+                ui,
+                transform,
+                show_x: *show_x,
+                show_y: *show_y,
+                time_x_axis: self.time_x_axis,
+            };
             let value = transform.value_from_position(pointer);
             items::rulers_at_value(pointer, value, "", &plot, shapes, label_formatter);
         }
@@ -1325,3 +1917,57 @@ fn fill_marks_between(out: &mut Vec<GridMark>, step_size: f64, (min, max): (f64,
     });
     out.extend(marks_iter);
 }
+
+#[cfg(test)]
+#[test]
+fn test_secondary_y_axis_bounds() {
+    let ctx = Context::default();
+    let raw_input = RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(300.0, 200.0))),
+        ..Default::default()
+    };
+    let mut bounds = None;
+    let mut shapes = vec![];
+    for _ in 0..2 {
+        shapes = ctx
+            .run(raw_input.clone(), |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    Plot::new("plot")
+                        .secondary_y_axis(SecondaryYAxis::new())
+                        .show(ui, |plot_ui| {
+                            let main = Values::from_ys_f32(&[0.0, 1.0]);
+                            plot_ui.line(Line::new(main).color(Color32::RED));
+                            plot_ui.on_secondary_y_axis(|plot_ui| {
+                                let secondary = Values::from_ys_f32(&[0.0, 1000.0]);
+                                plot_ui.line(Line::new(secondary).color(Color32::BLUE));
+                            });
+                            bounds = Some(plot_ui.plot_bounds());
+                        });
+                });
+            })
+            .shapes;
+    }
+
+    // The secondary values don't stretch the usual Y axis:
+    let bounds = bounds.unwrap();
+    assert!(bounds.min()[1] < 0.0 && 1.0 < bounds.max()[1] && bounds.max()[1] < 2.0);
+
+    // Each line fills the plot with its own Y bounds, so both span the same pixels:
+    let screen_y_range = |color: Color32| {
+        let points: Vec<Pos2> = shapes
+            .iter()
+            .filter_map(|clipped| match &clipped.1 {
+                Shape::Path(path) if path.stroke.color == color => Some(path.points.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(points.len(), 2);
+        (points[0].y, points[1].y)
+    };
+    let (main_bottom, main_top) = screen_y_range(Color32::RED);
+    let (secondary_bottom, secondary_top) = screen_y_range(Color32::BLUE);
+    assert!(main_top < main_bottom);
+    assert!((main_bottom - secondary_bottom).abs() < 1.0);
+    assert!((main_top - secondary_top).abs() < 1.0);
+}
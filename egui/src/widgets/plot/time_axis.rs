@@ -0,0 +1,359 @@
+//! Axes where the values are time stamps, in seconds since 1970-01-01 00:00:00 UTC.
+
+use std::ops::RangeInclusive;
+
+use super::{fill_marks_between, log_grid_spacer, GridInput, GridMark, GridSpacer};
+
+const SECONDS_PER_MINUTE: f64 = 60.0;
+const SECONDS_PER_HOUR: f64 = 60.0 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: f64 = 24.0 * SECONDS_PER_HOUR;
+
+const SECONDS_PER_WEEK: f64 = 7.0 * SECONDS_PER_DAY;
+
+/// The average length of a month in the Gregorian calendar.
+const SECONDS_PER_MONTH: f64 = 30.436_875 * SECONDS_PER_DAY;
+
+/// Weeks start on Mondays, and 1970-01-01 was a Thursday: the first Monday was 1970-01-05.
+const FIRST_MONDAY: f64 = 4.0 * SECONDS_PER_DAY;
+
+/// The distance between two marks of a time axis.
+#[derive(Clone, Copy, PartialEq)]
+enum TimeStep {
+    /// Up to two weeks, since days all have the same length in Unix time.
+    Seconds(f64),
+
+    /// Calendar months (and years).
+    Months(i64),
+}
+
+impl TimeStep {
+    const SECONDS: [f64; 30] = [
+        0.001,
+        0.002,
+        0.005,
+        0.01,
+        0.02,
+        0.05,
+        0.1,
+        0.2,
+        0.5,
+        1.0,
+        2.0,
+        5.0,
+        10.0,
+        15.0,
+        30.0,
+        SECONDS_PER_MINUTE,
+        2.0 * SECONDS_PER_MINUTE,
+        5.0 * SECONDS_PER_MINUTE,
+        10.0 * SECONDS_PER_MINUTE,
+        15.0 * SECONDS_PER_MINUTE,
+        30.0 * SECONDS_PER_MINUTE,
+        SECONDS_PER_HOUR,
+        2.0 * SECONDS_PER_HOUR,
+        3.0 * SECONDS_PER_HOUR,
+        6.0 * SECONDS_PER_HOUR,
+        12.0 * SECONDS_PER_HOUR,
+        SECONDS_PER_DAY,
+        2.0 * SECONDS_PER_DAY,
+        SECONDS_PER_WEEK,
+        2.0 * SECONDS_PER_WEEK,
+    ];
+
+    const MONTHS: [i64; 15] = [
+        1, 2, 3, 6, 12, 24, 60, 120, 240, 600, 1_200, 2_400, 6_000, 12_000, 24_000,
+    ];
+
+    /// All steps, from the shortest to the longest.
+    fn all() -> impl Iterator<Item = Self> {
+        let seconds = Self::SECONDS.iter().map(|&seconds| Self::Seconds(seconds));
+        let months = Self::MONTHS.iter().map(|&months| Self::Months(months));
+        seconds.chain(months)
+    }
+
+    /// The (average) length of the step in seconds.
+    fn seconds(self) -> f64 {
+        match self {
+            Self::Seconds(seconds) => seconds,
+            Self::Months(months) => months as f64 * SECONDS_PER_MONTH,
+        }
+    }
+
+    /// Does every mark of `self` fall on a mark of `smaller`?
+    fn is_multiple_of(self, smaller: Self) -> bool {
+        match (self, smaller) {
+            (Self::Seconds(a), Self::Seconds(b)) => ((a / b).round() * b - a).abs() < 1e-9 * a,
+            (Self::Months(a), Self::Months(b)) => a % b == 0,
+            // Months start at midnight, but not on every other day or week:
+            (Self::Months(_), Self::Seconds(b)) => {
+                Self::Seconds(SECONDS_PER_DAY).is_multiple_of(Self::Seconds(b))
+            }
+            (Self::Seconds(_), Self::Months(_)) => false,
+        }
+    }
+
+    fn fill_marks_between(self, out: &mut Vec<GridMark>, (min, max): (f64, f64)) {
+        match self {
+            Self::Seconds(seconds) if seconds % SECONDS_PER_WEEK == 0.0 => {
+                let start = out.len();
+                fill_marks_between(out, seconds, (min - FIRST_MONDAY, max - FIRST_MONDAY));
+                for mark in &mut out[start..] {
+                    mark.value += FIRST_MONDAY;
+                }
+            }
+            Self::Seconds(seconds) => fill_marks_between(out, seconds, (min, max)),
+            Self::Months(months) => {
+                let step_size = self.seconds();
+                let (year, month, _) = civil_from_days((min / SECONDS_PER_DAY).floor() as i64);
+                let mut month_index = (12 * year + month as i64 - 1).div_euclid(months) * months;
+                loop {
+                    let year = month_index.div_euclid(12);
+                    let month = month_index.rem_euclid(12) as u32 + 1;
+                    let value = days_from_civil(year, month, 1) as f64 * SECONDS_PER_DAY;
+                    if value >= max {
+                        break;
+                    }
+                    if value >= min {
+                        out.push(GridMark { value, step_size });
+                    }
+                    month_index += months;
+                }
+            }
+        }
+    }
+}
+
+/// Places the grid marks of a time axis at round times and dates:
+/// milliseconds, seconds, minutes, hours, days, weeks (from Monday), months and years.
+///
+/// The values are time stamps: seconds since 1970-01-01 00:00:00 UTC.
+/// Used by [`Plot::time_x_axis`](super::Plot::time_x_axis).
+pub fn time_grid_spacer() -> GridSpacer {
+    let decimal_spacer = log_grid_spacer(10);
+
+    Box::new(move |input: GridInput| -> Vec<GridMark> {
+        let smallest = TimeStep::all().find(|step| step.seconds() >= input.base_step_size);
+        let smallest = match smallest {
+            Some(step) if input.bounds.0.is_finite() && input.bounds.1.is_finite() => step,
+            // Below a millisecond or above thousands of years the calendar doesn't help.
+            _ => return decimal_spacer(input),
+        };
+
+        let mut steps = vec![smallest];
+        while steps.len() < 3 {
+            let previous = steps[steps.len() - 1];
+            let larger = TimeStep::all()
+                .find(|step| step.seconds() > previous.seconds() && step.is_multiple_of(previous));
+            match larger {
+                Some(step) => steps.push(step),
+                None => break,
+            }
+        }
+
+        let mut marks = vec![];
+        for step in steps {
+            step.fill_marks_between(&mut marks, input.bounds);
+        }
+        marks
+    })
+}
+
+/// Label a mark on a time axis with as much of its date or time as is needed, e.g. `2022`,
+/// `2022-05` or `2022-05-03` at the start of a year, month or day, and otherwise `14:30`,
+/// `14:30:15` or `14:30:15.250`.
+///
+/// The value is a time stamp: seconds since 1970-01-01 00:00:00 UTC.
+/// Can be passed to [`Plot::x_axis_formatter`](super::Plot::x_axis_formatter).
+pub fn time_axis_formatter(value: f64, _range: &RangeInclusive<f64>) -> String {
+    let time = CivilTime::from_timestamp(value);
+    if time.millisecond != 0 {
+        time.format_time(true, true)
+    } else if time.second != 0 {
+        time.format_time(true, false)
+    } else if time.hour != 0 || time.minute != 0 {
+        time.format_time(false, false)
+    } else if time.day != 1 {
+        format!("{:04}-{:02}-{:02}", time.year, time.month, time.day)
+    } else if time.month != 1 {
+        format!("{:04}-{:02}", time.year, time.month)
+    } else {
+        format!("{:04}", time.year)
+    }
+}
+
+/// Format a time stamp (seconds since 1970-01-01 00:00:00 UTC) as a date and time,
+/// e.g. `2022-05-03 14:30:15`.
+///
+/// `precision` is the smallest difference in seconds worth showing: the time is left out
+/// if it is a day or more, and milliseconds are shown if it is less than a second.
+///
+/// This is useful in a [`Plot::label_formatter`](super::Plot::label_formatter).
+pub fn format_timestamp(seconds: f64, precision: f64) -> String {
+    let time = CivilTime::from_timestamp(seconds);
+    let date = format!("{:04}-{:02}-{:02}", time.year, time.month, time.day);
+    if precision >= SECONDS_PER_DAY {
+        date
+    } else if precision >= SECONDS_PER_MINUTE {
+        format!("{} {}", date, time.format_time(false, false))
+    } else {
+        format!("{} {}", date, time.format_time(true, precision < 1.0))
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A date and time in UTC.
+struct CivilTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    millisecond: u32,
+}
+
+impl CivilTime {
+    fn from_timestamp(seconds: f64) -> Self {
+        let milliseconds = (seconds * 1000.0).round() as i64;
+        let days = milliseconds.div_euclid(1000 * SECONDS_PER_DAY as i64);
+        let millisecond_of_day = milliseconds.rem_euclid(1000 * SECONDS_PER_DAY as i64);
+        let second_of_day = (millisecond_of_day / 1000) as u32;
+
+        let (year, month, day) = civil_from_days(days);
+        Self {
+            year,
+            month,
+            day,
+            hour: second_of_day / 3600,
+            minute: second_of_day / 60 % 60,
+            second: second_of_day % 60,
+            millisecond: (millisecond_of_day % 1000) as u32,
+        }
+    }
+
+    fn format_time(&self, seconds: bool, milliseconds: bool) -> String {
+        if milliseconds {
+            format!(
+                "{:02}:{:02}:{:02}.{:03}",
+                self.hour, self.minute, self.second, self.millisecond
+            )
+        } else if seconds {
+            format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+        } else {
+            format!("{:02}:{:02}", self.hour, self.minute)
+        }
+    }
+}
+
+/// Days since 1970-01-01 of a date in the (proleptic) Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html>.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The year, month and day of the date that is this many days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153; // March is 0
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+#[test]
+fn test_time_axis() {
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+    for days in [-800_000, -1, 0, 59, 11_016, 19_115, 800_000] {
+        let (year, month, day) = civil_from_days(days);
+        assert_eq!(days_from_civil(year, month, day), days);
+    }
+
+    let may_3rd_2022 = days_from_civil(2022, 5, 3) as f64 * SECONDS_PER_DAY;
+    assert_eq!(
+        time_axis_formatter(may_3rd_2022, &(0.0..=1.0)),
+        "2022-05-03"
+    );
+    assert_eq!(
+        time_axis_formatter(may_3rd_2022 + 14.5 * SECONDS_PER_HOUR, &(0.0..=1.0)),
+        "14:30"
+    );
+    assert_eq!(
+        format_timestamp(may_3rd_2022 + 61.25, 0.01),
+        "2022-05-03 00:01:01.250"
+    );
+    assert_eq!(format_timestamp(-0.5, 1.0), "1969-12-31 23:59:59");
+
+    // Monthly marks fall on the first of each month:
+    let marks = (time_grid_spacer())(GridInput {
+        bounds: (may_3rd_2022, may_3rd_2022 + 200.0 * SECONDS_PER_DAY),
+        base_step_size: 20.0 * SECONDS_PER_DAY,
+    });
+    let labels: Vec<String> = marks
+        .iter()
+        .filter(|mark| mark.step_size == SECONDS_PER_MONTH)
+        .map(|mark| time_axis_formatter(mark.value, &(0.0..=1.0)))
+        .collect();
+    assert_eq!(
+        labels,
+        ["2022-06", "2022-07", "2022-08", "2022-09", "2022-10", "2022-11"]
+    );
+
+    let days = |days: f64| TimeStep::Seconds(days * SECONDS_PER_DAY);
+    assert!(TimeStep::Months(1).is_multiple_of(TimeStep::Seconds(SECONDS_PER_HOUR)));
+    assert!(TimeStep::Months(1).is_multiple_of(days(1.0)));
+    assert!(!TimeStep::Months(1).is_multiple_of(days(2.0)));
+    assert!(!TimeStep::Months(1).is_multiple_of(days(7.0)));
+    assert!(days(14.0).is_multiple_of(days(7.0)));
+
+    // Weekly marks on Mondays, every other one also a two-week mark:
+    assert_eq!(
+        days_from_civil(1970, 1, 5) as f64 * SECONDS_PER_DAY,
+        FIRST_MONDAY
+    );
+    let marks = (time_grid_spacer())(GridInput {
+        bounds: (may_3rd_2022, may_3rd_2022 + 60.0 * SECONDS_PER_DAY),
+        base_step_size: 5.0 * SECONDS_PER_DAY,
+    });
+    let week = SECONDS_PER_WEEK;
+    let weekly: Vec<f64> = marks
+        .iter()
+        .filter(|mark| mark.step_size == week)
+        .map(|mark| mark.value)
+        .collect();
+    assert_eq!(weekly.len(), 8);
+    assert!(weekly.windows(2).all(|w| w[1] - w[0] == week));
+    let is_monday = |value: f64| {
+        let days = (value / SECONDS_PER_DAY) as i64;
+        value % SECONDS_PER_DAY == 0.0 && (days - 4).rem_euclid(7) == 0
+    };
+    assert!(weekly.iter().all(|value| is_monday(*value)));
+    assert_eq!(time_axis_formatter(weekly[0], &(0.0..=1.0)), "2022-05-09");
+    let two_weekly: Vec<f64> = marks
+        .iter()
+        .filter(|mark| mark.step_size == 2.0 * week)
+        .map(|mark| mark.value)
+        .collect();
+    assert!(!two_weekly.is_empty());
+    assert!(two_weekly.iter().all(|value| weekly.contains(value)));
+    assert!(marks.iter().all(|mark| mark.step_size != SECONDS_PER_MONTH));
+}
@@ -59,6 +59,20 @@ impl PlotBounds {
         }
     }
 
+    /// Is the value inside the bounds, or on their edge?
+    pub(crate) fn contains(&self, value: &Value) -> bool {
+        (self.min[0]..=self.max[0]).contains(&value.x)
+            && (self.min[1]..=self.max[1]).contains(&value.y)
+    }
+
+    /// Do the bounds overlap, or touch?
+    pub(crate) fn intersects(&self, other: &PlotBounds) -> bool {
+        self.min[0] <= other.max[0]
+            && other.min[0] <= self.max[0]
+            && self.min[1] <= other.max[1]
+            && other.min[1] <= self.max[1]
+    }
+
     /// Expand to include the given (x,y) value
     pub(crate) fn extend_with(&mut self, value: &Value) {
         self.extend_with_x(value.x);
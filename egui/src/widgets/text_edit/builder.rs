@@ -1089,10 +1089,11 @@ fn on_key_press(
         }
 
         Key::ArrowLeft | Key::ArrowRight if modifiers.is_none() && !cursor_range.is_empty() => {
+            let [left, right] = cursor_range.visually_sorted_cursors(galley);
             if key == Key::ArrowLeft {
-                *cursor_range = CursorRange::one(cursor_range.sorted_cursors()[0]);
+                *cursor_range = CursorRange::one(left);
             } else {
-                *cursor_range = CursorRange::one(cursor_range.sorted_cursors()[1]);
+                *cursor_range = CursorRange::one(right);
             }
             None
         }
@@ -1124,8 +1125,9 @@ fn move_single_cursor(cursor: &mut Cursor, galley: &Galley, key: Key, modifiers:
             Key::E => *cursor = galley.cursor_end_of_row(cursor),
             Key::P => *cursor = galley.cursor_up_one_row(cursor),
             Key::N => *cursor = galley.cursor_down_one_row(cursor),
-            Key::B => *cursor = galley.cursor_left_one_character(cursor),
-            Key::F => *cursor = galley.cursor_right_one_character(cursor),
+            // Like Backspace and Delete, these go through the text in logical order, also in right-to-left text:
+            Key::B => *cursor = galley.cursor_previous_character(cursor),
+            Key::F => *cursor = galley.cursor_next_character(cursor),
             _ => (),
         }
         return;
@@ -1,4 +1,4 @@
-use epaint::text::cursor::*;
+use epaint::text::{cursor::*, Galley};
 
 /// A selected text range (could be a range of length zero).
 #[derive(Clone, Copy, Debug, Default)]
@@ -86,6 +86,21 @@ impl CursorRange {
             [self.secondary, self.primary]
         }
     }
+
+    /// Returns the two ends ordered left-to-right on screen.
+    ///
+    /// This differs from [`Self::sorted_cursors`] when both ends are in the same row of right-to-left text.
+    pub fn visually_sorted_cursors(&self, galley: &Galley) -> [Cursor; 2] {
+        let [min, max] = self.sorted_cursors();
+        if min.rcursor.row == max.rcursor.row {
+            let min_x = galley.pos_from_cursor(&min).min.x;
+            let max_x = galley.pos_from_cursor(&max).min.x;
+            if max_x < min_x {
+                return [max, min];
+            }
+        }
+        [min, max]
+    }
 }
 
 /// A selected text range (could be a range of length zero).
@@ -25,9 +25,11 @@ impl Default for Demos {
             Box::new(super::code_example::CodeExample::default()),
             Box::new(super::context_menu::ContextMenus::default()),
             Box::new(super::dancing_strings::DancingStrings::default()),
+            Box::new(super::docking::Docking::default()),
             Box::new(super::drag_and_drop::DragAndDropDemo::default()),
             Box::new(super::font_book::FontBook::default()),
             Box::new(super::MiscDemoWindow::default()),
+            Box::new(super::modals::Modals::default()),
             Box::new(super::multi_touch::MultiTouch::default()),
             Box::new(super::painting::Painting::default()),
             Box::new(super::plot_demo::PlotDemo::default()),
@@ -36,6 +38,7 @@ impl Default for Demos {
             Box::new(super::strip_demo::StripDemo::default()),
             Box::new(super::table_demo::TableDemo::default()),
             Box::new(super::text_edit::TextEdit::default()),
+            Box::new(super::tree_view::TreeViewDemo::default()),
             Box::new(super::widget_gallery::WidgetGallery::default()),
             Box::new(super::window_options::WindowOptions::default()),
             Box::new(super::tests::WindowResizeTest::default()),
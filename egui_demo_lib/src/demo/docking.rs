@@ -0,0 +1,88 @@
+use egui::dock::{DockArea, DockNode, DockState};
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Docking {
+    text: String,
+    counter: usize,
+}
+
+impl Default for Docking {
+    fn default() -> Self {
+        Self {
+            text: "Drag the tabs around!".to_owned(),
+            counter: 0,
+        }
+    }
+}
+
+impl super::Demo for Docking {
+    fn name(&self) -> &'static str {
+        "🗗 Docking"
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .default_width(600.0)
+            .default_height(400.0)
+            .vscroll(false)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl super::View for Docking {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        let dock = DockArea::new(
+            "docking_demo",
+            DockNode::horizontal(
+                DockNode::tabs(["Files", "Search"]),
+                DockNode::vertical(
+                    DockNode::tabs(["Editor", "Preview"]),
+                    DockNode::tabs(["Console"]),
+                    0.7,
+                ),
+                0.3,
+            ),
+        );
+
+        ui.horizontal(|ui| {
+            ui.label("Drag a tab onto another stack, to the edge of one, or out of the dock.");
+            if ui.button("Reset layout").clicked() {
+                ui.data().remove::<DockState>(dock.id());
+            }
+        });
+        ui.separator();
+
+        let Self { text, counter } = self;
+        dock.show_inside(ui, |ui, tab| match tab {
+            "Files" => {
+                for file in ["main.rs", "lib.rs", "dock.rs"] {
+                    ui.label(file);
+                }
+            }
+            "Search" => {
+                ui.text_edit_singleline(&mut String::new());
+            }
+            "Editor" => {
+                ui.add_sized(
+                    ui.available_size(),
+                    egui::TextEdit::multiline(text).code_editor(),
+                );
+            }
+            "Preview" => {
+                ui.label(text.as_str());
+            }
+            "Console" => {
+                if ui.button("Count").clicked() {
+                    *counter += 1;
+                }
+                ui.label(format!("Counted to {}", counter));
+            }
+            _ => {
+                ui.label(tab);
+            }
+        });
+    }
+}
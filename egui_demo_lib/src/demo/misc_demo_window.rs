@@ -1,6 +1,10 @@
 use super::*;
 use crate::LOREM_IPSUM;
-use egui::{color::*, epaint::text::TextWrapping, *};
+use egui::{
+    color::*,
+    epaint::text::{TextOverflow, TextWrapping},
+    *,
+};
 
 /// Showcase some ui code
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -11,6 +15,7 @@ pub struct MiscDemoWindow {
     break_anywhere: bool,
     max_rows: usize,
     overflow_character: Option<char>,
+    overflow: TextOverflow,
 
     widgets: Widgets,
     colors: ColorWidgets,
@@ -30,6 +35,7 @@ impl Default for MiscDemoWindow {
             max_rows: 2,
             break_anywhere: false,
             overflow_character: Some('…'),
+            overflow: TextOverflow::EndEllipsis,
 
             widgets: Default::default(),
             colors: Default::default(),
@@ -75,6 +81,7 @@ impl View for MiscDemoWindow {
                     &mut self.max_rows,
                     &mut self.break_anywhere,
                     &mut self.overflow_character,
+                    &mut self.overflow,
                 );
             });
 
@@ -477,6 +484,7 @@ fn text_layout_ui(
     max_rows: &mut usize,
     break_anywhere: &mut bool,
     overflow_character: &mut Option<char>,
+    overflow: &mut TextOverflow,
 ) {
     use egui::text::LayoutJob;
 
@@ -646,16 +654,31 @@ fn text_layout_ui(
         ui.selectable_value(overflow_character, Some('-'), "  -  ");
         ui.label("Overflow character");
     });
+    ui.horizontal(|ui| {
+        ui.selectable_value(overflow, TextOverflow::Clip, "Clip");
+        ui.selectable_value(overflow, TextOverflow::EndEllipsis, "End");
+        ui.selectable_value(overflow, TextOverflow::MiddleEllipsis, "Middle");
+        ui.label("Overflow");
+    });
 
     let mut job = LayoutJob::single_section(LOREM_IPSUM.to_string(), TextFormat::default());
     job.wrap = TextWrapping {
         max_rows: *max_rows,
         break_anywhere: *break_anywhere,
         overflow_character: *overflow_character,
+        overflow: *overflow,
         ..Default::default()
     };
     ui.label(job);
 
+    ui.separator();
+
+    ui.add(
+        Label::new("/home/user/projects/egui/egui_demo_lib/src/demo/misc_demo_window.rs")
+            .truncate(true)
+            .overflow(*overflow),
+    );
+
     ui.vertical_centered(|ui| {
         ui.add(crate::egui_github_link_file_line!());
     });
@@ -10,10 +10,12 @@ pub mod code_example;
 pub mod context_menu;
 pub mod dancing_strings;
 pub mod demo_app_windows;
+pub mod docking;
 pub mod drag_and_drop;
 pub mod font_book;
 pub mod layout_test;
 pub mod misc_demo_window;
+pub mod modals;
 pub mod multi_touch;
 pub mod paint_bezier;
 pub mod painting;
@@ -26,6 +28,7 @@ pub mod table_demo;
 pub mod tests;
 pub mod text_edit;
 pub mod toggle_switch;
+pub mod tree_view;
 pub mod widget_gallery;
 pub mod window_options;
 pub mod window_with_panels;
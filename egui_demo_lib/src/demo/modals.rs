@@ -0,0 +1,67 @@
+#[derive(Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Modals {
+    name: String,
+    edit_open: bool,
+    confirm_open: bool,
+}
+
+impl super::Demo for Modals {
+    fn name(&self) -> &'static str {
+        "🗐 Modals"
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        egui::Window::new(self.name())
+            .open(open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                use super::View as _;
+                self.ui(ui);
+            });
+
+        let Self {
+            name,
+            edit_open,
+            confirm_open,
+        } = self;
+        let mut saved = false;
+        egui::Modal::new("edit_modal")
+            .open(edit_open)
+            .show(ctx, |ui| {
+                ui.heading("Edit name");
+                ui.label("Nothing below can be used until this is closed. Tab stays in here.");
+                ui.text_edit_singleline(name);
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        *confirm_open = true;
+                    }
+                    ui.label("(Escape to cancel)");
+                });
+
+                // A modal on top of the modal:
+                egui::Modal::new("confirm_modal")
+                    .open(confirm_open)
+                    .show(ctx, |ui| {
+                        ui.label(format!("Save the name {:?}?", name));
+                        saved = ui.button("Yes").clicked();
+                    });
+            });
+        if saved {
+            *edit_open = false;
+            *confirm_open = false;
+        }
+    }
+}
+
+impl super::View for Modals {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(format!("Name: {:?}", self.name));
+        if ui.button("Edit…").clicked() {
+            self.edit_open = true;
+        }
+        ui.vertical_centered(|ui| {
+            ui.add(crate::egui_github_link_file!());
+        });
+    }
+}
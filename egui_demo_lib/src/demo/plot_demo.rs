@@ -4,9 +4,9 @@ use std::ops::RangeInclusive;
 use egui::plot::{GridInput, GridMark};
 use egui::*;
 use plot::{
-    Arrows, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, CoordinatesFormatter, Corner, HLine,
-    Legend, Line, LineStyle, MarkerShape, Plot, PlotImage, Points, Polygon, Text, VLine, Value,
-    Values,
+    AreaChart, Arrows, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, BrushMode, Colormap,
+    CoordinatesFormatter, Corner, ErrorBars, HLine, Heatmap, Legend, Line, LineStyle, MarkerShape,
+    PieChart, Plot, PlotImage, Points, Polygon, SecondaryYAxis, Slice, Text, VLine, Value, Values,
 };
 
 // ----------------------------------------------------------------------------
@@ -322,17 +322,31 @@ impl CustomAxisDemo {
     const MINS_PER_DAY: f64 = 24.0 * 60.0;
     const MINS_PER_H: f64 = 60.0;
 
+    fn logistic(x: f64) -> f64 {
+        1.0 / (1.0 + (-2.5 * (x / CustomAxisDemo::MINS_PER_DAY - 2.0)).exp())
+    }
+
+    fn days(min: f64) -> f64 {
+        CustomAxisDemo::MINS_PER_DAY * min
+    }
+
     fn logistic_fn() -> Line {
-        fn days(min: f64) -> f64 {
-            CustomAxisDemo::MINS_PER_DAY * min
-        }
+        let values =
+            Values::from_explicit_callback(Self::logistic, Self::days(0.0)..Self::days(5.0), 100);
+        Line::new(values)
+    }
 
+    /// The slope of [`Self::logistic_fn`], in percent per hour.
+    fn logistic_rate_fn() -> Line {
         let values = Values::from_explicit_callback(
-            move |x| 1.0 / (1.0 + (-2.5 * (x / CustomAxisDemo::MINS_PER_DAY - 2.0)).exp()),
-            days(0.0)..days(5.0),
+            move |x| {
+                let y = Self::logistic(x);
+                100.0 * 2.5 * Self::MINS_PER_H / Self::MINS_PER_DAY * y * (1.0 - y)
+            },
+            Self::days(0.0)..Self::days(5.0),
             100,
         );
-        Line::new(values)
+        Line::new(values).name("Growth rate")
     }
 
     #[allow(clippy::needless_pass_by_value)]
@@ -410,16 +424,31 @@ impl CustomAxisDemo {
             }
         };
 
-        let label_fmt = |_s: &str, val: &Value| {
-            format!(
-                "Day {d}, {h}:{m:02}\n{p:.2}%",
-                d = get_day(val.x),
-                h = get_hour(val.x),
-                m = get_minute(val.x),
-                p = get_percent(val.y)
-            )
+        let label_fmt = |s: &str, val: &Value| {
+            if s == "Growth rate" {
+                format!(
+                    "Day {d}, {h}:{m:02}\n{r:.2}%/h",
+                    d = get_day(val.x),
+                    h = get_hour(val.x),
+                    m = get_minute(val.x),
+                    r = val.y
+                )
+            } else {
+                format!(
+                    "Day {d}, {h}:{m:02}\n{p:.2}%",
+                    d = get_day(val.x),
+                    h = get_hour(val.x),
+                    m = get_minute(val.x),
+                    p = get_percent(val.y)
+                )
+            }
         };
 
+        let rate_axis = SecondaryYAxis::new()
+            .label("Growth rate")
+            .formatter(|y, _range| format!("{:.1}%/h", y))
+            .include_y(0.0);
+
         ui.label("Zoom in on the X-axis to see hours and minutes");
 
         Plot::new("custom_axes")
@@ -428,8 +457,14 @@ impl CustomAxisDemo {
             .y_axis_formatter(y_fmt)
             .x_grid_spacer(CustomAxisDemo::x_grid)
             .label_formatter(label_fmt)
+            .x_axis_label("Time")
+            .y_axis_label("Population")
+            .secondary_y_axis(rate_axis)
             .show(ui, |plot_ui| {
                 plot_ui.line(CustomAxisDemo::logistic_fn());
+                plot_ui.on_secondary_y_axis(|plot_ui| {
+                    plot_ui.line(CustomAxisDemo::logistic_rate_fn());
+                });
             })
             .response
     }
@@ -587,24 +622,71 @@ impl ItemsDemo {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Default, PartialEq)]
-struct InteractionDemo {}
+#[derive(PartialEq)]
+struct InteractionDemo {
+    threshold: f64,
+    points: Vec<Value>,
+    brush: bool,
+}
+
+impl Default for InteractionDemo {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            points: (0..8)
+                .map(|i| Value::new(i as f64 * 0.25, (i as f64).sin() * 0.5))
+                .collect(),
+            brush: false,
+        }
+    }
+}
 
 impl InteractionDemo {
-    #[allow(clippy::unused_self)]
     fn ui(&mut self, ui: &mut Ui) -> Response {
-        let plot = Plot::new("interaction_demo").height(300.0);
+        ui.label("Drag the horizontal line and the points around.");
+        ui.checkbox(
+            &mut self.brush,
+            "Select points with a brush instead of moving the plot",
+        );
 
+        let mut plot = Plot::new("interaction_demo").height(300.0);
+        if self.brush {
+            plot = plot.brush(BrushMode::Rect);
+        }
+
+        let Self {
+            threshold, points, ..
+        } = self;
         let InnerResponse {
             response,
-            inner: (screen_pos, pointer_coordinate, pointer_coordinate_drag_delta, bounds, hovered),
+            inner:
+                (
+                    screen_pos,
+                    pointer_coordinate,
+                    pointer_coordinate_drag_delta,
+                    bounds,
+                    hovered,
+                    selection,
+                ),
         } = plot.show(ui, |plot_ui| {
+            plot_ui
+                .hline(HLine::new(*threshold).name("threshold").draggable(true))
+                .on_drag(|_, target| *threshold = target.y);
+            plot_ui
+                .points(
+                    Points::new(Values::from_values(points.clone()))
+                        .name("points")
+                        .radius(5.0)
+                        .draggable(true),
+                )
+                .on_drag(|index, target| points[index] = target);
             (
                 plot_ui.screen_from_plot(Value::new(0.0, 0.0)),
                 plot_ui.pointer_coordinate(),
                 plot_ui.pointer_coordinate_drag_delta(),
                 plot_ui.plot_bounds(),
                 plot_ui.plot_hovered(),
+                plot_ui.selection().cloned(),
             )
         });
 
@@ -632,6 +714,11 @@ impl InteractionDemo {
             "pointer coordinate drag delta: {}",
             coordinate_text
         ));
+        ui.label(format!("threshold: {:.02}", self.threshold));
+        if let Some(selection) = selection {
+            let selected: Vec<usize> = selection.indices("points").collect();
+            ui.label(format!("selected points: {:?}", selected));
+        }
 
         response
     }
@@ -644,6 +731,10 @@ enum Chart {
     GaussBars,
     StackedBars,
     BoxPlot,
+    Heatmap,
+    StackedAreas,
+    ErrorBars,
+    Pie,
 }
 
 impl Default for Chart {
@@ -674,6 +765,10 @@ impl ChartsDemo {
             ui.selectable_value(&mut self.chart, Chart::GaussBars, "Histogram");
             ui.selectable_value(&mut self.chart, Chart::StackedBars, "Stacked Bar Chart");
             ui.selectable_value(&mut self.chart, Chart::BoxPlot, "Box Plot");
+            ui.selectable_value(&mut self.chart, Chart::Heatmap, "Heatmap");
+            ui.selectable_value(&mut self.chart, Chart::StackedAreas, "Stacked Areas");
+            ui.selectable_value(&mut self.chart, Chart::ErrorBars, "Error Bars");
+            ui.selectable_value(&mut self.chart, Chart::Pie, "Pie Chart");
         });
         ui.label("Orientation:");
         ui.horizontal(|ui| {
@@ -684,6 +779,10 @@ impl ChartsDemo {
             Chart::GaussBars => self.bar_gauss(ui),
             Chart::StackedBars => self.bar_stacked(ui),
             Chart::BoxPlot => self.box_plot(ui),
+            Chart::Heatmap => self.heatmap(ui),
+            Chart::StackedAreas => self.stacked_areas(ui),
+            Chart::ErrorBars => self.error_bars(ui),
+            Chart::Pie => self.pie(ui),
         }
     }
 
@@ -819,6 +918,118 @@ impl ChartsDemo {
             })
             .response
     }
+
+    fn heatmap(&self, ui: &mut Ui) -> Response {
+        let (columns, rows) = (40, 30);
+        let values = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                // Two waves interfering:
+                let (x, y) = (column as f64 - 12.0, row as f64 - 15.0);
+                let (u, v) = (column as f64 - 28.0, row as f64 - 15.0);
+                (x.hypot(y) * 0.8).cos() + (u.hypot(v) * 0.8).cos()
+            })
+            .collect();
+        let heatmap = Heatmap::new(values, columns)
+            .cell_size(0.1, 0.1)
+            .colormap(Colormap::Viridis)
+            .name("Interference");
+
+        Plot::new("Heatmap Demo")
+            .legend(Legend::default())
+            .data_aspect(1.0)
+            .show(ui, |plot_ui| plot_ui.heatmap(heatmap))
+            .response
+    }
+
+    fn stacked_areas(&self, ui: &mut Ui) -> Response {
+        let area = |name: &str, y: fn(f64) -> f64| {
+            let values = (0..=48)
+                .map(|i| i as f64 * 0.25)
+                .map(|x| Value::new(x, y(x)));
+            AreaChart::new(Values::from_values_iter(values)).name(name)
+        };
+        let coal = area("Coal", |x| 3.0 - 0.2 * x);
+        let gas = area("Gas", |x| 2.0 + 0.3 * (x * 0.8).sin()).stack_on(&[&coal]);
+        let solar = area("Solar", |x| 0.05 * x * x).stack_on(&[&coal, &gas]);
+
+        Plot::new("Stacked Area Demo")
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                plot_ui.area_chart(coal);
+                plot_ui.area_chart(gas);
+                plot_ui.area_chart(solar);
+            })
+            .response
+    }
+
+    fn pie(&self, ui: &mut Ui) -> Response {
+        let budget = PieChart::new(vec![
+            Slice::new(42.0).name("Rent"),
+            Slice::new(18.0).name("Groceries"),
+            Slice::new(12.0).name("Transport"),
+            Slice::new(8.0).name("Savings").explode(0.1),
+        ])
+        .name("Budget");
+        let day = PieChart::new(vec![
+            Slice::new(8.0).name("Sleep"),
+            Slice::new(8.0).name("Work"),
+            Slice::new(3.0).name("Commute"),
+            Slice::new(5.0).name("Leisure"),
+        ])
+        .name("Day")
+        .center(Value::new(3.5, 0.0))
+        .donut(0.5);
+
+        Plot::new("Pie Chart Demo")
+            .legend(Legend::default())
+            .data_aspect(1.0)
+            .show_axes([false, false])
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.pie_chart(budget);
+                plot_ui.pie_chart(day);
+            })
+            .response
+    }
+
+    fn error_bars(&self, ui: &mut Ui) -> Response {
+        let fit = |x: f64| 1.0 + 0.5 * x;
+        let xs: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let band = |offset: f64| {
+            Values::from_explicit_callback(
+                move |x| fit(x) + offset * (1.0 + 0.1 * x),
+                0.0..9.0,
+                100,
+            )
+        };
+        let measured = xs
+            .iter()
+            .map(|&x| Value::new(x, fit(x) + 0.4 * (x * 2.3).sin()));
+        let errors = xs.iter().map(|&x| 0.3 + 0.05 * x);
+
+        Plot::new("Error Bar Demo")
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                plot_ui.area_chart(
+                    AreaChart::between(band(0.5), band(-0.5))
+                        .width(0.0)
+                        .fill_alpha(0.2)
+                        .name("Confidence band"),
+                );
+                plot_ui
+                    .line(Line::new(Values::from_explicit_callback(fit, 0.0..9.0, 2)).name("Fit"));
+                plot_ui.points(
+                    Points::new(Values::from_values_iter(measured))
+                        .radius(3.0)
+                        .error_bars(ErrorBars::new().y(errors).x([0.2; 10]))
+                        .name("Measurements"),
+                );
+            })
+            .response
+    }
 }
 
 // ----------------------------------------------------------------------------
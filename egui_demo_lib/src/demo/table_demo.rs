@@ -11,7 +11,14 @@ enum DemoType {
 pub struct TableDemo {
     demo: DemoType,
     resizable: bool,
+    reorderable: bool,
+    hideable: bool,
+    selectable: bool,
+    hscroll: bool,
     num_rows: usize,
+    scroll_to_row: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    scroll_to_row_now: bool,
 }
 
 impl Default for TableDemo {
@@ -19,7 +26,13 @@ impl Default for TableDemo {
         Self {
             demo: DemoType::Manual,
             resizable: true,
+            reorderable: true,
+            hideable: true,
+            selectable: true,
+            hscroll: false,
             num_rows: 10_000,
+            scroll_to_row: 0,
+            scroll_to_row_now: false,
         }
     }
 }
@@ -45,6 +58,22 @@ impl super::View for TableDemo {
     fn ui(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             ui.checkbox(&mut self.resizable, "Resizable columns");
+            ui.checkbox(
+                &mut self.reorderable,
+                "Reorderable columns (drag the header)",
+            );
+            ui.checkbox(
+                &mut self.hideable,
+                "Hideable columns (right-click the header)",
+            );
+            ui.checkbox(
+                &mut self.selectable,
+                "Selectable rows (Ctrl or Shift to select more, arrow keys to move)",
+            );
+            ui.checkbox(
+                &mut self.hscroll,
+                "Horizontal scrolling, with the first column frozen (make the columns wider)",
+            );
 
             ui.label("Table type:");
             ui.radio_value(&mut self.demo, DemoType::Manual, "Few, manual rows");
@@ -66,6 +95,11 @@ impl super::View for TableDemo {
                         .text("Num rows"),
                 );
             }
+
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.scroll_to_row).speed(1.0));
+                self.scroll_to_row_now = ui.button("Scroll to row").clicked();
+            });
         });
 
         ui.separator();
@@ -90,53 +124,55 @@ impl super::View for TableDemo {
 
 impl TableDemo {
     fn table_ui(&mut self, ui: &mut egui::Ui) {
-        use egui_extras::{Size, TableBuilder};
+        use egui_extras::{SelectionMode, Size, SortOrder, TableBuilder};
 
         let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
 
-        TableBuilder::new(ui)
+        let mut builder = TableBuilder::new(ui)
             .striped(true)
             .cell_layout(egui::Layout::left_to_right().with_cross_align(egui::Align::Center))
             .column(Size::initial(60.0).at_least(40.0))
             .column(Size::initial(60.0).at_least(40.0))
             .column(Size::remainder().at_least(60.0))
             .resizable(self.resizable)
-            .header(20.0, |mut header| {
-                header.col(|ui| {
-                    ui.heading("Row");
-                });
-                header.col(|ui| {
-                    ui.heading("Clock");
-                });
-                header.col(|ui| {
-                    ui.heading("Content");
-                });
-            })
-            .body(|mut body| match self.demo {
-                DemoType::Manual => {
-                    for row_index in 0..20 {
-                        let is_thick = thick_row(row_index);
-                        let row_height = if is_thick { 30.0 } else { 18.0 };
-                        body.row(row_height, |mut row| {
-                            row.col(|ui| {
-                                ui.label(row_index.to_string());
-                            });
-                            row.col(|ui| {
-                                ui.label(clock_emoji(row_index));
-                            });
-                            row.col(|ui| {
-                                ui.style_mut().wrap = Some(false);
-                                if is_thick {
-                                    ui.heading("Extra thick row");
-                                } else {
-                                    ui.label("Normal row");
-                                }
-                            });
-                        });
-                    }
-                }
-                DemoType::ManyHomogenous => {
-                    body.rows(text_height, self.num_rows, |row_index, mut row| {
+            .reorderable(self.reorderable)
+            .hideable(self.hideable)
+            .hscroll(self.hscroll)
+            .frozen_columns(1)
+            .selection_mode(if self.selectable {
+                SelectionMode::Multiple
+            } else {
+                SelectionMode::None
+            });
+        if self.scroll_to_row_now {
+            builder = builder.scroll_to_row(self.scroll_to_row, Some(egui::Align::Center));
+        }
+        let table = builder.header(20.0, |mut header| {
+            header.sortable_col("Row");
+            header.label_col("Clock");
+            header.label_col("Content");
+        });
+
+        // Only the "Row" column is sortable, so the rows are either in order or reversed:
+        let reversed = table.sort() == Some((0, SortOrder::Descending));
+        let num_rows = match self.demo {
+            DemoType::Manual => 20,
+            _ => self.num_rows,
+        };
+        let row_index = |index: usize| {
+            if reversed {
+                num_rows - 1 - index
+            } else {
+                index
+            }
+        };
+
+        table.body(|mut body| match self.demo {
+            DemoType::Manual => {
+                for row_index in (0..num_rows).map(row_index) {
+                    let is_thick = thick_row(row_index);
+                    let row_height = if is_thick { 30.0 } else { 18.0 };
+                    body.row(row_height, |mut row| {
                         row.col(|ui| {
                             ui.label(row_index.to_string());
                         });
@@ -144,47 +180,66 @@ impl TableDemo {
                             ui.label(clock_emoji(row_index));
                         });
                         row.col(|ui| {
-                            ui.add(
-                                egui::Label::new("Thousands of rows of even height").wrap(false),
-                            );
+                            ui.style_mut().wrap = Some(false);
+                            if is_thick {
+                                ui.heading("Extra thick row");
+                            } else {
+                                ui.label("Normal row");
+                            }
                         });
                     });
                 }
-                DemoType::ManyHeterogenous => {
-                    fn row_thickness(row_index: usize) -> f32 {
-                        if thick_row(row_index) {
-                            30.0
-                        } else {
-                            18.0
-                        }
+            }
+            DemoType::ManyHomogenous => {
+                body.rows(text_height, num_rows, |index, mut row| {
+                    let row_index = row_index(index);
+                    row.col(|ui| {
+                        ui.label(row_index.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(clock_emoji(row_index));
+                    });
+                    row.col(|ui| {
+                        ui.add(egui::Label::new("Thousands of rows of even height").wrap(false));
+                    });
+                });
+            }
+            DemoType::ManyHeterogenous => {
+                fn row_thickness(row_index: usize) -> f32 {
+                    if thick_row(row_index) {
+                        30.0
+                    } else {
+                        18.0
                     }
-                    body.heterogeneous_rows(
-                        (0..self.num_rows).into_iter().map(row_thickness),
-                        |row_index, mut row| {
-                            row.col(|ui| {
-                                ui.centered_and_justified(|ui| {
-                                    ui.label(row_index.to_string());
-                                });
+                }
+                body.heterogeneous_rows(
+                    (0..num_rows).into_iter().map(row_index).map(row_thickness),
+                    |index, mut row| {
+                        let row_index = row_index(index);
+                        row.col(|ui| {
+                            ui.centered_and_justified(|ui| {
+                                ui.label(row_index.to_string());
                             });
-                            row.col(|ui| {
-                                ui.centered_and_justified(|ui| {
-                                    ui.label(clock_emoji(row_index));
-                                });
+                        });
+                        row.col(|ui| {
+                            ui.centered_and_justified(|ui| {
+                                ui.label(clock_emoji(row_index));
                             });
-                            row.col(|ui| {
-                                ui.centered_and_justified(|ui| {
-                                    ui.style_mut().wrap = Some(false);
-                                    if thick_row(row_index) {
-                                        ui.heading("Extra thick row");
-                                    } else {
-                                        ui.label("Normal row");
-                                    }
-                                });
+                        });
+                        row.col(|ui| {
+                            ui.centered_and_justified(|ui| {
+                                ui.style_mut().wrap = Some(false);
+                                if thick_row(row_index) {
+                                    ui.heading("Extra thick row");
+                                } else {
+                                    ui.label("Normal row");
+                                }
                             });
-                        },
-                    );
-                }
-            });
+                        });
+                    },
+                );
+            }
+        });
     }
 }
 
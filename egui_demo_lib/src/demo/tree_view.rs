@@ -0,0 +1,179 @@
+use egui::tree_view::{TreeData, TreeDrop, TreeView};
+
+/// A file or a directory.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct Entry {
+    name: String,
+    /// `None` for files.
+    children: Option<Vec<usize>>,
+    /// How many files to make the first time the directory is expanded.
+    unloaded: usize,
+}
+
+/// Files in directories, by index. The first entry holds the others.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct Files {
+    entries: Vec<Entry>,
+}
+
+impl Files {
+    fn add(&mut self, parent: usize, name: &str, children: Option<Vec<usize>>) -> usize {
+        let index = self.entries.len();
+        self.entries.push(Entry {
+            name: name.to_owned(),
+            children,
+            unloaded: 0,
+        });
+        if let Some(siblings) = &mut self.entries[parent].children {
+            siblings.push(index);
+        }
+        index
+    }
+
+    fn parent(&self, entry: usize) -> Option<usize> {
+        self.entries.iter().position(|parent| {
+            parent
+                .children
+                .as_ref()
+                .map_or(false, |children| children.contains(&entry))
+        })
+    }
+
+    fn move_entries(&mut self, drop: TreeDrop<usize>) {
+        let parent = drop.parent.unwrap_or(0);
+        let mut index = drop.index;
+        for entry in drop.nodes {
+            if let Some(old_parent) = self.parent(entry) {
+                if let Some(children) = &mut self.entries[old_parent].children {
+                    let old_index = children.iter().position(|child| *child == entry).unwrap();
+                    children.remove(old_index);
+                    if old_parent == parent && old_index < index {
+                        index -= 1;
+                    }
+                }
+            }
+            if let Some(children) = &mut self.entries[parent].children {
+                children.insert(index.min(children.len()), entry);
+                index += 1;
+            }
+        }
+    }
+}
+
+impl Default for Files {
+    fn default() -> Self {
+        let mut files = Self {
+            entries: vec![Entry {
+                name: String::new(),
+                children: Some(Vec::new()),
+                unloaded: 0,
+            }],
+        };
+        let src = files.add(0, "src", Some(Vec::new()));
+        files.add(src, "main.rs", None);
+        let widgets = files.add(src, "widgets", Some(Vec::new()));
+        files.add(widgets, "button.rs", None);
+        files.add(widgets, "label.rs", None);
+        files.add(widgets, "tree.rs", None);
+        let assets = files.add(0, "assets", Some(Vec::new()));
+        files.add(assets, "icon.png", None);
+        files.add(assets, "logo.svg", None);
+        let generated = files.add(0, "generated", Some(Vec::new()));
+        files.entries[generated].unloaded = 10_000;
+        files.add(0, "Cargo.toml", None);
+        files.add(0, "README.md", None);
+        files
+    }
+}
+
+impl TreeData for Files {
+    type Node = usize;
+
+    fn roots(&mut self) -> Vec<usize> {
+        self.children(&0)
+    }
+
+    fn is_branch(&mut self, node: &usize) -> bool {
+        self.entries[*node].children.is_some()
+    }
+
+    fn children(&mut self, node: &usize) -> Vec<usize> {
+        // Made when they are first shown:
+        for i in 0..std::mem::take(&mut self.entries[*node].unloaded) {
+            self.add(*node, &format!("file_{}.txt", i), None);
+        }
+        self.entries[*node].children.clone().unwrap_or_default()
+    }
+
+    fn node_ui(&mut self, ui: &mut egui::Ui, node: &usize) {
+        let entry = &self.entries[*node];
+        let icon = if entry.children.is_some() {
+            "🗀"
+        } else {
+            "🗋"
+        };
+        ui.add(egui::Label::new(format!("{} {}", icon, entry.name)).wrap(false));
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TreeViewDemo {
+    files: Files,
+    multi_select: bool,
+    drag_and_drop: bool,
+}
+
+impl Default for TreeViewDemo {
+    fn default() -> Self {
+        Self {
+            files: Files::default(),
+            multi_select: true,
+            drag_and_drop: true,
+        }
+    }
+}
+
+impl super::Demo for TreeViewDemo {
+    fn name(&self) -> &'static str {
+        "🗁 Tree View"
+    }
+
+    fn show(&mut self, ctx: &egui::Context, open: &mut bool) {
+        use super::View as _;
+        egui::Window::new(self.name())
+            .default_width(300.0)
+            .default_height(400.0)
+            .vscroll(false)
+            .open(open)
+            .show(ctx, |ui| self.ui(ui));
+    }
+}
+
+impl super::View for TreeViewDemo {
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(
+            &mut self.multi_select,
+            "Select more with Ctrl (Cmd on Mac) and Shift",
+        );
+        ui.checkbox(
+            &mut self.drag_and_drop,
+            "Drag the files to other directories",
+        );
+        ui.label("Click a file to move through them with the arrow keys.");
+        ui.vertical_centered(|ui| {
+            ui.add(crate::egui_github_link_file!());
+        });
+        ui.separator();
+
+        let response = TreeView::new("tree_view_demo")
+            .multi_select(self.multi_select)
+            .drag_and_drop(self.drag_and_drop)
+            .show(ui, &mut self.files);
+        if let Some(drop) = response.dropped {
+            self.files.move_entries(drop);
+        }
+    }
+}
@@ -136,7 +136,7 @@ fn format_from_style(
     egui_style: &egui::Style,
     emark_style: &easy_mark_parser::Style,
 ) -> egui::text::TextFormat {
-    use egui::{Align, Color32, Stroke, TextStyle};
+    use egui::{Align, Color32, FontStyle, FontWeight, Stroke, TextStyle};
 
     let color = if emark_style.strong || emark_style.heading {
         egui_style.visuals.strong_text_color()
@@ -180,13 +180,21 @@ fn format_from_style(
         Align::BOTTOM
     };
 
+    let mut font_id = text_style.resolve(egui_style);
+    if emark_style.strong {
+        font_id.weight = FontWeight::BOLD;
+    }
+    if emark_style.italics {
+        font_id.style = FontStyle::Italic;
+    }
+
     egui::text::TextFormat {
-        font_id: text_style.resolve(egui_style),
+        font_id,
         color,
         background,
-        italics: emark_style.italics,
         underline,
         strikethrough,
         valign,
+        ..Default::default()
     }
 }
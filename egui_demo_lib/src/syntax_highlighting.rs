@@ -353,7 +353,11 @@ impl Highlighter {
             for (style, range) in h.highlight(line, &self.ps) {
                 let fg = style.foreground;
                 let text_color = egui::Color32::from_rgb(fg.r, fg.g, fg.b);
-                let italics = style.font_style.contains(FontStyle::ITALIC);
+                let font_id = if style.font_style.contains(FontStyle::ITALIC) {
+                    egui::FontId::monospace(14.0).italic()
+                } else {
+                    egui::FontId::monospace(14.0)
+                };
                 let underline = style.font_style.contains(FontStyle::ITALIC);
                 let underline = if underline {
                     egui::Stroke::new(1.0, text_color)
@@ -364,9 +368,8 @@ impl Highlighter {
                     leading_space: 0.0,
                     byte_range: as_byte_range(text, range),
                     format: TextFormat {
-                        font_id: egui::FontId::monospace(14.0),
+                        font_id,
                         color: text_color,
-                        italics,
                         underline,
                         ..Default::default()
                     },
@@ -89,6 +89,25 @@ impl<'l> StripLayout<'l> {
         self.set_pos(self.cell_rect(&width, &height));
     }
 
+    /// Where the next cell goes.
+    pub(crate) fn cursor(&self) -> Pos2 {
+        self.cursor
+    }
+
+    /// Put the next cell this far from the left of the layout,
+    /// for the rows of a [`Table`](crate::Table) with reordered columns.
+    pub(crate) fn set_cell_offset(&mut self, offset: f32) {
+        self.cursor.x = self.rect.left() + offset;
+    }
+
+    /// A cell that is not shown, like that of a hidden column.
+    pub(crate) fn skip_cell(&mut self) -> Response {
+        self.ui.allocate_rect(
+            Rect::from_min_size(self.cursor, egui::Vec2::ZERO),
+            Sense::hover(),
+        )
+    }
+
     pub(crate) fn add(
         &mut self,
         width: CellSize,
@@ -119,6 +138,11 @@ impl<'l> StripLayout<'l> {
         self.add(width, height, add_contents)
     }
 
+    /// Don't let the cells go beyond this when allocating the rect, like those that are scrolled out of view.
+    pub(crate) fn limit_right(&mut self, right: f32) {
+        self.max.x = self.max.x.min(right);
+    }
+
     /// only needed for layouts with multiple lines, like [`Table`](crate::Table).
     pub fn end_line(&mut self) {
         match self.direction {
@@ -9,7 +9,248 @@ use crate::{
     Size, StripLayout,
 };
 
-use egui::{Rect, Response, Ui, Vec2};
+use egui::{Align, Id, Rect, Response, Sense, Ui, Vec2, WidgetText};
+use std::collections::BTreeSet;
+
+/// The order a table is sorted in, see [`TableRow::sortable_col`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// How many rows of a [`Table`] the user can select, see [`TableBuilder::selection_mode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SelectionMode {
+    /// The rows can't be selected.
+    None,
+
+    /// One row at a time.
+    Single,
+
+    /// Several rows, with Ctrl (Cmd on Mac) to add and remove single rows and Shift to select a range.
+    Multiple,
+}
+
+impl Default for SelectionMode {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// The rows the user has selected in a [`Table`], by index.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct RowSelection {
+    /// The selected rows.
+    pub rows: BTreeSet<usize>,
+
+    /// The row that was clicked or moved to with the keyboard last.
+    pub active: Option<usize>,
+
+    /// Where a range selected with Shift starts.
+    pub anchor: Option<usize>,
+}
+
+impl RowSelection {
+    /// Select the row like when it is clicked with these modifiers.
+    fn click(&mut self, row: usize, mode: SelectionMode, modifiers: egui::Modifiers) {
+        let range_start = match mode {
+            SelectionMode::Multiple if modifiers.shift => self.anchor,
+            _ => None,
+        };
+        if let Some(anchor) = range_start {
+            if !modifiers.command {
+                self.rows.clear();
+            }
+            self.rows.extend(anchor.min(row)..=anchor.max(row));
+        } else if modifiers.command {
+            if !self.rows.remove(&row) {
+                if mode == SelectionMode::Single {
+                    self.rows.clear();
+                }
+                self.rows.insert(row);
+            }
+            self.anchor = Some(row);
+        } else {
+            self.rows.clear();
+            self.rows.insert(row);
+            self.anchor = Some(row);
+        }
+        self.active = Some(row);
+    }
+
+    /// Move the active row with the arrow keys, Page Up/Down, Home and End, selecting it.
+    /// With Shift, the selection is extended to it instead.
+    ///
+    /// Returns the row moved to, if any.
+    fn keyboard_navigation(
+        &mut self,
+        input: &egui::InputState,
+        mode: SelectionMode,
+        num_rows: usize,
+        rows_per_page: usize,
+    ) -> Option<usize> {
+        use egui::Key;
+
+        let last = num_rows.checked_sub(1)?;
+        let page = rows_per_page.max(1);
+        let mut moved_to = None;
+        for event in &input.events {
+            if let egui::Event::Key {
+                key,
+                pressed: true,
+                modifiers,
+            } = event
+            {
+                let from = moved_to.or(self.active).map(|row: usize| row.min(last));
+                let row = match key {
+                    Key::ArrowUp => from.map_or(0, |row| row.saturating_sub(1)),
+                    Key::ArrowDown => from.map_or(0, |row| (row + 1).min(last)),
+                    Key::PageUp => from.map_or(0, |row| row.saturating_sub(page)),
+                    Key::PageDown => from.map_or(0, |row| (row + page).min(last)),
+                    Key::Home => 0,
+                    Key::End => last,
+                    _ => continue,
+                };
+                let modifiers = egui::Modifiers {
+                    command: false,
+                    ..*modifiers
+                };
+                self.click(row, mode, modifiers);
+                moved_to = Some(row);
+            }
+        }
+        moved_to
+    }
+}
+
+/// What the user has done to the columns of a [`Table`]: resized, reordered, hidden and sorted them,
+/// and which rows they have selected.
+///
+/// It is persisted with the id of the table, see [`TableBuilder::id_source`].
+/// All columns are referred to by the index they were added with.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct TableState {
+    /// The widths of the columns.
+    pub widths: Vec<f32>,
+
+    /// The columns, in the order they are shown.
+    pub order: Vec<usize>,
+
+    /// Which columns are hidden.
+    pub hidden: Vec<bool>,
+
+    /// The column to sort by, and in which order.
+    pub sort: Option<(usize, SortOrder)>,
+
+    /// The selected rows.
+    pub selection: RowSelection,
+}
+
+impl TableState {
+    fn new(num_columns: usize) -> Self {
+        Self {
+            widths: Vec::new(),
+            order: (0..num_columns).collect(),
+            hidden: vec![false; num_columns],
+            sort: None,
+            selection: Default::default(),
+        }
+    }
+
+    /// Load the state of the table with the given id.
+    pub fn load(ctx: &egui::Context, table_id: Id) -> Option<Self> {
+        ctx.data().get_persisted(table_id.with("__table_state"))
+    }
+
+    /// Store the state of the table with the given id.
+    pub fn store(self, ctx: &egui::Context, table_id: Id) {
+        ctx.data()
+            .insert_persisted(table_id.with("__table_state"), self);
+    }
+
+    /// Does the state fit a table with this many columns?
+    fn is_valid(&self, num_columns: usize) -> bool {
+        let mut order = self.order.clone();
+        order.sort_unstable();
+        order.into_iter().eq(0..num_columns)
+            && self.hidden.len() == num_columns
+            && self.sort.map_or(true, |(column, _)| column < num_columns)
+    }
+
+    /// The columns that are shown, in order.
+    fn visible_columns(&self) -> impl Iterator<Item = usize> + '_ {
+        self.order
+            .iter()
+            .copied()
+            .filter(|column| !self.hidden[*column])
+    }
+
+    /// Show the column before another one, or last.
+    fn move_column(&mut self, column: usize, before: Option<usize>) {
+        self.order.retain(|other| *other != column);
+        let index = before
+            .and_then(|before| self.order.iter().position(|other| *other == before))
+            .unwrap_or(self.order.len());
+        self.order.insert(index, column);
+    }
+
+    /// Where the cells of each column go in a row that isn't scrolled horizontally,
+    /// with the first `frozen_columns` that are shown frozen.
+    fn cell_positions(&self, spacing: f32, frozen_columns: usize) -> CellPositions {
+        let mut positions = CellPositions {
+            cells: vec![None; self.order.len()],
+            frozen: vec![false; self.order.len()],
+            scroll_clip: None,
+        };
+        let mut x = 0.0;
+        for (i, column) in self.visible_columns().enumerate() {
+            positions.cells[column] = Some((x, self.widths[column]));
+            x += self.widths[column] + spacing;
+            if i < frozen_columns {
+                positions.frozen[column] = true;
+                positions.scroll_clip = Some(x - 0.5 * spacing);
+            }
+        }
+        positions
+    }
+}
+
+/// Where the cells of each column go in a row.
+#[derive(Clone)]
+struct CellPositions {
+    /// How far from the left of the row each cell is, and how wide. `None` for hidden columns.
+    cells: Vec<Option<(f32, f32)>>,
+
+    /// Which columns stay in place when the table is scrolled horizontally.
+    frozen: Vec<bool>,
+
+    /// How far from the left of the row the other columns are clipped, so that they don't go under the frozen ones.
+    scroll_clip: Option<f32>,
+}
+
+impl CellPositions {
+    /// The positions in a table scrolled `scroll_x` to the right, for a row that starts `view_left` left of the view.
+    fn scrolled(mut self, scroll_x: f32, view_left: f32) -> Self {
+        for (cell, frozen) in self.cells.iter_mut().zip(&self.frozen) {
+            if let Some((x, _)) = cell {
+                *x += if *frozen {
+                    view_left
+                } else {
+                    view_left - scroll_x
+                };
+            }
+        }
+        if let Some(scroll_clip) = &mut self.scroll_clip {
+            *scroll_clip += view_left;
+        }
+        self
+    }
+}
 
 /// Builder for a [`Table`] with (optional) fixed header and scrolling body.
 ///
@@ -49,12 +290,52 @@ use egui::{Rect, Response, Ui, Vec2};
 ///     });
 /// # });
 /// ```
+///
+/// The user can also sort the table by clicking a header, and reorder and hide columns:
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui_extras::{SortOrder, TableBuilder, Size};
+/// let mut fruits = vec![("Banana", 3), ("Apple", 5), ("Cherry", 1)];
+/// let table = TableBuilder::new(ui)
+///     .columns(Size::remainder(), 2)
+///     .reorderable(true)
+///     .hideable(true)
+///     .header(20.0, |mut header| {
+///         header.sortable_col("Fruit");
+///         header.sortable_col("Count");
+///     });
+/// if let Some((column, order)) = table.sort() {
+///     match column {
+///         0 => fruits.sort_by_key(|(name, _)| *name),
+///         _ => fruits.sort_by_key(|(_, count)| *count),
+///     }
+///     if order == SortOrder::Descending {
+///         fruits.reverse();
+///     }
+/// }
+/// table.body(|mut body| {
+///     for (name, count) in fruits {
+///         body.row(18.0, |mut row| {
+///             row.label_col(name);
+///             row.label_col(count.to_string());
+///         });
+///     }
+/// });
+/// # });
+/// ```
 pub struct TableBuilder<'a> {
     ui: &'a mut Ui,
+    id: Id,
     sizing: Sizing,
     scroll: bool,
+    hscroll: bool,
+    frozen_columns: usize,
     striped: bool,
     resizable: bool,
+    reorderable: bool,
+    hideable: bool,
+    selection_mode: SelectionMode,
+    scroll_to_row: Option<(usize, Option<Align>)>,
     clip: bool,
     cell_layout: egui::Layout,
 }
@@ -62,23 +343,58 @@ pub struct TableBuilder<'a> {
 impl<'a> TableBuilder<'a> {
     pub fn new(ui: &'a mut Ui) -> Self {
         let cell_layout = *ui.layout();
+        let id = ui.id();
         Self {
             ui,
+            id,
             sizing: Default::default(),
             scroll: true,
+            hscroll: false,
+            frozen_columns: 0,
             striped: false,
             resizable: false,
+            reorderable: false,
+            hideable: false,
+            selection_mode: SelectionMode::None,
+            scroll_to_row: None,
             clip: true,
             cell_layout,
         }
     }
 
+    /// The column widths, order, visibility and sorting are stored with this,
+    /// see [`TableState`]. Default is the id of the [`Ui`].
+    ///
+    /// Only a table that is resizable, reorderable, hideable, selectable or sortable stores its state,
+    /// so two of those in the same [`Ui`] need different ids.
+    pub fn id_source(mut self, id_source: impl std::hash::Hash) -> Self {
+        self.id = self.ui.id().with(id_source);
+        self
+    }
+
     /// Enable scrollview in body (default: true)
     pub fn scroll(mut self, scroll: bool) -> Self {
         self.scroll = scroll;
         self
     }
 
+    /// Enable horizontal scrolling, for when the columns are wider than the table (default: false).
+    ///
+    /// The header scrolls along with the body, except for the [`Self::frozen_columns`].
+    pub fn hscroll(mut self, hscroll: bool) -> Self {
+        self.hscroll = hscroll;
+        self
+    }
+
+    /// Keep this many columns on the left in place when scrolling horizontally, see [`Self::hscroll`].
+    ///
+    /// These are the first columns that are shown, also when the user has reordered them.
+    /// Default is `0`.
+    pub fn frozen_columns(mut self, frozen_columns: usize) -> Self {
+        self.frozen_columns = frozen_columns;
+        self
+    }
+
     /// Enable striped row background (default: false)
     pub fn striped(mut self, striped: bool) -> Self {
         self.striped = striped;
@@ -93,12 +409,52 @@ impl<'a> TableBuilder<'a> {
     /// Default is `false`.
     ///
     /// If you have multiple [`Table`]:s in the same [`Ui`]
-    /// you will need to give them unique id:s with [`Ui::push_id`].
+    /// you will need to give them unique id:s with [`Ui::push_id`] or [`Self::id_source`].
     pub fn resizable(mut self, resizable: bool) -> Self {
         self.resizable = resizable;
         self
     }
 
+    /// Let the user reorder the columns by dragging their header cells.
+    ///
+    /// Default is `false`.
+    pub fn reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// Let the user hide and show columns with a menu, by right-clicking the header.
+    ///
+    /// The columns are named in the menu by the text of [`TableRow::sortable_col`] or [`TableRow::label_col`].
+    ///
+    /// Default is `false`.
+    pub fn hideable(mut self, hideable: bool) -> Self {
+        self.hideable = hideable;
+        self
+    }
+
+    /// Let the user select rows by clicking them, and move the selection with the keyboard.
+    ///
+    /// With the keyboard focus on the table, the arrow keys, Page Up/Down, Home and End
+    /// select another row and scroll to it, and extend the selection with Shift.
+    /// Read the selection with [`TableBody::selection`] or [`TableRow::is_selected`].
+    ///
+    /// Default is [`SelectionMode::None`].
+    pub fn selection_mode(mut self, selection_mode: SelectionMode) -> Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+
+    /// Scroll the body so that the row with this index is visible, also if it isn't laid out,
+    /// like the rows of [`TableBody::rows`] outside the view.
+    ///
+    /// `align` is where in the view to put the row ([`Align::TOP`], [`Align::Center`] or [`Align::BOTTOM`]).
+    /// If `None`, it scrolls just enough to bring the row into view.
+    pub fn scroll_to_row(mut self, row: usize, align: Option<Align>) -> Self {
+        self.scroll_to_row = Some((row, align));
+        self
+    }
+
     /// Should we clip the contents of each cell? Default: `true`.
     pub fn clip(mut self, clip: bool) -> Self {
         self.clip = clip;
@@ -136,49 +492,67 @@ impl<'a> TableBuilder<'a> {
 
     /// Create a header row which always stays visible and at the top
     pub fn header(self, height: f32, header: impl FnOnce(TableRow<'_, '_>)) -> Table<'a> {
-        let available_width = self.available_width();
-
-        let Self {
-            ui,
-            sizing,
-            scroll,
-            striped,
-            resizable,
-            clip,
-            cell_layout,
-        } = self;
+        let mut table = self.begin();
 
-        let resize_id = resizable.then(|| ui.id().with("__table_resize"));
-
-        let default_widths = sizing.to_lengths(available_width, ui.spacing().item_spacing.x);
-        let widths = read_persisted_widths(ui, default_widths, resize_id);
-
-        let table_top = ui.cursor().top();
+        // The header is scrolled like the body was, the last frame:
+        let scroll_x = if table.hscroll {
+            let scroll_x_id = table.id.with("__table_scroll_x");
+            table.ui.data().get_temp(scroll_x_id).unwrap_or_default()
+        } else {
+            0.0
+        };
+        let positions = table
+            .state
+            .cell_positions(table.ui.spacing().item_spacing.x, table.frozen_columns)
+            .scrolled(scroll_x, 0.0);
+        let num_columns = positions.cells.len();
+        let mut header_cells = HeaderCells {
+            sort: table.state.sort,
+            sortable: vec![false; num_columns],
+            names: vec![None; num_columns],
+            rects: vec![None; num_columns],
+        };
 
-        {
+        let (clip, cell_layout, hscroll) = (table.clip, table.cell_layout, table.hscroll);
+        let add_header = |ui: &mut Ui| {
+            let right = ui.max_rect().right();
             let mut layout = StripLayout::new(ui, CellDirection::Horizontal, clip, cell_layout);
             header(TableRow {
                 layout: &mut layout,
-                widths: &widths,
-                width_index: 0,
+                positions: &positions,
+                column: 0,
                 striped: false,
+                selected: false,
                 height,
+                header: Some(&mut header_cells),
             });
-            layout.allocate_rect();
-        }
+            if hscroll {
+                layout.limit_right(right);
+            }
+            layout.allocate_rect().rect
+        };
+        let header_rect = if hscroll {
+            // In a ui of its own, so that the columns that are scrolled out of view are clipped,
+            // and don't make the table grow:
+            let mut max_rect = table.ui.available_rect_before_wrap();
+            max_rect.set_width(table.available_width);
+            let mut header_ui = table.ui.child_ui(max_rect, *table.ui.layout());
+            let mut clip_rect = header_ui.clip_rect();
+            clip_rect.max.x = clip_rect.max.x.min(max_rect.right());
+            header_ui.set_clip_rect(clip_rect);
+            let header_rect = add_header(&mut header_ui);
+            table.ui.allocate_rect(header_rect, Sense::hover()).rect
+        } else {
+            add_header(table.ui)
+        };
 
-        Table {
-            ui,
-            table_top,
-            resize_id,
-            sizing,
-            available_width,
-            widths,
-            scroll,
-            striped,
-            clip,
-            cell_layout,
+        if !table.persist && header_cells.sortable.contains(&true) {
+            table.persist = true;
+            let rect = Rect::from_min_size(header_rect.min, Vec2::ZERO);
+            check_for_state_id_clash(table.ui, table.id, rect);
         }
+        table.header_interaction(header_rect, header_cells);
+        table
     }
 
     /// Create table body without a header row
@@ -186,58 +560,102 @@ impl<'a> TableBuilder<'a> {
     where
         F: for<'b> FnOnce(TableBody<'b>),
     {
+        self.begin().body(body);
+    }
+
+    fn begin(self) -> Table<'a> {
         let available_width = self.available_width();
 
         let Self {
             ui,
+            id,
             sizing,
             scroll,
+            hscroll,
+            frozen_columns,
             striped,
             resizable,
+            reorderable,
+            hideable,
+            selection_mode,
+            scroll_to_row,
             clip,
             cell_layout,
         } = self;
 
-        let resize_id = resizable.then(|| ui.id().with("__table_resize"));
+        let num_columns = sizing.sizes.len();
+        let persist = resizable || reorderable || hideable || selection_mode != SelectionMode::None;
+        if persist {
+            let rect = Rect::from_min_size(ui.available_rect_before_wrap().min, Vec2::ZERO);
+            check_for_state_id_clash(ui, id, rect);
+        }
+        let stored = TableState::load(ui.ctx(), id)
+            // make sure that the stored state isn't out-dated
+            .filter(|state| state.is_valid(num_columns));
+        let mut state = match stored {
+            Some(state) if persist => state,
+            // Only a sortable header can use the stored sorting then, see `Table::persist`:
+            stored => TableState {
+                sort: stored.and_then(|state| state.sort),
+                ..TableState::new(num_columns)
+            },
+        };
 
-        let default_widths = sizing.to_lengths(available_width, ui.spacing().item_spacing.x);
-        let widths = read_persisted_widths(ui, default_widths, resize_id);
+        if !resizable || state.widths.len() != num_columns {
+            // The hidden columns give their space to the others:
+            let visible: Vec<usize> = state.visible_columns().collect();
+            let visible_sizing = Sizing {
+                sizes: visible.iter().map(|column| sizing.sizes[*column]).collect(),
+            };
+            let default_widths =
+                visible_sizing.to_lengths(available_width, ui.spacing().item_spacing.x);
+            state.widths.resize(num_columns, 0.0);
+            for (column, width) in visible.into_iter().zip(default_widths) {
+                state.widths[column] = width;
+            }
+        }
 
         let table_top = ui.cursor().top();
 
         Table {
             ui,
+            id,
             table_top,
-            resize_id,
+            persist,
+            resizable,
+            reorderable,
+            hideable,
+            selection_mode,
+            scroll_to_row,
             sizing,
             available_width,
-            widths,
+            state,
             scroll,
+            hscroll,
+            // Without horizontal scrolling, all columns stay in place anyway:
+            frozen_columns: if hscroll { frozen_columns } else { 0 },
             striped,
             clip,
             cell_layout,
         }
-        .body(body);
     }
 }
 
-fn read_persisted_widths(
-    ui: &egui::Ui,
-    default_widths: Vec<f32>,
-    resize_id: Option<egui::Id>,
-) -> Vec<f32> {
-    if let Some(resize_id) = resize_id {
-        let rect = Rect::from_min_size(ui.available_rect_before_wrap().min, Vec2::ZERO);
-        ui.ctx().check_for_id_clash(resize_id, rect, "Table");
-        if let Some(persisted) = ui.data().get_persisted::<Vec<f32>>(resize_id) {
-            // make sure that the stored widths aren't out-dated
-            if persisted.len() == default_widths.len() {
-                return persisted;
-            }
-        }
-    }
+/// What the header row tells the table about its cells.
+struct HeaderCells {
+    sort: Option<(usize, SortOrder)>,
+    /// Which columns sort the table when their header cell is clicked.
+    sortable: Vec<bool>,
+    /// The names of the columns, for the menu to hide them.
+    names: Vec<Option<String>>,
+    /// Where the cells of the columns are, to drag them.
+    rects: Vec<Option<Rect>>,
+}
 
-    default_widths
+/// The state of tables that share an id would be mixed up.
+fn check_for_state_id_clash(ui: &Ui, id: Id, rect: Rect) {
+    ui.ctx()
+        .check_for_id_clash(id.with("__table_state"), rect, "Table");
 }
 
 /// Table struct which can construct a [`TableBody`].
@@ -245,18 +663,135 @@ fn read_persisted_widths(
 /// Is created by [`TableBuilder`] by either calling [`TableBuilder::body`] or after creating a header row with [`TableBuilder::header`].
 pub struct Table<'a> {
     ui: &'a mut Ui,
+    id: Id,
     table_top: f32,
-    resize_id: Option<egui::Id>,
+    /// Whether the [`TableState`] is used, and so stored.
+    /// Also set by a header with a [`TableRow::sortable_col`].
+    persist: bool,
+    resizable: bool,
+    reorderable: bool,
+    hideable: bool,
+    selection_mode: SelectionMode,
+    scroll_to_row: Option<(usize, Option<Align>)>,
     sizing: Sizing,
     available_width: f32,
-    widths: Vec<f32>,
+    state: TableState,
     scroll: bool,
+    hscroll: bool,
+    frozen_columns: usize,
     striped: bool,
     clip: bool,
     cell_layout: egui::Layout,
 }
 
 impl<'a> Table<'a> {
+    /// The column to sort the rows by, and in which order, if the user has clicked a [`TableRow::sortable_col`].
+    ///
+    /// It is up to you to sort the rows before adding them to the body.
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.state.sort
+    }
+
+    /// Drag the header cells to reorder the columns, and right-click the header to hide them.
+    fn header_interaction(&mut self, header_rect: Rect, header: HeaderCells) {
+        let ui = &mut *self.ui;
+        let state = &mut self.state;
+
+        // One response for the whole header, so that the labels of the cells don't take the clicks:
+        let response = ui.interact(header_rect, self.id.with("__table_header"), Sense::click());
+        if response.clicked() {
+            let pointer = response.interact_pointer_pos();
+            let clicked_column = (0..header.sortable.len()).find(|&column| {
+                header.sortable[column]
+                    && header.rects[column]
+                        .zip(pointer)
+                        .map_or(false, |(rect, pointer)| rect.contains(pointer))
+            });
+            if let Some(column) = clicked_column {
+                let order = match state.sort {
+                    Some((sorted, SortOrder::Ascending)) if sorted == column => {
+                        SortOrder::Descending
+                    }
+                    _ => SortOrder::Ascending,
+                };
+                state.sort = Some((column, order));
+                ui.ctx().request_repaint(); // To show the new order in the header.
+            }
+        }
+
+        if self.hideable {
+            response.context_menu(|ui| {
+                let num_visible = state.visible_columns().count();
+                for &column in &state.order {
+                    let name = header.names[column]
+                        .clone()
+                        .unwrap_or_else(|| format!("Column {}", column + 1));
+                    let mut visible = !state.hidden[column];
+                    // Keep at least one column:
+                    let enabled = !visible || num_visible > 1;
+                    if ui
+                        .add_enabled(enabled, egui::Checkbox::new(&mut visible, name))
+                        .changed()
+                    {
+                        state.hidden[column] = !visible;
+                    }
+                }
+                ui.separator();
+                if ui.button("Reset columns").clicked() {
+                    state.order.sort_unstable();
+                    state.hidden.iter_mut().for_each(|hidden| *hidden = false);
+                    ui.close_menu();
+                }
+            });
+        }
+
+        if self.reorderable {
+            let cells: Vec<(usize, Rect)> = state
+                .visible_columns()
+                .filter_map(|column| Some((column, header.rects[column]?)))
+                .collect();
+            for &(column, rect) in &cells {
+                let response = ui.interact(
+                    rect,
+                    self.id.with("__table_reorder").with(column),
+                    Sense::drag(),
+                );
+                // Only once the pointer has moved too much for a click, e.g. on a sortable column:
+                let dragging = (response.dragged() || response.drag_released())
+                    && ui.input().pointer.has_moved_too_much_for_a_click();
+                let pointer = match ui.ctx().pointer_latest_pos() {
+                    Some(pointer) if dragging => pointer,
+                    _ => continue,
+                };
+
+                // Before the first column whose middle is right of the pointer:
+                let before = cells
+                    .iter()
+                    .find(|(other, rect)| *other != column && rect.center().x > pointer.x)
+                    .map(|(other, rect)| (*other, rect.left()));
+
+                if response.drag_released() {
+                    state.move_column(column, before.map(|(other, _)| other));
+                } else {
+                    let half_spacing = 0.5 * ui.spacing().item_spacing.x;
+                    let x = match before {
+                        Some((_, left)) => left - half_spacing,
+                        None => {
+                            cells.last().map_or(rect.right(), |(_, rect)| rect.right())
+                                + half_spacing
+                        }
+                    };
+                    let selection = ui.visuals().selection;
+                    ui.painter()
+                        .rect_filled(rect, 0.0, selection.bg_fill.linear_multiply(0.3));
+                    ui.painter()
+                        .vline(x, header_rect.y_range(), selection.stroke);
+                    ui.output().cursor_icon = egui::CursorIcon::Grabbing;
+                }
+            }
+        }
+    }
+
     /// Create table body after adding a header row
     pub fn body<F>(self, body: F)
     where
@@ -264,59 +799,123 @@ impl<'a> Table<'a> {
     {
         let Table {
             ui,
+            id,
             table_top,
-            resize_id,
+            persist,
+            resizable,
+            selection_mode,
+            mut scroll_to_row,
             sizing,
             mut available_width,
-            widths,
+            mut state,
             scroll,
+            hscroll,
+            frozen_columns,
             striped,
             clip,
             cell_layout,
+            ..
         } = self;
 
         let avail_rect = ui.available_rect_before_wrap();
 
-        let mut new_widths = widths.clone();
+        let widths = state.widths.clone();
+        let positions = state.cell_positions(ui.spacing().item_spacing.x, frozen_columns);
+        // The columns that scroll can only be resized where they are in view:
+        let scrolled_range = avail_rect.left() + positions.scroll_clip.unwrap_or(0.0)
+            ..=avail_rect.left() + available_width;
+
+        let body_id = id.with("__table_body");
+        let selection = &mut state.selection;
+        if selection_mode != SelectionMode::None && ui.memory().has_focus(body_id) {
+            // How many rows there were, and how many fit in the view, the last frame:
+            let (num_rows, rows_per_page) = ui
+                .data()
+                .get_temp::<(usize, usize)>(body_id)
+                .unwrap_or_default();
+            let moved_to =
+                selection.keyboard_navigation(&ui.input(), selection_mode, num_rows, rows_per_page);
+            if let Some(row) = moved_to {
+                scroll_to_row = Some((row, None));
+            }
+        }
 
-        egui::ScrollArea::new([false, scroll])
+        let output = egui::ScrollArea::new([hscroll, scroll])
             .auto_shrink([true; 2])
-            .show(ui, move |ui| {
+            .show_viewport(ui, move |ui, viewport| {
+                // The rows start left of the view by as much as it is scrolled:
+                let scroll_x = viewport.min.x;
+                let positions = positions.scrolled(scroll_x, scroll_x);
+
+                // So that the table can have the keyboard focus, for moving the selection:
+                let has_focus = selection_mode != SelectionMode::None
+                    && ui
+                        .interact(ui.clip_rect(), body_id, Sense::focusable_noninteractive())
+                        .has_focus();
+
                 let layout = StripLayout::new(ui, CellDirection::Horizontal, clip, cell_layout);
 
                 body(TableBody {
                     layout,
+                    id: body_id,
                     widths,
+                    positions,
                     striped,
+                    selection_mode,
+                    selection,
+                    has_focus,
+                    scroll_to_row,
+                    rows_in_view: 0,
                     row_nr: 0,
                     start_y: avail_rect.top(),
                     end_y: avail_rect.bottom(),
                 });
+                scroll_x
             });
+        let scroll_x = output.inner;
+
+        if hscroll {
+            // For the header the next frame:
+            let new_scroll_x = output.state.offset.x;
+            ui.data()
+                .insert_temp(id.with("__table_scroll_x"), new_scroll_x);
+            if new_scroll_x != scroll_x {
+                ui.ctx().request_repaint();
+            }
+        }
 
         let bottom = ui.min_rect().bottom();
 
         // TODO(emilk): fix frame-delay by interacting before laying out (but painting later).
-        if let Some(resize_id) = resize_id {
+        if resizable {
             let spacing_x = ui.spacing().item_spacing.x;
             let mut x = avail_rect.left() - spacing_x * 0.5;
-            for (i, width) in new_widths.iter_mut().enumerate() {
+            let visible: Vec<usize> = state.visible_columns().collect();
+            for (i, &column) in visible.iter().enumerate() {
+                let width = &mut state.widths[column];
                 x += *width + spacing_x;
 
                 // If the last column is Size::Remainder, then let it fill the remainder!
-                let last_column = i + 1 == sizing.sizes.len();
+                let last_column = i + 1 == visible.len();
                 if last_column {
-                    if let Size::Remainder { range: (min, max) } = sizing.sizes[i] {
+                    if let Size::Remainder { range: (min, max) } = sizing.sizes[column] {
                         let eps = 0.1; // just to avoid some rounding errors.
                         *width = (available_width - eps).clamp(min, max);
                         break;
                     }
                 }
 
-                let resize_id = ui.id().with("__panel_resize").with(i);
+                let resize_id = ui.id().with("__panel_resize").with(column);
+
+                let line_x = if i < frozen_columns { x } else { x - scroll_x };
+                let in_view = i < frozen_columns || scrolled_range.contains(&line_x);
+                if !in_view && !ui.memory().is_being_dragged(resize_id) {
+                    available_width -= *width + spacing_x;
+                    continue;
+                }
 
-                let mut p0 = egui::pos2(x, table_top);
-                let mut p1 = egui::pos2(x, bottom);
+                let mut p0 = egui::pos2(line_x, table_top);
+                let mut p1 = egui::pos2(line_x, bottom);
                 let line_rect = egui::Rect::from_min_max(p0, p1)
                     .expand(ui.style().interaction.resize_grab_radius_side);
                 let mouse_over_resize_line = ui.rect_contains_pointer(line_rect);
@@ -330,10 +929,10 @@ impl<'a> Table<'a> {
                 let is_resizing = ui.memory().is_being_dragged(resize_id);
                 if is_resizing {
                     if let Some(pointer) = ui.ctx().pointer_latest_pos() {
-                        let new_width = *width + pointer.x - x;
-                        let (min, max) = sizing.sizes[i].range();
+                        let new_width = *width + pointer.x - line_x;
+                        let (min, max) = sizing.sizes[column].range();
                         let new_width = new_width.clamp(min, max);
-                        let x = x - *width + new_width;
+                        let x = line_x - *width + new_width;
                         p0.x = x;
                         p1.x = x;
 
@@ -361,8 +960,10 @@ impl<'a> Table<'a> {
 
                 available_width -= *width + spacing_x;
             }
+        }
 
-            ui.data().insert_persisted(resize_id, new_widths);
+        if persist {
+            state.store(ui.ctx(), id);
         }
     }
 }
@@ -371,8 +972,16 @@ impl<'a> Table<'a> {
 /// Is created by calling `body` on a [`Table`] (after adding a header row) or [`TableBuilder`] (without a header row).
 pub struct TableBody<'a> {
     layout: StripLayout<'a>,
+    id: Id,
     widths: Vec<f32>,
+    positions: CellPositions,
     striped: bool,
+    selection_mode: SelectionMode,
+    selection: &'a mut RowSelection,
+    has_focus: bool,
+    scroll_to_row: Option<(usize, Option<Align>)>,
+    /// How many of the rows that were added are completely in the view.
+    rows_in_view: usize,
     row_nr: usize,
     start_y: f32,
     end_y: f32,
@@ -383,6 +992,103 @@ impl<'a> TableBody<'a> {
         self.start_y - self.layout.rect.top()
     }
 
+    /// The rectangle of a row this far from the top of the body.
+    fn row_rect(&self, y: f32, height: f32) -> Rect {
+        let width = self
+            .positions
+            .cells
+            .iter()
+            .flatten()
+            .map(|(x, width)| x + width)
+            .fold(0.0, f32::max);
+        Rect::from_min_size(
+            egui::pos2(self.layout.rect.left(), self.layout.rect.top() + y),
+            egui::vec2(width, height),
+        )
+    }
+
+    /// Scroll to the row if it is the one to scroll to.
+    fn scroll_to(&mut self, row_index: usize, rect: Rect) {
+        if let Some((row, align)) = self.scroll_to_row {
+            if row == row_index {
+                // Only vertically:
+                let x_range = self.layout.ui.clip_rect().x_range();
+                let rect = Rect::from_x_y_ranges(x_range, rect.y_range());
+                self.layout.ui.scroll_to_rect(rect, align);
+                self.scroll_to_row = None;
+            }
+        }
+    }
+
+    /// Add a row where the cursor of the layout is, with the highlight and interaction of the selection.
+    fn add_row(&mut self, row_index: usize, height: f32, add_row: impl FnOnce(TableRow<'a, '_>)) {
+        let rect = self.row_rect(self.layout.cursor().y - self.layout.rect.top(), height);
+        let selectable = self.selection_mode != SelectionMode::None;
+        let selected = selectable && self.selection.rows.contains(&row_index);
+        let hovered = selectable && self.layout.ui.rect_contains_pointer(rect);
+
+        // Make sure we don't have a gap in the highlight, like with the stripes:
+        let highlight_rect = rect.expand2(egui::vec2(
+            0.5 * self.layout.ui.spacing().item_spacing.x,
+            0.0,
+        ));
+        let visuals = self.layout.ui.visuals();
+        let highlight = if selected {
+            Some(visuals.selection.bg_fill)
+        } else if hovered {
+            Some(visuals.widgets.hovered.bg_fill)
+        } else {
+            None
+        };
+        if let Some(fill) = highlight {
+            self.layout
+                .ui
+                .painter()
+                .rect_filled(highlight_rect, 0.0, fill);
+        }
+
+        add_row(TableRow {
+            layout: &mut self.layout,
+            positions: &self.positions,
+            column: 0,
+            striped: self.striped && row_index % 2 == 0 && highlight.is_none(),
+            selected,
+            height,
+            header: None,
+        });
+        self.row_nr = row_index + 1;
+
+        self.scroll_to(row_index, rect);
+        let clip_rect = self.layout.ui.clip_rect();
+        if clip_rect.top() <= rect.top() && rect.bottom() <= clip_rect.bottom() {
+            self.rows_in_view += 1;
+        }
+
+        if selectable {
+            // After the cells, so that the widgets in them get the clicks first:
+            let ui = &mut *self.layout.ui;
+            let response = ui.interact(rect, self.id.with(row_index), Sense::click());
+            if response.clicked() {
+                let modifiers = ui.input().modifiers;
+                self.selection
+                    .click(row_index, self.selection_mode, modifiers);
+                ui.memory().request_focus(self.id);
+                ui.ctx().request_repaint(); // To show the new selection.
+            }
+            if self.has_focus && self.selection.active == Some(row_index) {
+                ui.painter()
+                    .rect_stroke(highlight_rect, 0.0, ui.visuals().selection.stroke);
+            }
+        }
+    }
+
+    /// The rows that are selected, see [`TableBuilder::selection_mode`].
+    ///
+    /// This includes the changes made with the keyboard this frame, but not yet the clicks on the rows.
+    pub fn selection(&self) -> &RowSelection {
+        self.selection
+    }
+
     /// Return a vector containing all column widths for this table body.
     ///
     /// This is primarily meant for use with [`TableBody::heterogeneous_rows`] in cases where row
@@ -396,15 +1102,7 @@ impl<'a> TableBody<'a> {
     ///
     /// If you have many thousands of row it can be more performant to instead use [`Self::rows`] or [`Self::heterogeneous_rows`].
     pub fn row(&mut self, height: f32, row: impl FnOnce(TableRow<'a, '_>)) {
-        row(TableRow {
-            layout: &mut self.layout,
-            widths: &self.widths,
-            width_index: 0,
-            striped: self.striped && self.row_nr % 2 == 0,
-            height,
-        });
-
-        self.row_nr += 1;
+        self.add_row(self.row_nr, height, row);
     }
 
     /// Add many rows with same height.
@@ -445,6 +1143,15 @@ impl<'a> TableBody<'a> {
         let max_height = self.end_y - self.start_y;
         let mut min_row = 0;
 
+        if let Some((row, _)) = self.scroll_to_row {
+            // Also when the row isn't laid out:
+            let rect = self.row_rect(
+                row as f32 * row_height_with_spacing,
+                row_height_sans_spacing,
+            );
+            self.scroll_to(row, rect);
+        }
+
         if scroll_offset_y > 0.0 {
             min_row = (scroll_offset_y / row_height_with_spacing).floor() as usize;
             self.add_buffer(min_row as f32 * row_height_with_spacing);
@@ -455,22 +1162,14 @@ impl<'a> TableBody<'a> {
         let max_row = max_row.min(total_rows);
 
         for idx in min_row..max_row {
-            row(
-                idx,
-                TableRow {
-                    layout: &mut self.layout,
-                    widths: &self.widths,
-                    width_index: 0,
-                    striped: self.striped && idx % 2 == 0,
-                    height: row_height_sans_spacing,
-                },
-            );
+            self.add_row(idx, row_height_sans_spacing, |tr| row(idx, tr));
         }
 
         if total_rows - max_row > 0 {
             let skip_height = (total_rows - max_row) as f32 * row_height_with_spacing;
             self.add_buffer(skip_height - spacing.y);
         }
+        self.row_nr = total_rows;
     }
 
     /// Add rows with varying heights.
@@ -520,28 +1219,17 @@ impl<'a> TableBody<'a> {
             if cursor_y >= scroll_offset_y {
                 // This row is visible:
                 self.add_buffer(old_cursor_y as f32);
-                let tr = TableRow {
-                    layout: &mut self.layout,
-                    widths: &self.widths,
-                    width_index: 0,
-                    striped: self.striped && row_index % 2 == 0,
-                    height: row_height,
-                };
-                populate_row(row_index, tr);
+                self.add_row(row_index, row_height, |tr| populate_row(row_index, tr));
                 break;
             }
+            let rect = self.row_rect(old_cursor_y as f32, row_height);
+            self.scroll_to(row_index, rect);
+            self.row_nr = row_index + 1;
         }
 
         // populate visible rows:
         for (row_index, row_height) in &mut enumerated_heights {
-            let tr = TableRow {
-                layout: &mut self.layout,
-                widths: &self.widths,
-                width_index: 0,
-                striped: self.striped && row_index % 2 == 0,
-                height: row_height,
-            };
-            populate_row(row_index, tr);
+            self.add_row(row_index, row_height, |tr| populate_row(row_index, tr));
             cursor_y += (row_height + spacing.y) as f64;
 
             if cursor_y > scroll_offset_y + max_height as f64 {
@@ -551,13 +1239,18 @@ impl<'a> TableBody<'a> {
 
         // calculate height below the visible table range:
         let mut height_below_visible: f64 = 0.0;
-        for (_, height) in enumerated_heights {
-            height_below_visible += height as f64;
+        for (row_index, height) in enumerated_heights {
+            if self.scroll_to_row.is_some() {
+                let rect = self.row_rect((cursor_y + height_below_visible) as f32, height);
+                self.scroll_to(row_index, rect);
+            }
+            height_below_visible += (height + spacing.y) as f64;
+            self.row_nr = row_index + 1;
         }
         if height_below_visible > 0.0 {
             // we need to add a buffer to allow the table to
             // accurately calculate the scrollbar position
-            self.add_buffer(height_below_visible as f32);
+            self.add_buffer(height_below_visible as f32 - spacing.y);
         }
     }
 
@@ -571,6 +1264,13 @@ impl<'a> TableBody<'a> {
 impl<'a> Drop for TableBody<'a> {
     fn drop(&mut self) {
         self.layout.allocate_rect();
+        if self.selection_mode != SelectionMode::None {
+            // For the keyboard navigation the next frame:
+            self.layout
+                .ui
+                .data()
+                .insert_temp(self.id, (self.row_nr, self.rows_in_view));
+        }
     }
 }
 
@@ -578,34 +1278,136 @@ impl<'a> Drop for TableBody<'a> {
 /// Is created by [`TableRow`] for each created [`TableBody::row`] or each visible row in rows created by calling [`TableBody::rows`].
 pub struct TableRow<'a, 'b> {
     layout: &'b mut StripLayout<'a>,
-    widths: &'b [f32],
-    width_index: usize,
+    positions: &'b CellPositions,
+    column: usize,
     striped: bool,
+    selected: bool,
     height: f32,
+    header: Option<&'b mut HeaderCells>,
 }
 
 impl<'a, 'b> TableRow<'a, 'b> {
+    /// Is this row selected? See [`TableBuilder::selection_mode`].
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+
     /// Add the contents of a column.
+    ///
+    /// The columns are added in the order they were allocated with [`TableBuilder::column`],
+    /// even if the user has reordered them. The contents of hidden columns are not added.
     pub fn col(&mut self, add_contents: impl FnOnce(&mut Ui)) -> Response {
-        let width = if let Some(width) = self.widths.get(self.width_index) {
-            self.width_index += 1;
-            *width
+        let column = self.column;
+        let position = if let Some(position) = self.positions.cells.get(column) {
+            self.column += 1;
+            *position
         } else {
             crate::log_or_panic!(
                 "Added more `Table` columns than were pre-allocated ({} pre-allocated)",
-                self.widths.len()
+                self.positions.cells.len()
             );
-            8.0 // anything will look wrong, so pick something that is obviously wrong
+            // anything will look wrong, so pick something that is obviously wrong
+            Some((self.positions.cells.len() as f32 * 8.0, 8.0))
+        };
+        let (x, width) = match position {
+            Some(position) => position,
+            None => return self.layout.skip_cell(),
         };
+        self.layout.set_cell_offset(x);
+
+        // The columns that scroll horizontally go under the frozen ones:
+        let clip_rect = self.layout.ui.clip_rect();
+        let mut cell_clip_rect = clip_rect;
+        if let Some(scroll_clip) = self.positions.scroll_clip {
+            if !self
+                .positions
+                .frozen
+                .get(column)
+                .copied()
+                .unwrap_or_default()
+            {
+                let scroll_clip = self.layout.rect.left() + scroll_clip;
+                cell_clip_rect.min.x = cell_clip_rect.min.x.max(scroll_clip);
+                self.layout.ui.set_clip_rect(cell_clip_rect);
+            }
+        }
 
-        let width = CellSize::Absolute(width);
+        let cell_width = CellSize::Absolute(width);
         let height = CellSize::Absolute(self.height);
 
-        if self.striped {
-            self.layout.add_striped(width, height, add_contents)
+        let response = if self.striped {
+            self.layout.add_striped(cell_width, height, add_contents)
         } else {
-            self.layout.add(width, height, add_contents)
+            self.layout.add(cell_width, height, add_contents)
+        };
+        self.layout.ui.set_clip_rect(clip_rect);
+
+        if let Some(rect) = self
+            .header
+            .as_mut()
+            .and_then(|header| header.rects.get_mut(column))
+        {
+            // Only the part in view, for clicking and dragging it:
+            let left = self.layout.rect.left() + x;
+            let cell_rect = Rect::from_x_y_ranges(left..=left + width, response.rect.y_range())
+                .intersect(cell_clip_rect);
+            *rect = Some(cell_rect).filter(|rect| rect.is_positive());
+        }
+        response
+    }
+
+    /// Add a column with just a label.
+    ///
+    /// In the header, the text also names the column in the menu of [`TableBuilder::hideable`].
+    pub fn label_col(&mut self, text: impl Into<WidgetText>) -> Response {
+        let text = text.into();
+        if let Some(header) = &mut self.header {
+            if let Some(name) = header.names.get_mut(self.column) {
+                *name = Some(text.text().to_owned());
+            }
         }
+        self.col(|ui| {
+            ui.label(text);
+        })
+    }
+
+    /// Add a header cell with a label that sorts the table by this column when clicked,
+    /// and shows in which order it is sorted. Clicking it again reverses the order.
+    ///
+    /// Get the column to sort by with [`Table::sort`].
+    /// In other rows than the header, this is just a [`Self::label_col`].
+    pub fn sortable_col(&mut self, text: impl Into<WidgetText>) -> Response {
+        let column = self.column;
+        let sorted = self
+            .header
+            .as_ref()
+            .and_then(|header| header.sort)
+            .filter(|(sorted, _)| *sorted == column)
+            .map(|(_, order)| order);
+        let text = text.into();
+        let sortable = match &mut self.header {
+            Some(header) if column < header.sortable.len() => {
+                header.names[column] = Some(text.text().to_owned());
+                // The click is handled by the table, for the whole header at once.
+                header.sortable[column] = true;
+                true
+            }
+            _ => false,
+        };
+
+        self.col(|ui| {
+            ui.horizontal(|ui| {
+                let label = ui.label(text);
+                let response = match sorted {
+                    Some(SortOrder::Ascending) => ui.label("⏶"),
+                    Some(SortOrder::Descending) => ui.label("⏷"),
+                    None => label,
+                };
+                if sortable {
+                    response.on_hover_text("Click to sort");
+                }
+            });
+        })
     }
 }
 
@@ -614,3 +1416,250 @@ impl<'a, 'b> Drop for TableRow<'a, 'b> {
         self.layout.end_line();
     }
 }
+
+#[test]
+fn test_table_state_columns() {
+    let mut state = TableState::new(4);
+    assert!(state.is_valid(4));
+
+    state.move_column(3, Some(1));
+    assert_eq!(state.order, vec![0, 3, 1, 2]);
+    state.move_column(0, Some(2));
+    assert_eq!(state.order, vec![3, 1, 0, 2]);
+    state.move_column(1, None);
+    assert_eq!(state.order, vec![3, 0, 2, 1]);
+
+    state.hidden[0] = true;
+    state.hidden[1] = true;
+    assert_eq!(state.visible_columns().collect::<Vec<_>>(), vec![3, 2]);
+    assert!(state.is_valid(4));
+
+    // A state stored for another number of columns is thrown away.
+    assert!(!state.is_valid(3));
+    assert!(!state.is_valid(5));
+    assert!(!TableState::new(3).is_valid(4));
+
+    state.sort = Some((3, SortOrder::Ascending));
+    assert!(state.is_valid(4));
+    state.sort = Some((4, SortOrder::Ascending));
+    assert!(!state.is_valid(4));
+}
+
+#[test]
+fn test_row_selection_click() {
+    use egui::Modifiers;
+
+    let shift_command = Modifiers {
+        shift: true,
+        ..Modifiers::COMMAND
+    };
+
+    let mut selection = RowSelection::default();
+    selection.click(2, SelectionMode::Multiple, Modifiers::NONE);
+    assert_eq!(selection.rows, BTreeSet::from([2]));
+
+    // Ctrl toggles single rows and moves the anchor:
+    selection.click(5, SelectionMode::Multiple, Modifiers::COMMAND);
+    assert_eq!(selection.rows, BTreeSet::from([2, 5]));
+    selection.click(2, SelectionMode::Multiple, Modifiers::COMMAND);
+    assert_eq!(selection.rows, BTreeSet::from([5]));
+    assert_eq!(selection.anchor, Some(2));
+
+    // Shift selects the range from the anchor, which stays:
+    selection.click(4, SelectionMode::Multiple, Modifiers::SHIFT);
+    assert_eq!(selection.rows, BTreeSet::from([2, 3, 4]));
+    selection.click(0, SelectionMode::Multiple, Modifiers::SHIFT);
+    assert_eq!(selection.rows, BTreeSet::from([0, 1, 2]));
+    assert_eq!((selection.anchor, selection.active), (Some(2), Some(0)));
+
+    // With Ctrl too, the range is added to the selection:
+    selection.click(7, SelectionMode::Multiple, Modifiers::COMMAND);
+    selection.click(9, SelectionMode::Multiple, shift_command);
+    assert_eq!(selection.rows, BTreeSet::from([0, 1, 2, 7, 8, 9]));
+
+    // Only one row at a time, and no ranges:
+    let mut selection = RowSelection::default();
+    selection.click(1, SelectionMode::Single, Modifiers::NONE);
+    selection.click(3, SelectionMode::Single, Modifiers::COMMAND);
+    assert_eq!(selection.rows, BTreeSet::from([3]));
+    selection.click(5, SelectionMode::Single, Modifiers::SHIFT);
+    assert_eq!(selection.rows, BTreeSet::from([5]));
+    selection.click(5, SelectionMode::Single, Modifiers::COMMAND);
+    assert!(selection.rows.is_empty());
+}
+
+#[test]
+fn test_keyboard_navigation() {
+    use egui::{Key, Modifiers};
+
+    fn press(keys: &[(Key, Modifiers)]) -> egui::InputState {
+        let mut input = egui::InputState::default();
+        for &(key, modifiers) in keys {
+            input.events.push(egui::Event::Key {
+                key,
+                pressed: true,
+                modifiers,
+            });
+            input.events.push(egui::Event::Key {
+                key,
+                pressed: false,
+                modifiers,
+            });
+        }
+        input
+    }
+    let none = Modifiers::NONE;
+    let mode = SelectionMode::Multiple;
+
+    // Without an active row, the arrows start at the top:
+    let mut selection = RowSelection::default();
+    let input = press(&[(Key::ArrowDown, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(0));
+    let input = press(&[(Key::ArrowUp, none), (Key::PageUp, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(0));
+
+    let input = press(&[(Key::End, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(9));
+    let input = press(&[(Key::ArrowDown, none), (Key::PageDown, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(9));
+    let input = press(&[(Key::PageUp, none), (Key::ArrowUp, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(5));
+    let input = press(&[(Key::Home, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(0));
+    assert_eq!(selection.rows, BTreeSet::from([0]));
+
+    // Shift extends the selection from the anchor, Ctrl doesn't toggle:
+    let input = press(&[
+        (Key::ArrowDown, Modifiers::SHIFT),
+        (Key::ArrowDown, Modifiers::SHIFT),
+    ]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(2));
+    assert_eq!(selection.rows, BTreeSet::from([0, 1, 2]));
+    let input = press(&[(Key::ArrowDown, Modifiers::COMMAND)]);
+    selection.keyboard_navigation(&input, mode, 10, 3);
+    assert_eq!(selection.rows, BTreeSet::from([3]));
+
+    // The table got shorter since the row was active:
+    selection.active = Some(20);
+    let input = press(&[(Key::ArrowDown, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(9));
+    selection.active = Some(20);
+    let input = press(&[(Key::ArrowUp, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), Some(8));
+
+    // Nothing to move to, or no keys for it:
+    let before = selection.clone();
+    let input = press(&[(Key::End, none), (Key::ArrowDown, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 0, 3), None);
+    let input = press(&[(Key::A, none)]);
+    assert_eq!(selection.keyboard_navigation(&input, mode, 10, 3), None);
+    assert_eq!(selection, before);
+}
+
+#[test]
+fn test_scroll_to_row_in_empty_table() {
+    let ctx = egui::Context::default();
+    for _ in 0..2 {
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                for heterogeneous in [false, true] {
+                    TableBuilder::new(ui)
+                        .id_source(heterogeneous)
+                        .column(Size::remainder())
+                        .selection_mode(SelectionMode::Multiple)
+                        .scroll_to_row(5, None)
+                        .body(|body| {
+                            if heterogeneous {
+                                body.heterogeneous_rows(std::iter::empty(), |_, _| {
+                                    panic!("There are no rows");
+                                });
+                            } else {
+                                body.rows(20.0, 0, |_, _| panic!("There are no rows"));
+                            }
+                        });
+                }
+            });
+        });
+    }
+}
+
+#[test]
+fn test_only_used_table_state_is_stored() {
+    let ctx = egui::Context::default();
+    let mut ids = vec![];
+    for _ in 0..2 {
+        let _ = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ids = vec![ui.id(), ui.id().with("sortable")];
+                // Two plain tables with the default id don't share their state:
+                for num_columns in [2, 3] {
+                    TableBuilder::new(ui)
+                        .columns(Size::remainder(), num_columns)
+                        .body(|mut body| {
+                            body.row(20.0, |mut row| {
+                                row.col(|_| {});
+                            });
+                        });
+                }
+                TableBuilder::new(ui)
+                    .id_source("sortable")
+                    .columns(Size::remainder(), 2)
+                    .header(20.0, |mut header| {
+                        header.sortable_col("A");
+                        header.col(|_| {});
+                    })
+                    .body(|_| {});
+            });
+        });
+    }
+    assert!(TableState::load(&ctx, ids[0]).is_none());
+    assert!(TableState::load(&ctx, ids[1]).is_some());
+}
+
+#[test]
+fn test_cell_positions_scrolled() {
+    let mut state = TableState::new(4);
+    state.widths = vec![10.0, 20.0, 30.0, 40.0];
+
+    let positions = state.cell_positions(2.0, 2);
+    let lefts = |positions: &CellPositions| -> Vec<Option<f32>> {
+        positions
+            .cells
+            .iter()
+            .map(|cell| cell.map(|(x, _)| x))
+            .collect()
+    };
+    assert_eq!(
+        lefts(&positions),
+        vec![Some(0.0), Some(12.0), Some(34.0), Some(66.0)]
+    );
+    assert_eq!(positions.scroll_clip, Some(33.0));
+
+    let scrolled = positions.clone().scrolled(0.0, 0.0);
+    assert_eq!(lefts(&scrolled), lefts(&positions));
+
+    // The frozen columns only move with the row, the others also with the scrolling:
+    let scrolled = positions.clone().scrolled(15.0, 100.0);
+    assert_eq!(
+        lefts(&scrolled),
+        vec![Some(100.0), Some(112.0), Some(119.0), Some(151.0)]
+    );
+    assert_eq!(scrolled.scroll_clip, Some(133.0));
+    let scrolled = positions.scrolled(60.0, 100.0);
+    assert_eq!(
+        lefts(&scrolled),
+        vec![Some(100.0), Some(112.0), Some(74.0), Some(106.0)]
+    );
+    assert_eq!(scrolled.scroll_clip, Some(133.0));
+
+    // The frozen columns are the first ones shown:
+    state.move_column(2, Some(0));
+    state.hidden[1] = true;
+    let positions = state.cell_positions(2.0, 1).scrolled(50.0, -5.0);
+    assert_eq!(
+        lefts(&positions),
+        vec![Some(-23.0), None, Some(-5.0), Some(-11.0)]
+    );
+    assert_eq!(positions.frozen, vec![false, false, true, false]);
+    assert_eq!(positions.scroll_clip, Some(26.0));
+}
@@ -1,6 +1,9 @@
 #![allow(unsafe_code)]
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use egui::{
     emath::Rect,
@@ -49,6 +52,7 @@ pub struct Painter {
     program: glow::Program,
     u_screen_size: glow::UniformLocation,
     u_sampler: glow::UniformLocation,
+    u_distance_field: glow::UniformLocation,
     is_webgl_1: bool,
     is_embedded: bool,
     vao: crate::vao::VertexArrayObject,
@@ -59,6 +63,9 @@ pub struct Painter {
 
     textures: HashMap<egui::TextureId, glow::Texture>,
 
+    /// Font textures that store signed distance fields, see [`egui::epaint::GlyphAtlasMode`].
+    distance_field_textures: HashSet<egui::TextureId>,
+
     next_native_tex_id: u64,
 
     /// Stores outdated OpenGL textures that are yet to be deleted
@@ -157,6 +164,9 @@ impl Painter {
             gl.delete_shader(frag);
             let u_screen_size = gl.get_uniform_location(program, "u_screen_size").unwrap();
             let u_sampler = gl.get_uniform_location(program, "u_sampler").unwrap();
+            let u_distance_field = gl
+                .get_uniform_location(program, "u_distance_field")
+                .unwrap();
 
             let vbo = gl.create_buffer()?;
 
@@ -203,6 +213,7 @@ impl Painter {
                 program,
                 u_screen_size,
                 u_sampler,
+                u_distance_field,
                 is_webgl_1,
                 is_embedded: matches!(shader_version, ShaderVersion::Es100 | ShaderVersion::Es300),
                 vao,
@@ -211,6 +222,7 @@ impl Painter {
                 vbo,
                 element_array_buffer,
                 textures: Default::default(),
+                distance_field_textures: Default::default(),
                 next_native_tex_id: 1 << 32,
                 textures_to_destroy: Vec::new(),
                 destroyed: false,
@@ -432,6 +444,10 @@ impl Painter {
                 );
 
                 self.gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+
+                let distance_field = self.distance_field_textures.contains(&mesh.texture_id);
+                self.gl
+                    .uniform_1_i32(Some(&self.u_distance_field), distance_field as i32);
             }
 
             unsafe {
@@ -481,6 +497,13 @@ impl Painter {
                     "Mismatch between texture size and texel count"
                 );
 
+                if image.distance_field {
+                    self.distance_field_textures.insert(tex_id);
+                } else {
+                    self.distance_field_textures.remove(&tex_id);
+                }
+
+                // Distance fields ignore the gamma.
                 let gamma = if self.is_embedded && self.post_process.is_none() {
                     1.0 / 2.2
                 } else {
@@ -591,6 +614,7 @@ impl Painter {
         if let Some(old_tex) = self.textures.remove(&tex_id) {
             unsafe { self.gl.delete_texture(old_tex) };
         }
+        self.distance_field_textures.remove(&tex_id);
     }
 
     /// Get the [`glow::Texture`] bound to a [`egui::TextureId`].
@@ -1,8 +1,18 @@
+#if defined(GL_ES) && __VERSION__ < 300
+    #ifdef GL_OES_standard_derivatives
+        #extension GL_OES_standard_derivatives : enable
+        #define HAS_DERIVATIVES
+    #endif
+#else
+    #define HAS_DERIVATIVES
+#endif
+
 #ifdef GL_ES
     precision mediump float;
 #endif
 
 uniform sampler2D u_sampler;
+uniform int u_distance_field; // 1 if the texture is a signed distance field font atlas
 
 #ifdef NEW_SHADER_INTERFACE
     in vec4 v_rgba;
@@ -16,8 +26,24 @@ uniform sampler2D u_sampler;
     varying vec2 v_tc;
 #endif
 
+// The coverage of a glyph from its signed distance field, where 0.5 is the edge:
+float distance_field_coverage(float distance) {
+    #ifdef HAS_DERIVATIVES
+        float width = max(fwidth(distance), 0.0001);
+    #else
+        float width = 0.1; // no way to know how much the glyph is scaled
+    #endif
+    return clamp((distance - 0.5) / width + 0.5, 0.0, 1.0);
+}
+
 #ifdef SRGB_SUPPORTED
     void main() {
+        if (u_distance_field != 0) {
+            // The distance is in the (linear) alpha channel:
+            gl_FragColor = v_rgba * distance_field_coverage(texture2D(u_sampler, v_tc).a);
+            return;
+        }
+
         // The texture sampler is sRGB aware, and OpenGL already expects linear rgba output
         // so no need for any sRGB conversions here:
         gl_FragColor = v_rgba * texture2D(u_sampler, v_tc);
@@ -50,6 +76,9 @@ uniform sampler2D u_sampler;
     void main() {
         // We must decode the colors, since WebGL1 doesn't come with sRGBA textures:
         vec4 texture_rgba = linear_from_srgba(texture2D(u_sampler, v_tc) * 255.0);
+        if (u_distance_field != 0) {
+            texture_rgba = vec4(distance_field_coverage(texture2D(u_sampler, v_tc).a));
+        }
         /// Multiply vertex color with texture color (in linear space).
         gl_FragColor = v_rgba * texture_rgba;
 
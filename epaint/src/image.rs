@@ -171,6 +171,21 @@ pub struct FontImage {
     ///
     /// Often you want to use [`Self::srgba_pixels`] instead.
     pub pixels: Vec<f32>,
+
+    /// Premultiplied colors of the texels of color glyphs (e.g. emojis).
+    ///
+    /// Empty if there are no color glyphs. Otherwise one per texel,
+    /// with [`Color32::TRANSPARENT`] for the texels that only have a coverage value.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub colors: Vec<Color32>,
+
+    /// If `true`, [`Self::pixels`] are signed distances instead of coverage:
+    /// `0.5` on the edge of a glyph, more inside and less outside.
+    ///
+    /// The painter must turn them into coverage with a distance field shader,
+    /// see [`crate::GlyphAtlasMode::DistanceField`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub distance_field: bool,
 }
 
 impl FontImage {
@@ -178,6 +193,8 @@ impl FontImage {
         Self {
             size,
             pixels: vec![0.0; size[0] * size[1]],
+            colors: vec![],
+            distance_field: false,
         }
     }
 
@@ -196,8 +213,20 @@ impl FontImage {
     /// `gamma` should normally be set to 1.0.
     /// If you are having problems with text looking skinny and pixelated, try
     /// setting a lower gamma, e.g. `0.5`.
+    ///
+    /// The texels of color glyphs keep their own colors.
+    /// [`Self::distance_field`] images have their distances in alpha, unaffected by `gamma`.
     pub fn srgba_pixels(&'_ self, gamma: f32) -> impl ExactSizeIterator<Item = Color32> + '_ {
-        self.pixels.iter().map(move |coverage| {
+        self.pixels.iter().enumerate().map(move |(i, coverage)| {
+            if let Some(&color) = self.colors.get(i) {
+                if color != Color32::TRANSPARENT {
+                    return color;
+                }
+            }
+            if self.distance_field {
+                let a = fast_round(coverage * 255.0);
+                return Color32::from_rgba_premultiplied(a, a, a, a);
+            }
             // This is arbitrarily chosen to make text look as good as possible.
             // In particular, it looks good with gamma=1 and the default eframe backend,
             // which uses linear blending.
@@ -213,15 +242,34 @@ impl FontImage {
         assert!(y + h <= self.height());
 
         let mut pixels = Vec::with_capacity(w * h);
+        let mut colors = Vec::with_capacity(if self.colors.is_empty() { 0 } else { w * h });
         for y in y..y + h {
             let offset = y * self.width() + x;
             pixels.extend(&self.pixels[offset..(offset + w)]);
+            if !self.colors.is_empty() {
+                colors.extend(&self.colors[offset..(offset + w)]);
+            }
         }
         assert_eq!(pixels.len(), w * h);
         FontImage {
             size: [w, h],
             pixels,
+            colors,
+            distance_field: self.distance_field,
+        }
+    }
+
+    /// Set the color of a texel that is part of a color glyph.
+    ///
+    /// `color` is premultiplied. The coverage of the texel is set to its alpha.
+    pub fn set_color(&mut self, (x, y): (usize, usize), color: Color32) {
+        let [w, h] = self.size;
+        assert!(x < w && y < h);
+        if self.colors.is_empty() {
+            self.colors = vec![Color32::TRANSPARENT; self.pixels.len()];
         }
+        self.colors[y * w + x] = color;
+        self.pixels[y * w + x] = color.a() as f32 / 255.0;
     }
 }
 
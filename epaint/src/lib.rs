@@ -39,7 +39,7 @@ pub use {
     stroke::Stroke,
     tessellator::{tessellate_shapes, TessellationOptions, Tessellator},
     text::{FontId, FontPaintManager, FontType, Galley},
-    texture_atlas::TextureAtlas,
+    texture_atlas::{GlyphAtlasMode, TextureAtlas},
     texture_handle::TextureHandle,
     textures::TextureManager,
 };
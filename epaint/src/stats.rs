@@ -91,7 +91,9 @@ impl AllocInfo {
     }
 
     fn from_galley_row(row: &crate::text::Row) -> Self {
-        Self::from_mesh(&row.visuals.mesh) + Self::from_slice(&row.glyphs)
+        Self::from_mesh(&row.visuals.mesh)
+            + Self::from_slice(&row.glyphs)
+            + Self::from_slice(&row.shaped_glyphs)
     }
 
     pub fn from_mesh(mesh: &Mesh) -> Self {
@@ -171,6 +173,8 @@ pub struct PaintStats {
     pub clipped_primitives: AllocInfo,
     pub vertices: AllocInfo,
     pub indices: AllocInfo,
+
+    pub font_atlas: FontAtlasStats,
 }
 
 impl PaintStats {
@@ -235,6 +239,39 @@ impl PaintStats {
         }
         self
     }
+
+    pub fn with_font_atlas(mut self, atlas: &TextureAtlas) -> Self {
+        self.font_atlas = FontAtlasStats::from_atlas(atlas);
+        self
+    }
+}
+
+/// How full the font texture atlas is, and how much it has grown.
+#[derive(Clone, Copy, Default)]
+pub struct FontAtlasStats {
+    /// Size of the font texture, in texels.
+    pub size: [usize; 2],
+
+    /// Number of glyphs etc in the atlas.
+    /// A glyph with subpixel variants counts once.
+    pub num_allocations: usize,
+
+    /// How many times the atlas has grown since it was created.
+    pub num_resizes: usize,
+
+    /// See [`TextureAtlas::fill_ratio`].
+    pub fill_ratio: f32,
+}
+
+impl FontAtlasStats {
+    pub fn from_atlas(atlas: &TextureAtlas) -> Self {
+        Self {
+            size: atlas.size(),
+            num_allocations: atlas.num_allocations(),
+            num_resizes: atlas.num_resizes(),
+            fill_ratio: atlas.fill_ratio(),
+        }
+    }
 }
 
 fn megabytes(size: usize) -> String {
@@ -627,6 +627,26 @@ pub struct TessellationOptions {
     /// This makes the text sharper on most platforms.
     pub round_text_to_pixels: bool,
 
+    /// If `true`, glyphs are rasterized at several horizontal offsets within a pixel,
+    /// so that small text is spaced evenly instead of snapped to whole pixels.
+    ///
+    /// This uses more of the font texture.
+    /// The text is laid out before it is tessellated, so this is applied with
+    /// [`crate::text::FontPaintManager::set_subpixel_positioning`] (which `egui` does for you).
+    ///
+    /// Default: `false`.
+    pub subpixel_text_positioning: bool,
+
+    /// How glyphs are stored in the font texture.
+    ///
+    /// With [`GlyphAtlasMode::DistanceField`] each glyph is stored once and scaled to all sizes,
+    /// which keeps text sharp when zooming, but needs a backend that supports it
+    /// (`egui_glow` and `egui-wgpu` do).
+    /// This is applied with [`crate::text::FontPaintManager::set_glyph_atlas_mode`] (which `egui` does for you).
+    ///
+    /// Default: [`GlyphAtlasMode::Coverage`].
+    pub glyph_atlas_mode: GlyphAtlasMode,
+
     /// Output the clip rectangles to be painted.
     pub debug_paint_clip_rects: bool,
 
@@ -651,6 +671,8 @@ impl Default for TessellationOptions {
             coarse_tessellation_culling: true,
             prerasterized_discs: true,
             round_text_to_pixels: true,
+            subpixel_text_positioning: false,
+            glyph_atlas_mode: GlyphAtlasMode::Coverage,
             debug_paint_text_rects: false,
             debug_paint_clip_rects: false,
             debug_ignore_clip_rects: false,
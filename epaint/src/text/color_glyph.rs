@@ -0,0 +1,497 @@
+//! Rasterizing color glyphs, e.g. emojis.
+//!
+//! Supports layered outlines (the `COLR`/`CPAL` tables) and,
+//! with the `color_bitmap_glyphs` feature, embedded PNG bitmaps (the `CBDT` and `sbix` tables).
+
+use std::sync::Arc;
+
+use emath::{vec2, Vec2};
+
+use crate::{text::FontData, Color32};
+
+/// A rasterized color glyph.
+pub(crate) struct ColorGlyphImage {
+    /// Width and height in pixels.
+    pub size: [usize; 2],
+
+    /// Premultiplied colors, row by row, from top to bottom.
+    pub pixels: Vec<Color32>,
+
+    /// Where the top left corner of the image is relative to the glyph origin (on the baseline).
+    /// Unit: pixels, y down.
+    pub offset: Vec2,
+}
+
+/// The color glyph tables of a font.
+pub(crate) struct ColorFont {
+    font_data: Arc<FontData>,
+
+    /// The first palette of the `CPAL` table, used by the `COLR` layers.
+    palette: Vec<Color32>,
+
+    has_colr: bool,
+
+    #[cfg(feature = "color_bitmap_glyphs")]
+    has_bitmaps: bool,
+}
+
+impl ColorFont {
+    /// `None` if the font has no color glyphs.
+    pub fn new(font_data: Arc<FontData>) -> Option<Self> {
+        let raw_face = ttf_parser::RawFace::from_slice(&font_data.font, font_data.index).ok()?;
+        let table = |tag: &[u8; 4]| raw_face.table(ttf_parser::Tag::from_bytes(tag));
+
+        let palette = table(b"CPAL").and_then(cpal_palette).unwrap_or_default();
+        let has_colr = table(b"COLR").is_some() && !palette.is_empty();
+
+        #[cfg(feature = "color_bitmap_glyphs")]
+        let has_bitmaps = table(b"CBDT").is_some() || table(b"sbix").is_some();
+        #[cfg(not(feature = "color_bitmap_glyphs"))]
+        let has_bitmaps = false;
+
+        if !has_colr && !has_bitmaps {
+            return None;
+        }
+
+        Some(Self {
+            font_data,
+            palette,
+            has_colr,
+            #[cfg(feature = "color_bitmap_glyphs")]
+            has_bitmaps,
+        })
+    }
+
+    /// `None` if this is not a color glyph.
+    pub fn rasterize(
+        &self,
+        ab_glyph_font: &ab_glyph::FontArc,
+        glyph_id: ab_glyph::GlyphId,
+        scale_in_pixels: f32,
+    ) -> Option<ColorGlyphImage> {
+        if self.has_colr {
+            let raw_face =
+                ttf_parser::RawFace::from_slice(&self.font_data.font, self.font_data.index).ok()?;
+            let colr = raw_face.table(ttf_parser::Tag::from_bytes(b"COLR"))?;
+            if let Some(layers) = colr_layers(colr, glyph_id.0) {
+                return self.rasterize_layers(ab_glyph_font, &layers, scale_in_pixels);
+            }
+        }
+
+        #[cfg(feature = "color_bitmap_glyphs")]
+        if self.has_bitmaps {
+            return self.rasterize_bitmap(ab_glyph_font, glyph_id, scale_in_pixels);
+        }
+
+        None
+    }
+
+    /// Paint the outline of each layer in its palette color, back to front.
+    fn rasterize_layers(
+        &self,
+        ab_glyph_font: &ab_glyph::FontArc,
+        layers: &[(u16, u16)],
+        scale_in_pixels: f32,
+    ) -> Option<ColorGlyphImage> {
+        use ab_glyph::Font as _;
+
+        let outlines: Vec<(ab_glyph::OutlinedGlyph, Color32)> = layers
+            .iter()
+            .filter_map(|&(layer_glyph_id, palette_index)| {
+                let glyph = ab_glyph::GlyphId(layer_glyph_id)
+                    .with_scale_and_position(scale_in_pixels, ab_glyph::point(0.0, 0.0));
+                let outline = ab_glyph_font.outline_glyph(glyph)?;
+                // 0xFFFF means "the text color", which we don't know here, so we use white.
+                // The glyph isn't tinted when it is painted, see `UvRect::is_color`.
+                let color = self
+                    .palette
+                    .get(palette_index as usize)
+                    .copied()
+                    .unwrap_or(Color32::WHITE);
+                Some((outline, color))
+            })
+            .collect();
+
+        let bounds = outlines
+            .iter()
+            .map(|(outline, _)| outline.px_bounds())
+            .reduce(|a, b| ab_glyph::Rect {
+                min: ab_glyph::point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+                max: ab_glyph::point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+            })?;
+        let size = [bounds.width() as usize, bounds.height() as usize];
+        if size[0] == 0 || size[1] == 0 {
+            return None;
+        }
+
+        let mut pixels = vec![Color32::TRANSPARENT; size[0] * size[1]];
+        for (outline, color) in &outlines {
+            let layer_bounds = outline.px_bounds();
+            let dx = (layer_bounds.min.x - bounds.min.x) as usize;
+            let dy = (layer_bounds.min.y - bounds.min.y) as usize;
+            outline.draw(|x, y, coverage| {
+                let (x, y) = (dx + x as usize, dy + y as usize);
+                if coverage > 0.0 && x < size[0] && y < size[1] {
+                    let pixel = &mut pixels[y * size[0] + x];
+                    *pixel = blend_over(*pixel, color.linear_multiply(coverage));
+                }
+            });
+        }
+
+        Some(ColorGlyphImage {
+            size,
+            pixels,
+            offset: vec2(bounds.min.x, bounds.min.y),
+        })
+    }
+
+    #[cfg(feature = "color_bitmap_glyphs")]
+    fn rasterize_bitmap(
+        &self,
+        ab_glyph_font: &ab_glyph::FontArc,
+        glyph_id: ab_glyph::GlyphId,
+        scale_in_pixels: f32,
+    ) -> Option<ColorGlyphImage> {
+        use ab_glyph::{Font as _, ScaleFont as _};
+
+        let pixels_per_em = ab_glyph_font.units_per_em()?
+            * ab_glyph_font.as_scaled(scale_in_pixels).h_scale_factor();
+
+        let face = ttf_parser::Face::from_slice(&self.font_data.font, self.font_data.index).ok()?;
+        let raster = face.glyph_raster_image(
+            ttf_parser::GlyphId(glyph_id.0),
+            pixels_per_em.round() as u16,
+        )?;
+        if raster.format != ttf_parser::RasterImageFormat::PNG {
+            return None;
+        }
+        let (image_size, image) = decode_png(raster.data)?;
+
+        // The bitmaps come in a few sizes ("strikes"), so we need to scale to the font size:
+        let scale = pixels_per_em / raster.pixels_per_em as f32;
+        let size = [
+            ((image_size[0] as f32 * scale).round() as usize).max(1),
+            ((image_size[1] as f32 * scale).round() as usize).max(1),
+        ];
+
+        Some(ColorGlyphImage {
+            size,
+            pixels: resize(image_size, &image, size),
+            // `raster.y` is the offset of the bottom edge, y up:
+            offset: vec2(
+                raster.x as f32 * scale,
+                -(raster.y as f32 + raster.height as f32) * scale,
+            ),
+        })
+    }
+}
+
+/// Premultiplied alpha blending.
+fn blend_over(dst: Color32, src: Color32) -> Color32 {
+    let inv_alpha = 255 - src.a() as u16;
+    let blend = |s: u8, d: u8| (s as u16 + (d as u16 * inv_alpha + 127) / 255).min(255) as u8;
+    Color32::from_rgba_premultiplied(
+        blend(src.r(), dst.r()),
+        blend(src.g(), dst.g()),
+        blend(src.b(), dst.b()),
+        blend(src.a(), dst.a()),
+    )
+}
+
+/// The layers of a color glyph in a version 0 `COLR` table, as `(glyph id, palette index)`, bottom layer first.
+///
+/// See <https://docs.microsoft.com/en-us/typography/opentype/spec/colr>.
+fn colr_layers(colr: &[u8], glyph_id: u16) -> Option<Vec<(u16, u16)>> {
+    let u16_at = |offset: usize| {
+        Some(u16::from_be_bytes([
+            *colr.get(offset)?,
+            *colr.get(offset + 1)?,
+        ]))
+    };
+    let u32_at =
+        |offset: usize| Some(((u16_at(offset)? as u32) << 16) | u16_at(offset + 2)? as u32);
+
+    let num_base_glyphs = u16_at(2)? as usize;
+    let base_glyphs_offset = u32_at(4)? as usize;
+    let layers_offset = u32_at(8)? as usize;
+    let num_layers = u16_at(12)? as usize;
+
+    // BaseGlyphRecord: glyph id, first layer index, number of layers.
+    const BASE_GLYPH_SIZE: usize = 6;
+    let base_glyph = |index: usize| u16_at(base_glyphs_offset + index * BASE_GLYPH_SIZE);
+    let (mut low, mut high) = (0, num_base_glyphs);
+    while low < high {
+        let mid = (low + high) / 2;
+        let id = base_glyph(mid)?;
+        if id < glyph_id {
+            low = mid + 1;
+        } else if id > glyph_id {
+            high = mid;
+        } else {
+            let record = base_glyphs_offset + mid * BASE_GLYPH_SIZE;
+            let first_layer = u16_at(record + 2)? as usize;
+            let layer_count = u16_at(record + 4)? as usize;
+            if first_layer + layer_count > num_layers {
+                return None;
+            }
+            // LayerRecord: glyph id, palette index.
+            return (first_layer..first_layer + layer_count)
+                .map(|layer| {
+                    let record = layers_offset + layer * 4;
+                    Some((u16_at(record)?, u16_at(record + 2)?))
+                })
+                .collect();
+        }
+    }
+    None
+}
+
+/// The first palette of a `CPAL` table, premultiplied.
+///
+/// See <https://docs.microsoft.com/en-us/typography/opentype/spec/cpal>.
+fn cpal_palette(cpal: &[u8]) -> Option<Vec<Color32>> {
+    let u16_at = |offset: usize| {
+        Some(u16::from_be_bytes([
+            *cpal.get(offset)?,
+            *cpal.get(offset + 1)?,
+        ]))
+    };
+
+    let num_entries = u16_at(2)? as usize;
+    let colors_offset = ((u16_at(8)? as usize) << 16) | u16_at(10)? as usize;
+    let first_color = u16_at(12)? as usize;
+
+    // Each color record is BGRA:
+    let start = colors_offset + first_color * 4;
+    let records = cpal.get(start..start + num_entries * 4)?;
+    Some(
+        records
+            .chunks_exact(4)
+            .map(|bgra| Color32::from_rgba_unmultiplied(bgra[2], bgra[1], bgra[0], bgra[3]))
+            .collect(),
+    )
+}
+
+/// Decode a PNG into premultiplied colors.
+#[cfg(feature = "color_bitmap_glyphs")]
+fn decode_png(data: &[u8]) -> Option<([usize; 2], Vec<Color32>)> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    let size = [info.width as usize, info.height as usize];
+    let bytes = &buf[..info.buffer_size()];
+
+    let pixels: Vec<Color32> = match info.color_type {
+        png::ColorType::Rgba => bytes
+            .chunks_exact(4)
+            .map(|p| Color32::from_rgba_unmultiplied(p[0], p[1], p[2], p[3]))
+            .collect(),
+        png::ColorType::Rgb => bytes
+            .chunks_exact(3)
+            .map(|p| Color32::from_rgb(p[0], p[1], p[2]))
+            .collect(),
+        png::ColorType::GrayscaleAlpha => bytes
+            .chunks_exact(2)
+            .map(|p| Color32::from_rgba_unmultiplied(p[0], p[0], p[0], p[1]))
+            .collect(),
+        png::ColorType::Grayscale => bytes.iter().map(|&l| Color32::from_gray(l)).collect(),
+        png::ColorType::Indexed => return None, // expanded by `normalize_to_color8`
+    };
+
+    (pixels.len() == size[0] * size[1]).then(|| (size, pixels))
+}
+
+/// Resize premultiplied colors by averaging the source pixels that cover each destination pixel.
+#[cfg(feature = "color_bitmap_glyphs")]
+fn resize(src_size: [usize; 2], src: &[Color32], dst_size: [usize; 2]) -> Vec<Color32> {
+    if src_size == dst_size {
+        return src.to_vec();
+    }
+
+    let source_range = |dst: usize, src_len: usize, dst_len: usize| {
+        let start = dst * src_len / dst_len;
+        let end = ((dst + 1) * src_len + dst_len - 1) / dst_len;
+        start..end.max(start + 1).min(src_len)
+    };
+
+    let mut dst = Vec::with_capacity(dst_size[0] * dst_size[1]);
+    for y in 0..dst_size[1] {
+        let src_ys = source_range(y, src_size[1], dst_size[1]);
+        for x in 0..dst_size[0] {
+            let src_xs = source_range(x, src_size[0], dst_size[0]);
+            let mut sum = [0_u32; 4];
+            let mut count = 0;
+            for sy in src_ys.clone() {
+                for sx in src_xs.clone() {
+                    let color = src[sy * src_size[0] + sx];
+                    for (s, c) in sum.iter_mut().zip(color.to_array()) {
+                        *s += c as u32;
+                    }
+                    count += 1;
+                }
+            }
+            let avg = |i: usize| ((sum[i] + count / 2) / count) as u8;
+            dst.push(Color32::from_rgba_premultiplied(
+                avg(0),
+                avg(1),
+                avg(2),
+                avg(3),
+            ));
+        }
+    }
+    dst
+}
+
+#[test]
+fn test_colr_layers() {
+    // COLR version 0 with two base glyphs, the second one with two layers:
+    #[rustfmt::skip]
+    let colr: &[u8] = &[
+        0, 0, // version
+        0, 2, // number of base glyph records
+        0, 0, 0, 14, // offset to base glyph records
+        0, 0, 0, 26, // offset to layer records
+        0, 3, // number of layer records
+        // base glyph records: glyph id, first layer index, number of layers
+        0, 5, 0, 0, 0, 1,
+        0, 9, 0, 1, 0, 2,
+        // layer records: glyph id, palette index
+        0, 20, 0, 0,
+        0, 21, 0, 1,
+        0, 22, 0xFF, 0xFF,
+    ];
+    assert_eq!(colr_layers(colr, 5), Some(vec![(20, 0)]));
+    assert_eq!(colr_layers(colr, 9), Some(vec![(21, 1), (22, 0xFFFF)]));
+    assert_eq!(colr_layers(colr, 7), None);
+
+    #[rustfmt::skip]
+    let cpal: &[u8] = &[
+        0, 0, // version
+        0, 2, // number of palette entries
+        0, 1, // number of palettes
+        0, 2, // number of color records
+        0, 0, 0, 14, // offset to color records
+        0, 0, // first color record of the first palette
+        // color records, BGRA:
+        0, 0, 255, 255,
+        255, 0, 0, 0,
+    ];
+    assert_eq!(
+        cpal_palette(cpal),
+        Some(vec![Color32::RED, Color32::TRANSPARENT])
+    );
+}
+
+#[test]
+fn test_rasterize_colr_glyph() {
+    let (is_color, glyph_pixels) = rasterize_colr_a(0);
+    assert!(is_color);
+    assert!(!glyph_pixels.is_empty());
+    // Red, not the gray of coverage-only text:
+    assert!(glyph_pixels
+        .iter()
+        .all(|color| color.r() > 0 && color.g() == 0 && color.b() == 0));
+    assert!(glyph_pixels.contains(&Color32::RED));
+}
+
+#[test]
+fn test_colr_layers_in_text_color_are_white() {
+    // The text color isn't known when the glyph is rasterized, and color glyphs aren't tinted:
+    let (is_color, glyph_pixels) = rasterize_colr_a(0xFFFF);
+    assert!(is_color);
+    assert!(!glyph_pixels.is_empty());
+    assert!(glyph_pixels
+        .iter()
+        .all(|color| color.r() == color.g() && color.g() == color.b()));
+    assert!(glyph_pixels.contains(&Color32::WHITE));
+}
+
+/// Lay out 'A' in Hack, with a COLR table that draws it as its own outline in the color with the palette index,
+/// where the first palette color is red.
+///
+/// Returns if it is a color glyph, and its visible pixels in the font atlas.
+#[cfg(test)]
+fn rasterize_colr_a(palette_index: u16) -> (bool, Vec<Color32>) {
+    use crate::text::{FontDefinitions, FontId, FontPaintManager, FontType, LayoutJob};
+
+    let hack = include_bytes!("../../fonts/Hack-Regular.ttf");
+    let glyph_a = ttf_parser::Face::from_slice(hack, 0)
+        .unwrap()
+        .glyph_index('A')
+        .unwrap()
+        .0;
+    let [a_hi, a_lo] = glyph_a.to_be_bytes();
+    let [index_hi, index_lo] = palette_index.to_be_bytes();
+    #[rustfmt::skip]
+    let colr = vec![
+        0, 0, 0, 1, 0, 0, 0, 14, 0, 0, 0, 20, 0, 1,
+        a_hi, a_lo, 0, 0, 0, 1, // base glyph record
+        a_hi, a_lo, index_hi, index_lo, // layer record
+    ];
+    #[rustfmt::skip]
+    let cpal = vec![
+        0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 14, 0, 0,
+        0, 0, 255, 255, // red, BGRA
+    ];
+    let font = with_tables(hack, vec![(*b"COLR", colr), (*b"CPAL", cpal)]);
+
+    let mut definitions = FontDefinitions::default();
+    definitions
+        .font_data_map
+        .insert("Hack-COLR".to_owned(), FontData::from_owned(font));
+    definitions
+        .type_fonts
+        .insert(FontType::Proportional, vec!["Hack-COLR".to_owned()]);
+    let fonts = FontPaintManager::new(1.0, 1024, definitions);
+
+    let job = LayoutJob::simple_singleline("A".into(), FontId::proportional(20.0), Color32::WHITE);
+    let uv_rect = fonts.layout_job(job).rows[0].shaped_glyphs[0].uv_rect;
+
+    let image = match fonts.font_image_delta().unwrap().image {
+        crate::ImageData::Font(image) => image,
+        crate::ImageData::Color(_) => panic!("Expected a font image"),
+    };
+    let pixels: Vec<Color32> = image.srgba_pixels(1.0).collect();
+    let glyph_pixels = (uv_rect.min[1]..uv_rect.max[1])
+        .flat_map(|y| (uv_rect.min[0]..uv_rect.max[0]).map(move |x| (x as usize, y as usize)))
+        .map(|(x, y)| pixels[y * image.width() + x])
+        .filter(|color| color.a() > 0)
+        .collect();
+    (uv_rect.is_color, glyph_pixels)
+}
+
+/// Add tables to a TrueType font, keeping the table directory sorted by tag.
+#[cfg(test)]
+fn with_tables(font: &[u8], extra_tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    let read_u32 = |at: usize| u32::from_be_bytes(font[at..at + 4].try_into().unwrap()) as usize;
+    let num_tables = u16::from_be_bytes([font[4], font[5]]) as usize;
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = (0..num_tables)
+        .map(|i| {
+            let record = 12 + 16 * i;
+            let tag = font[record..record + 4].try_into().unwrap();
+            let (offset, length) = (read_u32(record + 8), read_u32(record + 12));
+            (tag, font[offset..offset + length].to_vec())
+        })
+        .collect();
+    tables.extend(extra_tables);
+    tables.sort_by_key(|(tag, _)| *tag);
+
+    let mut out = font[0..4].to_vec();
+    out.extend((tables.len() as u16).to_be_bytes());
+    out.extend([0; 6]); // search range, entry selector, range shift: unused by the parser
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        out.extend(tag);
+        out.extend([0; 4]); // checksum: unused by the parser
+        out.extend((offset as u32).to_be_bytes());
+        out.extend((data.len() as u32).to_be_bytes());
+        offset += (data.len() + 3) / 4 * 4;
+    }
+    for (_, data) in &tables {
+        out.extend(data);
+        out.resize((out.len() + 3) / 4 * 4, 0);
+    }
+    out
+}
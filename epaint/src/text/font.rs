@@ -1,9 +1,10 @@
 use crate::{
     mutex::{Mutex, RwLock},
-    TextureAtlas,
+    text::{color_glyph::ColorFont, FontData},
+    GlyphAtlasMode, TextureAtlas,
 };
-use ahash::AHashMap;
-use emath::{vec2, Vec2};
+use ahash::{AHashMap, AHashSet};
+use emath::{pos2, vec2, Pos2, Vec2};
 use std::collections::BTreeSet;
 use std::sync::Arc;
 
@@ -24,18 +25,64 @@ pub struct UvRect {
 
     /// Bottom right corner (exclusive).
     pub max: [u16; 2],
+
+    /// Does the glyph have its own colors (e.g. a color emoji)?
+    ///
+    /// If `false`, the glyph is a coverage mask that is tinted with [`crate::text::TextFormat::color`].
+    /// If `true`, it is painted with its own colors, and only the alpha of the text color is used.
+    /// This includes the layers of a `COLR` glyph that are meant to be in the text color:
+    /// they are always white.
+    pub is_color: bool,
+
+    /// How many copies of the glyph, each shifted a bit further to the right,
+    /// are next to each other in the texture, for subpixel positioning.
+    ///
+    /// `0` or `1` means there is just the one. See [`Self::subpixel_variant`].
+    pub subpixel_variants: u8,
 }
 
 impl UvRect {
     pub fn is_nothing(&self) -> bool {
         self.min == self.max
     }
+
+    /// Pick the copy of the glyph that is rasterized closest to the horizontal position `x` (in pixels).
+    ///
+    /// Returns the whole pixel to put that copy at, and where it is in the texture.
+    pub fn subpixel_variant(&self, x: f32) -> (f32, UvRect) {
+        if self.subpixel_variants <= 1 {
+            return (x.round(), *self);
+        }
+
+        let num_variants = self.subpixel_variants as f32;
+        let steps = (x * num_variants).round();
+        let whole_x = (steps / num_variants).floor();
+        let variant = (steps - whole_x * num_variants) as u16;
+
+        let texel_offset = variant * (self.max[0] - self.min[0] + SUBPIXEL_VARIANT_SPACING);
+        let mut uv_rect = *self;
+        uv_rect.min[0] += texel_offset;
+        uv_rect.max[0] += texel_offset;
+        (whole_x, uv_rect)
+    }
 }
 
+/// How many horizontally shifted copies of each glyph to rasterize when using subpixel positioning.
+const SUBPIXEL_VARIANTS: usize = 4;
+
+/// Empty texels between the subpixel copies of a glyph, so they don't bleed into each other.
+const SUBPIXEL_VARIANT_SPACING: u16 = 1;
+
+/// The size (in pixels) that distance field glyphs are made for.
+/// They are scaled from this to every font size.
+const DISTANCE_FIELD_SCALE_IN_PIXELS: f32 = 32.0;
+
+/// How far (in pixels at [`DISTANCE_FIELD_SCALE_IN_PIXELS`]) from the edge of a glyph
+/// its distance field goes, inside and outside.
+const DISTANCE_FIELD_SPREAD: f32 = 4.0;
+
 #[derive(Clone, Copy, Debug)]
 pub struct GlyphInfo {
-    pub(crate) id: ab_glyph::GlyphId,
-
     /// Unit: points.
     pub advance_width: f32,
 
@@ -46,13 +93,53 @@ pub struct GlyphInfo {
 impl Default for GlyphInfo {
     fn default() -> Self {
         Self {
-            id: ab_glyph::GlyphId(0),
             advance_width: 0.0,
             uv_rect: Default::default(),
         }
     }
 }
 
+/// What to fake when rasterizing a font, because there is no real bold or italic version of it.
+///
+/// See [`crate::text::FontDefinitions::font_variants`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FontSynthesis {
+    /// Thicken the glyphs horizontally.
+    pub bold: bool,
+
+    /// Slant the glyphs to the right.
+    pub oblique: bool,
+}
+
+impl FontSynthesis {
+    /// How far to slant the glyphs: `tan(14°)`, same as browsers.
+    const OBLIQUE_SKEW: f32 = 0.25;
+
+    /// How many pixels to thicken the glyphs by at this font size.
+    fn bold_pixels(self, scale_in_pixels: u32) -> usize {
+        if self.bold {
+            ((scale_in_pixels as f32 / 24.0).round() as usize).max(1)
+        } else {
+            0
+        }
+    }
+}
+
+/// One glyph output by [`FontImpl::shape`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ShapedGlyphInfo {
+    /// Byte offset into the shaped text of the first character of the cluster this glyph belongs to.
+    pub cluster: usize,
+
+    pub glyph_info: GlyphInfo,
+
+    /// How much to advance the pen after this glyph. Unit: points.
+    pub x_advance: f32,
+
+    /// Where to draw the glyph relative to the pen position. Unit: points, y down.
+    pub offset: Vec2,
+}
+
 // ----------------------------------------------------------------------------
 
 /// A specific font with a size.
@@ -60,24 +147,39 @@ impl Default for GlyphInfo {
 pub struct FontImpl {
     name: String,
     ab_glyph_font: ab_glyph::FontArc,
+    /// The raw font file, used for shaping.
+    font_data: Arc<FontData>,
+    /// `None` if the font has no color glyphs.
+    color_font: Option<ColorFont>,
     /// Maximum character height
     scale_in_pixels: u32,
     height_in_points: f32,
     // move each character by this much (hack)
     y_offset: f32,
     pixels_per_point: f32,
+    synthesis: FontSynthesis,
+    /// Rasterize each glyph at several subpixel offsets.
+    subpixel_positioning: bool,
+    /// Vertical scale to put the x-height on a whole pixel, if [`crate::text::FontTweak::hinting`].
+    hinting_y_scale: f32,
     glyph_info_cache: RwLock<AHashMap<char, GlyphInfo>>, // TODO(emilk): standard Mutex
+    /// Glyphs looked up by id, as output by the shaper.
+    glyph_id_info_cache: RwLock<AHashMap<u16, GlyphInfo>>,
     atlas: Arc<Mutex<TextureAtlas>>,
 }
 
 impl FontImpl {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         atlas: Arc<Mutex<TextureAtlas>>,
         pixels_per_point: f32,
         name: String,
         ab_glyph_font: ab_glyph::FontArc,
+        font_data: Arc<FontData>,
         scale_in_pixels: u32,
         y_offset_points: f32,
+        synthesis: FontSynthesis,
+        subpixel_positioning: bool,
     ) -> FontImpl {
         assert!(scale_in_pixels > 0);
         assert!(pixels_per_point > 0.0);
@@ -94,18 +196,36 @@ impl FontImpl {
         // Round to closest pixel:
         let y_offset = (y_offset_points * pixels_per_point).round() / pixels_per_point;
 
+        let hinting_y_scale = if font_data.tweak.hinting {
+            snapped_x_height_scale(&font_data, &ab_glyph_font, scale_in_pixels)
+        } else {
+            1.0
+        };
+
         Self {
             name,
             ab_glyph_font,
+            color_font: ColorFont::new(font_data.clone()),
+            font_data,
             scale_in_pixels,
             height_in_points,
             y_offset,
             pixels_per_point,
+            synthesis,
+            subpixel_positioning,
+            hinting_y_scale,
             glyph_info_cache: Default::default(),
+            glyph_id_info_cache: Default::default(),
             atlas,
         }
     }
 
+    /// The key of this font in [`crate::text::FontDefinitions::font_data_map`].
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     fn ignore_character(&self, chr: char) -> bool {
         if self.name == "emoji-icon-font" {
             // HACK: https://github.com/emilk/egui/issues/1284 https://github.com/jslegers/emoji-icon-font/issues/18
@@ -134,6 +254,17 @@ impl FontImpl {
             .filter(|&chr| !self.ignore_character(chr))
     }
 
+    /// Does this font have a glyph for the given character?
+    ///
+    /// Unlike [`Self::glyph_info`] this will not allocate anything in the font atlas.
+    pub(crate) fn has_glyph(&self, c: char) -> bool {
+        if self.glyph_info_cache.read().contains_key(&c) {
+            return true;
+        }
+        use ab_glyph::Font as _;
+        !self.ignore_character(c) && self.ab_glyph_font.glyph_id(c).0 != 0
+    }
+
     /// `\n` will result in `None`
     fn glyph_info(&self, c: char) -> Option<GlyphInfo> {
         {
@@ -171,20 +302,104 @@ impl FontImpl {
                 None // unsupported character
             }
         } else {
-            let glyph_info = allocate_glyph(
-                &mut self.atlas.lock(),
-                &self.ab_glyph_font,
-                glyph_id,
-                self.scale_in_pixels as f32,
-                self.y_offset,
-                self.pixels_per_point,
-            );
+            let glyph_info = self.allocate_glyph(glyph_id);
 
             self.glyph_info_cache.write().insert(c, glyph_info);
             Some(glyph_info)
         }
     }
 
+    /// Look up (and if needed, rasterize) a glyph by its id, as output by the shaper.
+    fn glyph_info_from_id(&self, glyph_id: ab_glyph::GlyphId) -> GlyphInfo {
+        if let Some(glyph_info) = self.glyph_id_info_cache.read().get(&glyph_id.0) {
+            return *glyph_info;
+        }
+
+        let glyph_info = if glyph_id.0 == 0 {
+            // The shaper could not find a glyph. Leave it blank rather than showing `.notdef`.
+            GlyphInfo::default()
+        } else {
+            self.allocate_glyph(glyph_id)
+        };
+
+        self.glyph_id_info_cache
+            .write()
+            .insert(glyph_id.0, glyph_info);
+        glyph_info
+    }
+
+    /// Shape a run of text (that contains no newlines) using only this font.
+    ///
+    /// This applies ligatures, contextual forms, kerning and mark positioning.
+    /// Each output glyph knows what cluster (byte offset into `text`) it came from,
+    /// which is what lets us map the glyphs back to characters for the cursor.
+    ///
+    /// The glyphs are returned in visual order, i.e. left-to-right on screen.
+    pub(crate) fn shape(&self, text: &str, right_to_left: bool) -> Vec<ShapedGlyphInfo> {
+        let face = match rustybuzz::Face::from_slice(&self.font_data.font, self.font_data.index) {
+            Some(face) => face,
+            None => return self.shape_without_shaper(text, right_to_left),
+        };
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        buffer.set_direction(if right_to_left {
+            rustybuzz::Direction::RightToLeft
+        } else {
+            rustybuzz::Direction::LeftToRight
+        });
+
+        let glyph_buffer = rustybuzz::shape(&face, &[], buffer);
+
+        // The shaper works in font units, and so must we convert it to points the same way `ab_glyph` does:
+        use ab_glyph::{Font as _, ScaleFont as _};
+        let scaled_font = self.ab_glyph_font.as_scaled(self.scale_in_pixels as f32);
+        let h_scale_in_pixels = scaled_font.h_scale_factor();
+        let h_scale = h_scale_in_pixels / self.pixels_per_point;
+        let v_scale = scaled_font.v_scale_factor() / self.pixels_per_point;
+        let bold_pixels = self.synthesis.bold_pixels(self.scale_in_pixels) as f32;
+
+        glyph_buffer
+            .glyph_infos()
+            .iter()
+            .zip(glyph_buffer.glyph_positions())
+            .map(|(info, pos)| ShapedGlyphInfo {
+                cluster: info.cluster as usize,
+                glyph_info: self.glyph_info_from_id(ab_glyph::GlyphId(info.glyph_id as u16)),
+                x_advance: if pos.x_advance == 0 {
+                    0.0 // e.g. a combining mark
+                } else {
+                    self.advance_in_points(pos.x_advance as f32 * h_scale_in_pixels + bold_pixels)
+                },
+                offset: vec2(
+                    pos.x_offset as f32 * h_scale,
+                    -pos.y_offset as f32 * v_scale,
+                ),
+            })
+            .collect()
+    }
+
+    /// One glyph per character with no kerning; used if the shaper can't read the font.
+    fn shape_without_shaper(&self, text: &str, right_to_left: bool) -> Vec<ShapedGlyphInfo> {
+        let mut glyphs: Vec<ShapedGlyphInfo> = text
+            .char_indices()
+            .map(|(cluster, c)| {
+                let glyph_info = self.glyph_info(c).unwrap_or_default();
+                ShapedGlyphInfo {
+                    cluster,
+                    glyph_info,
+                    x_advance: glyph_info.advance_width,
+                    offset: Vec2::ZERO,
+                }
+            })
+            .collect();
+        if right_to_left {
+            glyphs.reverse();
+        }
+        glyphs
+    }
+
     #[inline]
     pub fn pair_kerning(
         &self,
@@ -208,9 +423,195 @@ impl FontImpl {
     pub fn pixels_per_point(&self) -> f32 {
         self.pixels_per_point
     }
+
+    /// Can the glyphs be put between pixels,
+    /// i.e. are they rasterized at several subpixel offsets or as distance fields?
+    pub fn subpixel_positioning(&self) -> bool {
+        self.subpixel_positioning || self.atlas.lock().mode() == GlyphAtlasMode::DistanceField
+    }
+
+    /// Convert a horizontal advance to points, rounded to whole pixels if the font is hinted.
+    fn advance_in_points(&self, advance_in_pixels: f32) -> f32 {
+        if self.font_data.tweak.hinting {
+            advance_in_pixels.round() / self.pixels_per_point
+        } else {
+            advance_in_pixels / self.pixels_per_point
+        }
+    }
+
+    fn allocate_glyph(&self, glyph_id: ab_glyph::GlyphId) -> GlyphInfo {
+        assert!(glyph_id.0 != 0);
+        use ab_glyph::{Font as _, ScaleFont};
+
+        let font = &self.ab_glyph_font;
+        let pixels_per_point = self.pixels_per_point;
+        let bold_pixels = self.synthesis.bold_pixels(self.scale_in_pixels);
+        let scale_in_pixels = self.scale_in_pixels as f32;
+        let mut atlas = self.atlas.lock();
+
+        let advance_width_in_points = self.advance_in_points(
+            font.as_scaled(scale_in_pixels).h_advance(glyph_id) + bold_pixels as f32,
+        );
+
+        if atlas.mode() == GlyphAtlasMode::DistanceField {
+            return GlyphInfo {
+                advance_width: advance_width_in_points,
+                uv_rect: self.distance_field_uv_rect(&mut atlas, glyph_id),
+            };
+        }
+
+        if let Some(image) = self
+            .color_font
+            .as_ref()
+            .and_then(|color_font| color_font.rasterize(font, glyph_id, scale_in_pixels))
+        {
+            let [glyph_width, glyph_height] = image.size;
+            let (glyph_pos, atlas_image) = atlas.allocate((glyph_width, glyph_height));
+            for y in 0..glyph_height {
+                for x in 0..glyph_width {
+                    let color = image.pixels[y * glyph_width + x];
+                    if color != crate::Color32::TRANSPARENT {
+                        atlas_image.set_color((glyph_pos.0 + x, glyph_pos.1 + y), color);
+                    }
+                }
+            }
+
+            let offset_in_pixels = image.offset + vec2(0.0, scale_in_pixels);
+            let offset = offset_in_pixels / pixels_per_point + self.y_offset * Vec2::Y;
+            return GlyphInfo {
+                advance_width: advance_width_in_points,
+                uv_rect: UvRect {
+                    offset,
+                    size: vec2(glyph_width as f32, glyph_height as f32) / pixels_per_point,
+                    min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+                    max: [
+                        (glyph_pos.0 + glyph_width) as u16,
+                        (glyph_pos.1 + glyph_height) as u16,
+                    ],
+                    is_color: true,
+                    subpixel_variants: 1,
+                },
+            };
+        }
+
+        let num_variants = if self.subpixel_positioning {
+            SUBPIXEL_VARIANTS
+        } else {
+            1
+        };
+
+        // The glyph moved `x_offset` pixels to the right:
+        let outlined_glyph = |x_offset: f32| {
+            let glyph =
+                glyph_id.with_scale_and_position(scale_in_pixels, ab_glyph::point(x_offset, 0.0));
+            if self.synthesis.oblique || self.hinting_y_scale != 1.0 {
+                font.outline(glyph_id).map(|outline| {
+                    let skew = if self.synthesis.oblique {
+                        FontSynthesis::OBLIQUE_SKEW
+                    } else {
+                        0.0
+                    };
+                    let y_scale = self.hinting_y_scale;
+                    // Font units are y-up, so this slants the points above the baseline to the right:
+                    let outline = transform_outline(outline, |p| {
+                        let y = y_scale * p.y;
+                        ab_glyph::point(p.x + skew * y, y)
+                    });
+                    let scale_factor = font.as_scaled(scale_in_pixels).scale_factor();
+                    ab_glyph::OutlinedGlyph::new(glyph, outline, scale_factor)
+                })
+            } else {
+                font.outline_glyph(glyph)
+            }
+        };
+
+        let uv_rect = outlined_glyph(0.0).map(|glyph| {
+            let bb = glyph.px_bounds();
+            let mut outline_width = bb.width() as usize;
+            let glyph_height = bb.height() as usize;
+            if outline_width == 0 || glyph_height == 0 {
+                return UvRect::default();
+            }
+            if num_variants > 1 {
+                // Moving the glyph by a fraction of a pixel can make it cover one more column:
+                outline_width += 1;
+            }
+            let glyph_width = outline_width + bold_pixels;
+
+            let stride = glyph_width + SUBPIXEL_VARIANT_SPACING as usize;
+            let (glyph_pos, image) = atlas.allocate((
+                num_variants * stride - SUBPIXEL_VARIANT_SPACING as usize,
+                glyph_height,
+            ));
+            for variant in 0..num_variants {
+                let glyph = if variant == 0 {
+                    Some(glyph.clone())
+                } else {
+                    outlined_glyph(variant as f32 / num_variants as f32)
+                };
+                if let Some(glyph) = glyph {
+                    let left = glyph_pos.0
+                        + variant * stride
+                        + (glyph.px_bounds().min.x - bb.min.x) as usize;
+                    glyph.draw(|x, y, v| {
+                        if v > 0.0 {
+                            // Synthetic bold: smear the coverage to the right.
+                            for dx in 0..=bold_pixels {
+                                let px = left + x as usize + dx;
+                                let py = glyph_pos.1 + y as usize;
+                                image[(px, py)] = image[(px, py)].max(v);
+                            }
+                        }
+                    });
+                }
+            }
+
+            let offset_in_pixels = vec2(bb.min.x, scale_in_pixels + bb.min.y);
+            let offset = offset_in_pixels / pixels_per_point + self.y_offset * Vec2::Y;
+            UvRect {
+                offset,
+                size: vec2(glyph_width as f32, glyph_height as f32) / pixels_per_point,
+                min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+                max: [
+                    (glyph_pos.0 + glyph_width) as u16,
+                    (glyph_pos.1 + glyph_height) as u16,
+                ],
+                is_color: false,
+                subpixel_variants: num_variants as u8,
+            }
+        });
+
+        GlyphInfo {
+            advance_width: advance_width_in_points,
+            uv_rect: uv_rect.unwrap_or_default(),
+        }
+    }
+
+    /// Find (or add) the distance field of a glyph in the atlas, and scale it to this font size.
+    fn distance_field_uv_rect(
+        &self,
+        atlas: &mut TextureAtlas,
+        glyph_id: ab_glyph::GlyphId,
+    ) -> UvRect {
+        let key = crate::util::hash((&self.name, self.synthesis, glyph_id.0));
+        let uv_rect = atlas.distance_field_glyph(key).unwrap_or_else(|| {
+            let uv_rect =
+                allocate_distance_field_glyph(atlas, &self.ab_glyph_font, glyph_id, self.synthesis);
+            atlas.insert_distance_field_glyph(key, uv_rect);
+            uv_rect
+        });
+
+        let scale =
+            self.scale_in_pixels as f32 / DISTANCE_FIELD_SCALE_IN_PIXELS / self.pixels_per_point;
+        UvRect {
+            offset: scale * uv_rect.offset + self.y_offset * Vec2::Y,
+            size: scale * uv_rect.size,
+            ..uv_rect
+        }
+    }
 }
 
-type FontIndex = usize;
+pub(crate) type FontIndex = usize;
 
 /// Wrapper over multiple `FontImpl` (e.g. a primary + fallbacks for emojis)
 pub struct FontImplManager {
@@ -219,8 +620,11 @@ pub struct FontImplManager {
     characters: Option<std::collections::BTreeSet<char>>,
     replacement_glyph: (FontIndex, GlyphInfo),
     pixels_per_point: f32,
+    subpixel_positioning: bool,
     row_height: f32,
     glyph_info_cache: AHashMap<char, (FontIndex, GlyphInfo)>,
+    /// The characters in `glyph_info_cache` that no font supports, cached as the replacement glyph.
+    replaced_chars: AHashSet<char>,
 }
 
 impl FontImplManager {
@@ -231,12 +635,15 @@ impl FontImplManager {
                 characters: None,
                 replacement_glyph: Default::default(),
                 pixels_per_point: 1.0,
+                subpixel_positioning: false,
                 row_height: 0.0,
                 glyph_info_cache: Default::default(),
+                replaced_chars: Default::default(),
             };
         }
 
         let pixels_per_point = fonts[0].pixels_per_point();
+        let subpixel_positioning = fonts[0].subpixel_positioning();
         let row_height = fonts[0].row_height();
 
         let mut slf = Self {
@@ -244,8 +651,10 @@ impl FontImplManager {
             characters: None,
             replacement_glyph: Default::default(),
             pixels_per_point,
+            subpixel_positioning,
             row_height,
             glyph_info_cache: Default::default(),
+            replaced_chars: Default::default(),
         };
 
         const PRIMARY_REPLACEMENT_CHAR: char = '◻'; // white medium square
@@ -292,6 +701,16 @@ impl FontImplManager {
         (point * self.pixels_per_point).round() / self.pixels_per_point
     }
 
+    /// Round a horizontal position to whole pixels, unless the glyphs can be put between pixels.
+    #[inline(always)]
+    pub(crate) fn round_x_to_pixel(&self, x: f32) -> f32 {
+        if self.subpixel_positioning {
+            x
+        } else {
+            self.round_to_pixel(x)
+        }
+    }
+
     /// Height of one row of text. In points
     #[inline(always)]
     pub fn row_height(&self) -> f32 {
@@ -310,16 +729,49 @@ impl FontImplManager {
         self.glyph_info(c).1.advance_width
     }
 
+    /// Does any of the fonts support this character?
     pub fn has_glyph_info_and_cache(&mut self, c: char) -> bool {
-        if let Some(_font_index_glyph_info) = self.glyph_info_cache.get(&c) {
+        if self.replaced_chars.contains(&c) {
+            return false;
+        }
+        if self.glyph_info_cache.contains_key(&c) {
             return true;
         }
 
         self.glyph_info_no_cache_or_fallback(c).is_some()
     }
 
+    /// Which font to use for the given character.
+    ///
+    /// Prefers `preferred` (usually the font of the previous character) if it has a glyph for `c`,
+    /// so that runs of text stay in the same font for shaping.
+    /// Returns `None` if no font supports the character.
+    pub(crate) fn font_index_for_char(
+        &self,
+        c: char,
+        preferred: Option<FontIndex>,
+    ) -> Option<FontIndex> {
+        if let Some(preferred) = preferred {
+            if self.font_impl_list[preferred].has_glyph(c) {
+                return Some(preferred);
+            }
+        }
+        self.font_impl_list
+            .iter()
+            .position(|font_impl| font_impl.has_glyph(c))
+    }
+
+    #[inline]
+    pub(crate) fn font_impl(&self, font_index: FontIndex) -> &FontImpl {
+        &self.font_impl_list[font_index]
+    }
+
     pub fn push_font_impl(&mut self, new_font_impl: Arc<FontImpl>) {
         self.font_impl_list.push(new_font_impl);
+        // The new font may support them:
+        for c in self.replaced_chars.drain() {
+            self.glyph_info_cache.remove(&c);
+        }
     }
 
     /// `\n` will (intentionally) show up as the replacement character.
@@ -329,7 +781,10 @@ impl FontImplManager {
         }
 
         let font_index_glyph_info = self.glyph_info_no_cache_or_fallback(c);
-        let font_index_glyph_info = font_index_glyph_info.unwrap_or(self.replacement_glyph);
+        let font_index_glyph_info = font_index_glyph_info.unwrap_or_else(|| {
+            self.replaced_chars.insert(c);
+            self.replacement_glyph
+        });
         self.glyph_info_cache.insert(c, font_index_glyph_info);
         font_index_glyph_info
     }
@@ -356,64 +811,217 @@ impl FontImplManager {
 }
 
 #[inline]
-fn invisible_char(c: char) -> bool {
+pub(crate) fn invisible_char(c: char) -> bool {
     // See https://github.com/emilk/egui/issues/336
 
     // From https://www.fileformat.info/info/unicode/category/Cf/list.htm
     ('\u{200B}'..='\u{206F}').contains(&c) // TODO(emilk): heed bidi characters
 }
 
-fn allocate_glyph(
+/// Add the signed distance field of a glyph to the atlas.
+///
+/// The offset and size of the returned [`UvRect`] are in pixels at [`DISTANCE_FIELD_SCALE_IN_PIXELS`].
+fn allocate_distance_field_glyph(
     atlas: &mut TextureAtlas,
     font: &ab_glyph::FontArc,
     glyph_id: ab_glyph::GlyphId,
-    scale_in_pixels: f32,
-    y_offset: f32,
-    pixels_per_point: f32,
-) -> GlyphInfo {
-    assert!(glyph_id.0 != 0);
-    use ab_glyph::{Font as _, ScaleFont};
-
-    let glyph =
-        glyph_id.with_scale_and_position(scale_in_pixels, ab_glyph::Point { x: 0.0, y: 0.0 });
-
-    let uv_rect = font.outline_glyph(glyph).map(|glyph| {
-        let bb = glyph.px_bounds();
-        let glyph_width = bb.width() as usize;
-        let glyph_height = bb.height() as usize;
-        if glyph_width == 0 || glyph_height == 0 {
-            UvRect::default()
+    synthesis: FontSynthesis,
+) -> UvRect {
+    use ab_glyph::{Font as _, ScaleFont as _};
+
+    let outline = match font.outline(glyph_id) {
+        Some(outline) => outline,
+        None => return UvRect::default(),
+    };
+
+    let scale_factor = font
+        .as_scaled(DISTANCE_FIELD_SCALE_IN_PIXELS)
+        .scale_factor();
+    let skew = if synthesis.oblique {
+        FontSynthesis::OBLIQUE_SKEW
+    } else {
+        0.0
+    };
+    // Synthetic bold: grow the glyph by this much on each side.
+    let bold = 0.5 * synthesis.bold_pixels(DISTANCE_FIELD_SCALE_IN_PIXELS as u32) as f32;
+
+    // From font units (y-up) to pixels, y-down from the baseline:
+    let to_pixels = |p: ab_glyph::Point| {
+        pos2(
+            (p.x + skew * p.y) * scale_factor.horizontal + bold,
+            -p.y * scale_factor.vertical,
+        )
+    };
+    let segments = flatten_outline(&outline.curves, to_pixels);
+    if segments.is_empty() {
+        return UvRect::default();
+    }
+
+    let mut bounds = emath::Rect::NOTHING;
+    for &[a, b] in &segments {
+        bounds.extend_with(a);
+        bounds.extend_with(b);
+    }
+    let bounds = bounds.expand(DISTANCE_FIELD_SPREAD + bold);
+    let (left, top) = (bounds.min.x.floor(), bounds.min.y.floor());
+    let width = (bounds.max.x.ceil() - left) as usize;
+    let height = (bounds.max.y.ceil() - top) as usize;
+
+    let (glyph_pos, image) = atlas.allocate((width, height));
+    for y in 0..height {
+        for x in 0..width {
+            let texel_center = pos2(left + x as f32 + 0.5, top + y as f32 + 0.5);
+            let distance = signed_distance(&segments, texel_center) + bold;
+            image[(glyph_pos.0 + x, glyph_pos.1 + y)] =
+                (0.5 + 0.5 * distance / DISTANCE_FIELD_SPREAD).clamp(0.0, 1.0);
+        }
+    }
+
+    UvRect {
+        offset: vec2(left, DISTANCE_FIELD_SCALE_IN_PIXELS + top),
+        size: vec2(width as f32, height as f32),
+        min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+        max: [(glyph_pos.0 + width) as u16, (glyph_pos.1 + height) as u16],
+        is_color: false,
+        subpixel_variants: 1,
+    }
+}
+
+/// Approximate the curves of an outline with line segments.
+fn flatten_outline(
+    curves: &[ab_glyph::OutlineCurve],
+    to_pixels: impl Fn(ab_glyph::Point) -> Pos2,
+) -> Vec<[Pos2; 2]> {
+    use ab_glyph::OutlineCurve;
+
+    fn add_curve(segments: &mut Vec<[Pos2; 2]>, point_at: impl Fn(f32) -> Pos2) {
+        const STEPS_PER_CURVE: usize = 8;
+        let mut previous = point_at(0.0);
+        for i in 1..=STEPS_PER_CURVE {
+            let point = point_at(i as f32 / STEPS_PER_CURVE as f32);
+            segments.push([previous, point]);
+            previous = point;
+        }
+    }
+
+    let mut segments = vec![];
+
+    for curve in curves {
+        match *curve {
+            OutlineCurve::Line(p0, p1) => segments.push([to_pixels(p0), to_pixels(p1)]),
+            OutlineCurve::Quad(p0, p1, p2) => {
+                let [p0, p1, p2] = [p0, p1, p2].map(|p| to_pixels(p).to_vec2());
+                add_curve(&mut segments, |t| {
+                    let s = 1.0 - t;
+                    (s * s * p0 + 2.0 * s * t * p1 + t * t * p2).to_pos2()
+                });
+            }
+            OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                let [p0, p1, p2, p3] = [p0, p1, p2, p3].map(|p| to_pixels(p).to_vec2());
+                add_curve(&mut segments, |t| {
+                    let s = 1.0 - t;
+                    (s * s * s * p0 + 3.0 * s * s * t * p1 + 3.0 * s * t * t * p2 + t * t * t * p3)
+                        .to_pos2()
+                });
+            }
+        }
+    }
+    segments
+}
+
+/// Distance from `point` to the closest edge, positive inside the glyph (using the non-zero fill rule).
+fn signed_distance(segments: &[[Pos2; 2]], point: Pos2) -> f32 {
+    let mut closest_distance_sq = f32::INFINITY;
+    let mut winding = 0;
+    for &[a, b] in segments {
+        let edge = b - a;
+        let t = if edge.length_sq() > 0.0 {
+            ((point - a).dot(edge) / edge.length_sq()).clamp(0.0, 1.0)
         } else {
-            let (glyph_pos, image) = atlas.allocate((glyph_width, glyph_height));
-            glyph.draw(|x, y, v| {
-                if v > 0.0 {
-                    let px = glyph_pos.0 + x as usize;
-                    let py = glyph_pos.1 + y as usize;
-                    image[(px, py)] = v;
-                }
-            });
+            0.0
+        };
+        closest_distance_sq = closest_distance_sq.min((a + t * edge - point).length_sq());
 
-            let offset_in_pixels = vec2(bb.min.x as f32, scale_in_pixels + bb.min.y as f32);
-            let offset = offset_in_pixels / pixels_per_point + y_offset * Vec2::Y;
-            UvRect {
-                offset,
-                size: vec2(glyph_width as f32, glyph_height as f32) / pixels_per_point,
-                min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
-                max: [
-                    (glyph_pos.0 + glyph_width) as u16,
-                    (glyph_pos.1 + glyph_height) as u16,
-                ],
+        // Count the edges crossed by a ray going right from the point:
+        if (a.y <= point.y) != (b.y <= point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * edge.x;
+            if x > point.x {
+                winding += if b.y > a.y { 1 } else { -1 };
             }
         }
-    });
-    let uv_rect = uv_rect.unwrap_or_default();
+    }
+
+    let distance = closest_distance_sq.sqrt();
+    if winding == 0 {
+        -distance
+    } else {
+        distance
+    }
+}
+
+/// Scale the x-height of a font to a whole number of pixels: returns the vertical scale to apply to its glyphs.
+fn snapped_x_height_scale(
+    font_data: &FontData,
+    font: &ab_glyph::FontArc,
+    scale_in_pixels: u32,
+) -> f32 {
+    use ab_glyph::{Font as _, ScaleFont as _};
+    let x_height = ttf_parser::Face::from_slice(&font_data.font, font_data.index)
+        .ok()
+        .and_then(|face| face.x_height());
+    match x_height {
+        Some(x_height) if x_height > 0 => {
+            let x_height_in_pixels =
+                x_height as f32 * font.as_scaled(scale_in_pixels as f32).v_scale_factor();
+            x_height_in_pixels.round().max(1.0) / x_height_in_pixels
+        }
+        _ => 1.0,
+    }
+}
 
-    let advance_width_in_points =
-        font.as_scaled(scale_in_pixels).h_advance(glyph_id) / pixels_per_point;
+/// Move all points of an outline, e.g. to slant or hint it.
+///
+/// `transform` must be affine, so that the bounds can be found from the transformed corners.
+fn transform_outline(
+    outline: ab_glyph::Outline,
+    transform: impl Fn(ab_glyph::Point) -> ab_glyph::Point,
+) -> ab_glyph::Outline {
+    use ab_glyph::{point, Outline, OutlineCurve, Rect};
 
-    GlyphInfo {
-        id: glyph_id,
-        advance_width: advance_width_in_points,
-        uv_rect,
+    let Outline { bounds, curves } = outline;
+    let corners = [
+        bounds.min,
+        point(bounds.max.x, bounds.min.y),
+        bounds.max,
+        point(bounds.min.x, bounds.max.y),
+    ]
+    .map(&transform);
+    let (mut left, mut right) = (corners[0].x, corners[0].x);
+    let (mut bottom, mut top) = (corners[0].y, corners[0].y);
+    for corner in corners {
+        left = left.min(corner.x);
+        right = right.max(corner.x);
+        bottom = bottom.min(corner.y);
+        top = top.max(corner.y);
     }
+    // Font units are y-up, and `ab_glyph` puts the top in `min`:
+    let bounds = Rect {
+        min: point(left, top),
+        max: point(right, bottom),
+    };
+
+    let curves = curves
+        .into_iter()
+        .map(|curve| match curve {
+            OutlineCurve::Line(p0, p1) => OutlineCurve::Line(transform(p0), transform(p1)),
+            OutlineCurve::Quad(p0, p1, p2) => {
+                OutlineCurve::Quad(transform(p0), transform(p1), transform(p2))
+            }
+            OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                OutlineCurve::Cubic(transform(p0), transform(p1), transform(p2), transform(p3))
+            }
+        })
+        .collect();
+
+    Outline { bounds, curves }
 }
@@ -1,20 +1,21 @@
 #[cfg(feature = "system_fonts")]
 use font_kit::family_handle::FamilyHandle;
 use std::collections::BTreeMap;
-#[cfg(feature = "system_fonts")]
-use std::fs;
 use std::sync::Arc;
 
 use crate::{
     mutex::{Mutex, MutexGuard},
     text::{
-        font::{FontImpl, FontImplManager},
+        font::{FontImpl, FontImplManager, FontSynthesis},
         Galley, LayoutJob,
     },
-    TextureAtlas,
+    GlyphAtlasMode, TextureAtlas,
 };
 use emath::NumExt as _;
 
+#[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+use super::SystemFonts;
+
 // ----------------------------------------------------------------------------
 
 /// How to select a sized font.
@@ -26,7 +27,18 @@ pub struct FontId {
 
     /// What font family to use.
     pub font_type: FontType,
-    // TODO(emilk): weight (bold), italics, …
+
+    /// How bold the text should be.
+    ///
+    /// Picks the closest of the [`FontDefinitions::font_variants`], or makes the glyphs bolder if there is none.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weight: FontWeight,
+
+    /// Upright or italic.
+    ///
+    /// Picks an italic font of the [`FontDefinitions::font_variants`], or slants the glyphs if there is none.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub style: FontStyle,
 }
 
 impl Default for FontId {
@@ -35,6 +47,8 @@ impl Default for FontId {
         Self {
             size: 14.0,
             font_type: FontType::Proportional,
+            weight: FontWeight::NORMAL,
+            style: FontStyle::Normal,
         }
     }
 }
@@ -42,7 +56,12 @@ impl Default for FontId {
 impl FontId {
     #[inline]
     pub const fn new(size: f32, font_type: FontType) -> Self {
-        Self { size, font_type }
+        Self {
+            size,
+            font_type,
+            weight: FontWeight::NORMAL,
+            style: FontStyle::Normal,
+        }
     }
 
     #[inline]
@@ -54,15 +73,112 @@ impl FontId {
     pub const fn monospace(size: f32) -> Self {
         Self::new(size, FontType::Monospace)
     }
+
+    #[inline]
+    pub const fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    #[inline]
+    pub const fn with_style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Shorthand for `.with_weight(FontWeight::BOLD)`.
+    #[inline]
+    pub const fn bold(self) -> Self {
+        self.with_weight(FontWeight::BOLD)
+    }
+
+    /// Shorthand for `.with_style(FontStyle::Italic)`.
+    #[inline]
+    pub const fn italic(self) -> Self {
+        self.with_style(FontStyle::Italic)
+    }
 }
 
 #[allow(clippy::derive_hash_xor_eq)]
 impl std::hash::Hash for FontId {
     #[inline(always)]
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        let Self { size, font_type } = self;
+        let Self {
+            size,
+            font_type,
+            weight,
+            style,
+        } = self;
         crate::f32_hash(state, *size);
         font_type.hash(state);
+        weight.hash(state);
+        style.hash(state);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// How thick the strokes of a font are, on the same scale as CSS and OpenType:
+/// from 100 (thin) via 400 (normal) to 900 (black).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const THIN: Self = Self(100);
+    pub const LIGHT: Self = Self(300);
+    pub const NORMAL: Self = Self(400);
+    pub const MEDIUM: Self = Self(500);
+    pub const SEMIBOLD: Self = Self(600);
+    pub const BOLD: Self = Self(700);
+    pub const BLACK: Self = Self(900);
+
+    /// Semibold or heavier.
+    #[inline]
+    pub fn is_bold(self) -> bool {
+        self >= Self::SEMIBOLD
+    }
+}
+
+impl Default for FontWeight {
+    #[inline]
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+/// Upright or italic text.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FontStyle {
+    Normal,
+    Italic,
+}
+
+impl Default for FontStyle {
+    #[inline]
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// A bold and/or italic version of a font, see [`FontDefinitions::font_variants`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FontVariant {
+    /// Key into [`FontDefinitions::font_data_map`].
+    pub font: String,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+}
+
+impl FontVariant {
+    pub fn new(font: impl Into<String>, weight: FontWeight, style: FontStyle) -> Self {
+        Self {
+            font: font.into(),
+            weight,
+            style,
+        }
     }
 }
 
@@ -185,6 +301,15 @@ pub struct FontTweak {
     ///
     /// Example value: `2.0`.
     pub y_offset: f32,
+
+    /// Fit the glyphs to the pixel grid: the x-height (the top of lowercase letters)
+    /// is scaled to a whole number of pixels, and glyph advances are rounded to whole pixels.
+    ///
+    /// This makes small text crisper, at the cost of slightly distorted shapes and spacing.
+    ///
+    /// Default: `false`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hinting: bool,
 }
 
 impl Default for FontTweak {
@@ -193,6 +318,43 @@ impl Default for FontTweak {
             scale: 1.0,
             y_offset_factor: -0.2, // makes the default fonts look more centered in buttons and such
             y_offset: 0.0,
+            hinting: false,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Decides how [`FontsManager::ensure_correct_fonts_for_text`] adds fallback fonts
+/// for characters that none of the fonts of a [`FontType`] support.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FontFallbackPolicy {
+    /// Only these font types get fallback fonts added to them.
+    ///
+    /// Default: [`FontType::Proportional`] and [`FontType::Monospace`].
+    pub font_types: Vec<FontType>,
+
+    /// Fonts to try first for the characters of a script, before looking for a system font.
+    ///
+    /// The keys are four-letter ISO 15924 script codes, e.g. `"Hani"` (Chinese characters) or `"Arab"` (Arabic).
+    /// The values are keys into [`FontDefinitions::font_data_map`].
+    pub script_fonts: BTreeMap<String, Vec<String>>,
+
+    /// The most fallback fonts to add to each font type.
+    /// When more are needed, the least recently used fallback font is removed.
+    ///
+    /// Default: `8`.
+    pub max_fallback_fonts: usize,
+}
+
+impl Default for FontFallbackPolicy {
+    fn default() -> Self {
+        Self {
+            font_types: vec![FontType::Proportional, FontType::Monospace],
+            script_fonts: Default::default(),
+            max_fallback_fonts: 8,
         }
     }
 }
@@ -255,9 +417,62 @@ pub struct FontDefinitions {
     /// the first font and then move to the second, and so on.
     /// So the first font is the primary, and then comes a list of fallbacks in order of priority.
     pub type_fonts: BTreeMap<FontType, Vec<String>>,
+
+    /// Bold and italic versions of fonts, keyed by the name of the regular font.
+    ///
+    /// When the [`FontId`] asks for a [`FontWeight`] or [`FontStyle`], each font of the [`FontType`]
+    /// is replaced by its closest variant here (the regular font counts as normal weight and upright).
+    /// If that is still too light or not italic, the glyphs are made bolder or slanted when rasterized.
+    ///
+    /// ```
+    /// # use epaint::text::{FontData, FontDefinitions, FontStyle, FontVariant, FontWeight};
+    /// # let bold_font = FontData::from_static(include_bytes!("../../fonts/Ubuntu-Light.ttf"));
+    /// let mut fonts = FontDefinitions::default();
+    /// fonts.font_data_map.insert("Ubuntu-Bold".to_owned(), bold_font);
+    /// fonts.font_variants.insert(
+    ///     "Ubuntu-Light".to_owned(),
+    ///     vec![FontVariant::new("Ubuntu-Bold", FontWeight::BOLD, FontStyle::Normal)],
+    /// );
+    /// ```
+    pub font_variants: BTreeMap<String, Vec<FontVariant>>,
+
+    /// How to find fonts for characters that the fonts in [`Self::type_fonts`] don't support.
+    ///
+    /// Fallback fonts are appended to [`Self::type_fonts`] (and [`Self::font_data_map`]) as they are needed.
+    pub fallback: FontFallbackPolicy,
 }
 
 impl FontDefinitions {
+    /// The font to use instead of `font_name` for text of the given weight and style,
+    /// chosen from [`Self::font_variants`], and what it lacks that must be synthesized.
+    ///
+    /// An upright font is preferred over a slanted one of a better weight, as in CSS.
+    fn font_variant<'a>(
+        &'a self,
+        font_name: &'a str,
+        weight: FontWeight,
+        style: FontStyle,
+    ) -> (&'a str, FontSynthesis) {
+        let regular = (font_name, FontWeight::NORMAL, FontStyle::Normal);
+        let variants = self.font_variants.get(font_name).into_iter().flatten();
+        let (font, font_weight, font_style) = std::iter::once(regular)
+            .chain(variants.map(|variant| (variant.font.as_str(), variant.weight, variant.style)))
+            .min_by_key(|&(_, font_weight, font_style)| {
+                (
+                    font_style != style,
+                    (i32::from(font_weight.0) - i32::from(weight.0)).abs(),
+                    font_weight, // lighter on ties
+                )
+            })
+            .unwrap_or(regular);
+
+        let synthesis = FontSynthesis {
+            bold: weight.is_bold() && !font_weight.is_bold(),
+            oblique: style == FontStyle::Italic && font_style == FontStyle::Normal,
+        };
+        (font, synthesis)
+    }
+
     #[cfg(feature = "system_fonts")]
     pub fn query_fonts_for_character(c: char) -> Option<FamilyHandle> {
         use font_kit::source::SystemSource;
@@ -310,6 +525,7 @@ impl Default for FontDefinitions {
                         scale: 0.8,            // make it smaller
                         y_offset_factor: 0.07, // move it down slightly
                         y_offset: 0.0,
+                        hinting: false,
                     },
                 ),
             );
@@ -342,6 +558,8 @@ impl Default for FontDefinitions {
         Self {
             font_data_map,
             type_fonts,
+            font_variants: Default::default(),
+            fallback: Default::default(),
         }
     }
 }
@@ -397,9 +615,26 @@ impl FontPaintManager {
 
         if needs_recreate {
             let definitions = fonts_and_cache.font_manager.definitions.clone();
+            let mut font_manager =
+                FontsManager::new(pixels_per_point, max_texture_side, definitions);
+            font_manager.fallback_fonts = fonts_and_cache.font_manager.fallback_fonts.clone();
+            font_manager
+                .set_subpixel_positioning(fonts_and_cache.font_manager.subpixel_positioning());
+            let glyph_atlas_mode = fonts_and_cache.font_manager.glyph_atlas_mode();
+            if glyph_atlas_mode == GlyphAtlasMode::DistanceField
+                && !max_texture_side_changed
+                && !font_atlas_almost_full
+            {
+                // Distance fields don't depend on `pixels_per_point`, so keep them:
+                font_manager.set_atlas(fonts_and_cache.font_manager.atlas.clone());
+            } else {
+                font_manager.set_glyph_atlas_mode(glyph_atlas_mode);
+            }
+            #[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+            font_manager.set_system_fonts(fonts_and_cache.font_manager.system_fonts.clone());
 
             *fonts_and_cache = FontManagerAndGallyCache {
-                font_manager: FontsManager::new(pixels_per_point, max_texture_side, definitions),
+                font_manager,
                 galley_cache: Default::default(),
             };
         }
@@ -407,6 +642,30 @@ impl FontPaintManager {
         fonts_and_cache.galley_cache.flush_cache();
     }
 
+    /// Rasterize glyphs at several subpixel offsets, see [`FontsManager::set_subpixel_positioning`].
+    ///
+    /// This is set from [`crate::TessellationOptions::subpixel_text_positioning`] by `egui`.
+    pub fn set_subpixel_positioning(&self, subpixel_positioning: bool) {
+        let mut fonts_and_cache = self.0.lock();
+        if fonts_and_cache.font_manager.subpixel_positioning() != subpixel_positioning {
+            fonts_and_cache
+                .font_manager
+                .set_subpixel_positioning(subpixel_positioning);
+            fonts_and_cache.galley_cache = Default::default();
+        }
+    }
+
+    /// How glyphs are stored in the font atlas, see [`FontsManager::set_glyph_atlas_mode`].
+    ///
+    /// This is set from [`crate::TessellationOptions::glyph_atlas_mode`] by `egui`.
+    pub fn set_glyph_atlas_mode(&self, mode: GlyphAtlasMode) {
+        let mut fonts_and_cache = self.0.lock();
+        if fonts_and_cache.font_manager.glyph_atlas_mode() != mode {
+            fonts_and_cache.font_manager.set_glyph_atlas_mode(mode);
+            fonts_and_cache.galley_cache = Default::default();
+        }
+    }
+
     /// Call at the end of each frame (before painting) to get the change to the font texture since last call.
     pub fn font_image_delta(&self) -> Option<crate::ImageDelta> {
         self.lock().font_manager.atlas.lock().take_delta()
@@ -557,7 +816,10 @@ pub struct FontsManager {
     definitions: FontDefinitions,
     atlas: Arc<Mutex<TextureAtlas>>,
     fonts_impl_cache: FontsImplCache,
-    font_impl_manager_map: ahash::AHashMap<(u32, FontType), FontImplManager>,
+    font_impl_manager_map: ahash::AHashMap<(u32, FontType, FontWeight, FontStyle), FontImplManager>,
+    fallback_fonts: FallbackFonts,
+    #[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+    system_fonts: Arc<SystemFonts>,
 }
 
 impl FontsManager {
@@ -590,6 +852,9 @@ impl FontsManager {
             atlas,
             fonts_impl_cache: font_impl_cache,
             font_impl_manager_map: Default::default(),
+            fallback_fonts: Default::default(),
+            #[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+            system_fonts: Default::default(),
         }
     }
 
@@ -607,13 +872,62 @@ impl FontsManager {
         &mut self.definitions
     }
 
-    /// Get the right font implementation from size and [`FontFamily`].
+    /// Are glyphs rasterized at several subpixel offsets?
+    #[inline]
+    pub fn subpixel_positioning(&self) -> bool {
+        self.fonts_impl_cache.subpixel_positioning
+    }
+
+    /// Rasterize each glyph at several horizontal subpixel offsets (in the font atlas),
+    /// so that text is spaced evenly instead of snapped to whole pixels.
+    ///
+    /// Changing this forgets all fonts, so it is best done before laying out any text.
+    /// Default: `false`.
+    pub fn set_subpixel_positioning(&mut self, subpixel_positioning: bool) {
+        if self.fonts_impl_cache.subpixel_positioning != subpixel_positioning {
+            self.fonts_impl_cache.subpixel_positioning = subpixel_positioning;
+            self.fonts_impl_cache.cache.clear();
+            self.font_impl_manager_map.clear();
+        }
+    }
+
+    /// How glyphs are stored in the font atlas.
+    pub fn glyph_atlas_mode(&self) -> GlyphAtlasMode {
+        self.atlas.lock().mode()
+    }
+
+    /// Store glyph coverage for each font size, or one signed distance field per glyph
+    /// that is scaled to all sizes. See [`GlyphAtlasMode`].
+    ///
+    /// Changing this starts a new font atlas and forgets all fonts.
+    /// Default: [`GlyphAtlasMode::Coverage`].
+    pub fn set_glyph_atlas_mode(&mut self, mode: GlyphAtlasMode) {
+        if self.glyph_atlas_mode() != mode {
+            let size = self.atlas.lock().size();
+            let atlas = TextureAtlas::new_with_mode([size[0], 64], mode);
+            self.set_atlas(Arc::new(Mutex::new(atlas)));
+        }
+    }
+
+    fn set_atlas(&mut self, atlas: Arc<Mutex<TextureAtlas>>) {
+        self.atlas = atlas.clone();
+        self.fonts_impl_cache.atlas = atlas;
+        self.fonts_impl_cache.cache.clear();
+        self.font_impl_manager_map.clear();
+    }
+
+    /// Get the right font implementation from size, [`FontFamily`], weight and style.
     pub fn font(&mut self, font_id: &FontId) -> &mut FontImplManager {
-        let FontId { size, font_type } = font_id;
+        let FontId {
+            size,
+            font_type,
+            weight,
+            style,
+        } = font_id;
         let scale_in_pixels = self.fonts_impl_cache.scale_as_pixels(*size);
 
         self.font_impl_manager_map
-            .entry((scale_in_pixels, font_type.clone()))
+            .entry((scale_in_pixels, font_type.clone(), *weight, *style))
             .or_insert_with(|| {
                 let fonts = &self.definitions.type_fonts.get(font_type);
 
@@ -625,7 +939,12 @@ impl FontsManager {
 
                 let fonts: Vec<Arc<FontImpl>> = fonts
                     .iter()
-                    .map(|font_name| self.fonts_impl_cache.font_impl(scale_in_pixels, font_name))
+                    .map(|font_name| {
+                        let (font_name, synthesis) =
+                            self.definitions.font_variant(font_name, *weight, *style);
+                        self.fonts_impl_cache
+                            .font_impl(scale_in_pixels, font_name, synthesis)
+                    })
                     .collect();
 
                 FontImplManager::new(fonts)
@@ -642,62 +961,273 @@ impl FontsManager {
         self.font(font_id).row_height()
     }
 
-    #[cfg(feature = "system_fonts")]
+    /// Replace where [`Self::ensure_correct_fonts_for_text`] looks for fallback fonts.
+    ///
+    /// By default the fonts installed on the system are used.
+    #[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+    pub fn set_system_fonts(&mut self, system_fonts: Arc<SystemFonts>) {
+        self.system_fonts = system_fonts;
+    }
+
+    /// Make sure every character in `text` can be displayed with `main_font_id`,
+    /// by adding fallback fonts for the characters that its [`FontType`] doesn't support.
+    ///
+    /// Fallback fonts come from [`FontFallbackPolicy::script_fonts`],
+    /// or from the system if the `system_fonts` or `system_fonts_fontdb` feature is enabled.
     pub fn ensure_correct_fonts_for_text(&mut self, text: &str, main_font_id: &FontId) {
-        use font_kit::handle::Handle;
-        let FontId { size, font_type: _ } = main_font_id;
-        let scale_in_pixels = self.fonts_impl_cache.scale_as_pixels(*size);
+        self.ensure_correct_fonts(&[(text, main_font_id)]);
+    }
+
+    /// [`Self::ensure_correct_fonts_for_text`] for each section of `job`.
+    ///
+    /// A fallback font used by one section is never removed to make room for the fonts of another.
+    pub(crate) fn ensure_correct_fonts_for_job(&mut self, job: &LayoutJob) {
+        let sections: Vec<(&str, &FontId)> = job
+            .sections
+            .iter()
+            .map(|section| {
+                (
+                    &job.text[section.byte_range.clone()],
+                    &section.format.font_id,
+                )
+            })
+            .collect();
+        self.ensure_correct_fonts(&sections);
+    }
+
+    fn ensure_correct_fonts(&mut self, sections: &[(&str, &FontId)]) {
+        #[cfg(not(any(feature = "system_fonts", feature = "system_fonts_fontdb")))]
+        if self.definitions.fallback.script_fonts.is_empty() {
+            return; // Nowhere to find fallback fonts
+        }
+
+        // Mark the fallback fonts used by any of the sections before adding new ones,
+        // so that none of them is the least recently used one:
+        self.fallback_fonts.clock += 1;
+        let mut missing_chars = vec![];
+        for (text, main_font_id) in sections {
+            let policy = &self.definitions.fallback;
+            if policy.max_fallback_fonts == 0
+                || !policy.font_types.contains(&main_font_id.font_type)
+            {
+                continue;
+            }
+            for c in self.mark_used_fallback_fonts(text, main_font_id) {
+                missing_chars.push((c, *main_font_id));
+            }
+        }
+
+        missing_chars.sort_unstable_by_key(|(c, font_id)| (*c, font_id.font_type.clone()));
+        missing_chars.dedup_by(|(a, a_font_id), (b, b_font_id)| {
+            a == b && a_font_id.font_type == b_font_id.font_type
+        });
+        for (c, main_font_id) in missing_chars {
+            // A fallback font added for an earlier character may support this one too:
+            if !self.font(main_font_id).has_glyph_info_and_cache(c) {
+                let font_type = &main_font_id.font_type;
+                if let Some((name, font_data)) = self.find_fallback_font(font_type, c) {
+                    self.add_fallback_font(font_type, name, font_data);
+                }
+            }
+        }
+    }
 
-        let mut font_impl_manager = self.font(main_font_id);
+    /// Mark the fallback fonts that `text` is shown with as used now,
+    /// and return the characters that no font of `main_font_id` supports.
+    fn mark_used_fallback_fonts(&mut self, text: &str, main_font_id: &FontId) -> Vec<char> {
+        let mut used_font_indices = ahash::AHashSet::default();
+        let mut missing_chars = vec![];
+        let font = self.font(main_font_id);
         for c in text.chars() {
-            if font_impl_manager.has_glyph_info_and_cache(c) {
+            if c.is_control() {
                 continue;
             }
-            if let Some(fonts) = FontDefinitions::query_fonts_for_character(c) {
-                for font in fonts.fonts() {
-                    if let Handle::Path {
-                        path,
-                        font_index: _,
-                    } = font
-                    {
-                        if let Ok(buf) = fs::read(path) {
-                            let new_font_name =
-                                path.file_name().unwrap().to_str().unwrap().to_string();
-                            // update FontData
-                            let font_data = self
-                                .definitions
-                                .font_data_map
-                                .entry(new_font_name.clone())
-                                .or_insert_with(|| FontData::from_owned(buf));
-
-                            self.definitions
-                                .type_fonts
-                                .entry(FontType::Monospace)
-                                .or_default()
-                                .push(new_font_name.clone());
-                            self.definitions
-                                .type_fonts
-                                .entry(FontType::Proportional)
-                                .or_default()
-                                .push(new_font_name.clone());
-                            // update fonts_impl_cache
-                            let ab_glyph = ab_glyph_font_from_font_data(&new_font_name, font_data);
-                            let tweak = font_data.tweak;
-                            self.fonts_impl_cache
-                                .ab_glyph_fonts
-                                .insert(new_font_name.clone(), (tweak, ab_glyph));
-                            // update fonts_impl_cache
-                            let new_font_impl = self
-                                .fonts_impl_cache
-                                .font_impl(scale_in_pixels, &new_font_name);
-                            font_impl_manager = self.font(main_font_id);
-                            font_impl_manager.push_font_impl(new_font_impl);
-                        }
-                    }
+            match font.font_index_for_char(c, None) {
+                Some(font_index) => {
+                    used_font_indices.insert(font_index);
                 }
+                None => missing_chars.push(c),
+            }
+        }
+        let used_fonts: Vec<String> = used_font_indices
+            .into_iter()
+            .map(|font_index| font.font_impl(font_index).name().to_owned())
+            .collect();
+
+        let now = self.fallback_fonts.clock;
+        for fallback in &mut self.fallback_fonts.fonts {
+            if fallback.font_type == main_font_id.font_type && used_fonts.contains(&fallback.name) {
+                fallback.last_used = now;
             }
         }
+        missing_chars
     }
+
+    /// Find a font that supports `c` and isn't already used by `font_type`.
+    ///
+    /// Returns the name of the font, and its data if it isn't in the [`FontDefinitions`] yet.
+    fn find_fallback_font(
+        &self,
+        font_type: &FontType,
+        c: char,
+    ) -> Option<(String, Option<FontData>)> {
+        use unicode_script::UnicodeScript as _;
+
+        let type_fonts = self.definitions.type_fonts.get(font_type);
+        let is_unused =
+            |name: &str| type_fonts.map_or(true, |fonts| !fonts.iter().any(|f| f == name));
+
+        let script_fonts = self
+            .definitions
+            .fallback
+            .script_fonts
+            .get(c.script().short_name());
+        for name in script_fonts.into_iter().flatten() {
+            if let Some((_, ab_glyph_font)) = self.fonts_impl_cache.ab_glyph_fonts.get(name) {
+                if is_unused(name) && ab_glyph::Font::glyph_id(ab_glyph_font, c).0 != 0 {
+                    return Some((name.clone(), None));
+                }
+            }
+        }
+
+        #[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+        for (name, font_data) in self.system_fonts.fonts_for_character(c) {
+            let supports_c =
+                ab_glyph::FontRef::try_from_slice_and_index(&font_data.font, font_data.index)
+                    .map_or(false, |font| ab_glyph::Font::glyph_id(&font, c).0 != 0);
+            if supports_c && is_unused(&name) {
+                let font_data =
+                    (!self.definitions.font_data_map.contains_key(&name)).then(|| font_data);
+                return Some((name, font_data));
+            }
+        }
+
+        None
+    }
+
+    /// Append a fallback font to `font_type`, evicting the least recently used one if we are at the limit.
+    ///
+    /// Fonts used since the last clock tick are never evicted:
+    /// if they are all in use, the new font isn't added.
+    fn add_fallback_font(
+        &mut self,
+        font_type: &FontType,
+        name: String,
+        font_data: Option<FontData>,
+    ) {
+        let max_fallback_fonts = self.definitions.fallback.max_fallback_fonts;
+        let now = self.fallback_fonts.clock;
+        while self.fallback_fonts.count(font_type) >= max_fallback_fonts {
+            let least_recently_used = self
+                .fallback_fonts
+                .fonts
+                .iter()
+                .enumerate()
+                .filter(|(_, fallback)| {
+                    &fallback.font_type == font_type && fallback.last_used < now
+                })
+                .min_by_key(|(_, fallback)| fallback.last_used)
+                .map(|(index, _)| index);
+            match least_recently_used {
+                Some(index) => self.remove_fallback_font(index),
+                None => return,
+            }
+        }
+
+        let from_system = font_data.is_some()
+            || self
+                .fallback_fonts
+                .fonts
+                .iter()
+                .any(|fallback| fallback.name == name && fallback.from_system);
+        if let Some(font_data) = font_data {
+            let ab_glyph = ab_glyph_font_from_font_data(&name, &font_data);
+            self.fonts_impl_cache
+                .ab_glyph_fonts
+                .insert(name.clone(), (Arc::new(font_data.clone()), ab_glyph));
+            self.definitions
+                .font_data_map
+                .insert(name.clone(), font_data);
+        }
+
+        self.definitions
+            .type_fonts
+            .entry(font_type.clone())
+            .or_default()
+            .push(name.clone());
+        self.fallback_fonts.fonts.push(FallbackFont {
+            font_type: font_type.clone(),
+            name,
+            from_system,
+            last_used: self.fallback_fonts.clock,
+        });
+
+        // Rebuilt with the new font list on next use:
+        self.font_impl_manager_map
+            .retain(|(_, cached_font_type, _, _), _| cached_font_type != font_type);
+    }
+
+    fn remove_fallback_font(&mut self, index: usize) {
+        let removed = self.fallback_fonts.fonts.remove(index);
+
+        if let Some(fonts) = self.definitions.type_fonts.get_mut(&removed.font_type) {
+            fonts.retain(|name| name != &removed.name);
+        }
+        self.font_impl_manager_map
+            .retain(|(_, font_type, _, _), _| font_type != &removed.font_type);
+
+        let still_used = self
+            .definitions
+            .type_fonts
+            .values()
+            .any(|fonts| fonts.contains(&removed.name));
+        if removed.from_system && !still_used {
+            self.definitions.font_data_map.remove(&removed.name);
+            self.fonts_impl_cache.remove(&removed.name);
+        }
+    }
+
+    /// The fallback fonts added by [`Self::ensure_correct_fonts_for_text`] to a font type,
+    /// from the least to the most recently used.
+    pub fn fallback_fonts(&self, font_type: &FontType) -> Vec<&str> {
+        let mut fonts: Vec<&FallbackFont> = self
+            .fallback_fonts
+            .fonts
+            .iter()
+            .filter(|fallback| &fallback.font_type == font_type)
+            .collect();
+        fonts.sort_by_key(|fallback| fallback.last_used);
+        fonts
+            .iter()
+            .map(|fallback| fallback.name.as_str())
+            .collect()
+    }
+}
+
+/// Book-keeping of the fonts added by [`FontsManager::ensure_correct_fonts_for_text`].
+#[derive(Clone, Default)]
+struct FallbackFonts {
+    /// Counts calls to [`FontsManager::ensure_correct_fonts_for_text`], to find the least recently used font.
+    clock: u64,
+    fonts: Vec<FallbackFont>,
+}
+
+impl FallbackFonts {
+    fn count(&self, font_type: &FontType) -> usize {
+        self.fonts
+            .iter()
+            .filter(|fallback| &fallback.font_type == font_type)
+            .count()
+    }
+}
+
+#[derive(Clone)]
+struct FallbackFont {
+    font_type: FontType,
+    name: String,
+    /// Was the font data loaded from the system (rather than being in the [`FontDefinitions`] from the start)?
+    from_system: bool,
+    last_used: u64,
 }
 
 // ----------------------------------------------------------------------------
@@ -756,10 +1286,11 @@ impl GalleyCache {
 struct FontsImplCache {
     atlas: Arc<Mutex<TextureAtlas>>,
     pixels_per_point: f32,
-    ab_glyph_fonts: BTreeMap<String, (FontTweak, ab_glyph::FontArc)>,
+    subpixel_positioning: bool,
+    ab_glyph_fonts: BTreeMap<String, (Arc<FontData>, ab_glyph::FontArc)>,
 
-    /// Map font pixel sizes and names to the cached [`FontImpl`].
-    cache: ahash::AHashMap<(u32, String), Arc<FontImpl>>,
+    /// Map font pixel sizes, names and synthesis to the cached [`FontImpl`].
+    cache: ahash::AHashMap<(u32, String, FontSynthesis), Arc<FontImpl>>,
 }
 
 impl FontsImplCache {
@@ -771,20 +1302,26 @@ impl FontsImplCache {
         let ab_glyph_fonts = font_data
             .iter()
             .map(|(name, font_data)| {
-                let tweak = font_data.tweak;
                 let ab_glyph = ab_glyph_font_from_font_data(name, font_data);
-                (name.clone(), (tweak, ab_glyph))
+                (name.clone(), (Arc::new(font_data.clone()), ab_glyph))
             })
             .collect();
 
         Self {
             atlas,
             pixels_per_point,
+            subpixel_positioning: false,
             ab_glyph_fonts,
             cache: Default::default(),
         }
     }
 
+    /// Forget a font that has been removed from the [`FontDefinitions`].
+    fn remove(&mut self, font_name: &str) {
+        self.ab_glyph_fonts.remove(font_name);
+        self.cache.retain(|(_, name, _), _| name != font_name);
+    }
+
     #[inline]
     pub fn scale_as_pixels(&self, scale_in_points: f32) -> u32 {
         let scale_in_pixels = self.pixels_per_point * scale_in_points;
@@ -794,12 +1331,18 @@ impl FontsImplCache {
         scale_in_pixels.round() as u32
     }
 
-    pub fn font_impl(&mut self, scale_in_pixels: u32, font_name: &str) -> Arc<FontImpl> {
-        let (tweak, ab_glyph_font) = self
+    pub fn font_impl(
+        &mut self,
+        scale_in_pixels: u32,
+        font_name: &str,
+        synthesis: FontSynthesis,
+    ) -> Arc<FontImpl> {
+        let (font_data, ab_glyph_font) = self
             .ab_glyph_fonts
             .get(font_name)
             .unwrap_or_else(|| panic!("No font data found for {:?}", font_name))
             .clone();
+        let tweak = font_data.tweak;
 
         let scale_in_pixels = (scale_in_pixels as f32 * tweak.scale).round() as u32;
 
@@ -809,15 +1352,18 @@ impl FontsImplCache {
         } + tweak.y_offset;
 
         self.cache
-            .entry((scale_in_pixels, font_name.to_owned()))
+            .entry((scale_in_pixels, font_name.to_owned(), synthesis))
             .or_insert_with(|| {
                 Arc::new(FontImpl::new(
                     self.atlas.clone(),
                     self.pixels_per_point,
                     font_name.to_owned(),
                     ab_glyph_font,
+                    font_data,
                     scale_in_pixels,
                     y_offset_points,
+                    synthesis,
+                    self.subpixel_positioning,
                 ))
             })
             .clone()
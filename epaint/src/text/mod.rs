@@ -1,9 +1,12 @@
 //! Everything related to text, fonts, text layout, cursors etc.
 
+mod color_glyph;
 pub mod cursor;
 mod font;
 // mod font_source;
 mod fonts;
+#[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+mod system_fonts;
 mod text_layout;
 mod text_layout_types;
 
@@ -11,12 +14,17 @@ mod text_layout_types;
 pub const TAB_SIZE: usize = 4;
 
 pub use {
+    font::UvRect,
     fonts::{
-        FontData, FontDefinitions, FontId, FontPaintManager, FontTweak, FontType, FontsManager,
+        FontData, FontDefinitions, FontFallbackPolicy, FontId, FontPaintManager, FontStyle,
+        FontTweak, FontType, FontVariant, FontWeight, FontsManager,
     },
     text_layout::layout,
     text_layout_types::*,
 };
 
+#[cfg(any(feature = "system_fonts", feature = "system_fonts_fontdb"))]
+pub use system_fonts::SystemFonts;
+
 /// Suggested character to use to replace those in password text fields.
 pub const PASSWORD_REPLACEMENT_CHAR: char = '•';
@@ -0,0 +1,287 @@
+//! Finding installed fonts that can display a character.
+//!
+//! Used by [`super::FontsManager::ensure_correct_fonts_for_text`] to fall back to
+//! system fonts for characters that none of the [`super::FontDefinitions`] fonts cover.
+//!
+//! There are two backends:
+//! * `system_fonts`: asks skia and `font-kit`, which use the platform font APIs.
+//! * `system_fonts_fontdb`: a pure-Rust directory scan using [`fontdb`](https://docs.rs/fontdb),
+//!   reading the fontconfig configuration on Linux. Takes precedence if both features are enabled.
+
+use ahash::AHashSet;
+
+use crate::{mutex::Mutex, text::FontData};
+
+/// A source of installed fonts, queried one character at a time.
+///
+/// Lookups are remembered, so asking again for the same character doesn't search the fonts again.
+pub struct SystemFonts {
+    #[cfg(feature = "system_fonts_fontdb")]
+    source: Mutex<DatabaseSource>,
+
+    /// The face found for each character that some system font covers.
+    #[cfg(feature = "system_fonts_fontdb")]
+    found: Mutex<ahash::AHashMap<char, fontdb::ID>>,
+
+    /// The data of the faces that have been handed out, mapped from their files once and shared with the database.
+    #[cfg(feature = "system_fonts_fontdb")]
+    shared: Mutex<ahash::AHashMap<fontdb::ID, &'static [u8]>>,
+
+    /// Characters that no system font covers.
+    missing: Mutex<AHashSet<char>>,
+}
+
+impl Default for SystemFonts {
+    /// Use the fonts installed on this system.
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "system_fonts_fontdb")]
+            source: Mutex::new(DatabaseSource::System(None)),
+            #[cfg(feature = "system_fonts_fontdb")]
+            found: Default::default(),
+            #[cfg(feature = "system_fonts_fontdb")]
+            shared: Default::default(),
+            missing: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SystemFonts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SystemFonts").finish_non_exhaustive()
+    }
+}
+
+impl SystemFonts {
+    /// Only use the fonts found (recursively) in these directories,
+    /// instead of the ones installed on the system.
+    #[cfg(feature = "system_fonts_fontdb")]
+    pub fn from_dirs(dirs: impl IntoIterator<Item = impl AsRef<std::path::Path>>) -> Self {
+        let mut db = fontdb::Database::new();
+        for dir in dirs {
+            db.load_fonts_dir(dir);
+        }
+        Self {
+            source: Mutex::new(DatabaseSource::Custom(db)),
+            found: Default::default(),
+            shared: Default::default(),
+            missing: Default::default(),
+        }
+    }
+
+    /// Fonts that contain a glyph for `c`, as `(font_name, font_data)` pairs.
+    ///
+    /// Returns an empty list if no installed font supports the character.
+    pub fn fonts_for_character(&self, c: char) -> Vec<(String, FontData)> {
+        if self.missing.lock().contains(&c) {
+            return vec![];
+        }
+
+        let fonts = self.query(c);
+        if fonts.is_empty() {
+            self.missing.lock().insert(c);
+        }
+        fonts
+    }
+
+    #[cfg(feature = "system_fonts_fontdb")]
+    fn query(&self, c: char) -> Vec<(String, FontData)> {
+        let mut source = self.source.lock();
+        let db = source.database();
+
+        let cached = self.found.lock().get(&c).copied();
+        let id = match cached {
+            Some(id) => id,
+            None => match Self::best_face(db, c) {
+                Some(id) => {
+                    self.found.lock().insert(c, id);
+                    id
+                }
+                None => return vec![],
+            },
+        };
+
+        let name = match db.face(id) {
+            Some(face) => face.post_script_name.clone(),
+            None => return vec![],
+        };
+        let font = self.shared_face_data(db, id);
+        font.map(|font| (name, font)).into_iter().collect()
+    }
+
+    /// The data of the face, borrowed from the file mapping that the database shares with us
+    /// instead of copied, since fallback fonts like CJK and emoji fonts can be tens of megabytes.
+    ///
+    /// The mapping of a face is kept for as long as the program runs, so that the [`FontData`]
+    /// can borrow it like a `'static` font. Each face is only mapped once.
+    #[cfg(feature = "system_fonts_fontdb")]
+    fn shared_face_data(&self, db: &mut fontdb::Database, id: fontdb::ID) -> Option<FontData> {
+        let index = db.face(id)?.index;
+        let mut shared = self.shared.lock();
+        let font: &'static [u8] = match shared.get(&id) {
+            Some(font) => font,
+            None => {
+                // SAFETY: the font file could be changed by another process while it is mapped,
+                // which the database already risks when it maps the files to look at their faces.
+                let (data, _) = unsafe { db.make_shared_face_data(id) }?;
+                let data: &'static std::sync::Arc<dyn AsRef<[u8]> + Send + Sync> =
+                    Box::leak(Box::new(data));
+                shared.entry(id).or_insert((**data).as_ref())
+            }
+        };
+        Some(FontData {
+            font: std::borrow::Cow::Borrowed(font),
+            index,
+            tweak: Default::default(),
+        })
+    }
+
+    /// Scan all faces in `db` for the best one that covers `c`.
+    #[cfg(feature = "system_fonts_fontdb")]
+    fn best_face(db: &fontdb::Database, c: char) -> Option<fontdb::ID> {
+        let covers = |face: &&fontdb::FaceInfo| {
+            db.with_face_data(face.id, |data, index| {
+                ab_glyph::FontRef::try_from_slice_and_index(data, index)
+                    .map_or(false, |font| ab_glyph::Font::glyph_id(&font, c).0 != 0)
+            })
+            .unwrap_or(false)
+        };
+
+        // Prefer an upright face of normal weight, and break ties by name
+        // so that the result doesn't depend on the order the directories were scanned in.
+        let best = db.faces().iter().filter(covers).min_by_key(|face| {
+            (
+                face.style != fontdb::Style::Normal,
+                (i32::from(face.weight.0) - i32::from(fontdb::Weight::NORMAL.0)).abs(),
+                face.post_script_name.clone(),
+            )
+        });
+        best.map(|face| face.id)
+    }
+
+    #[cfg(not(feature = "system_fonts_fontdb"))]
+    fn query(&self, c: char) -> Vec<(String, FontData)> {
+        use font_kit::handle::Handle;
+
+        let family = match super::FontDefinitions::query_fonts_for_character(c) {
+            Some(family) => family,
+            None => return vec![],
+        };
+
+        family
+            .fonts()
+            .iter()
+            .filter_map(|font| match font {
+                Handle::Path { path, font_index } => {
+                    let buf = std::fs::read(path).ok()?;
+                    let name = path.file_name()?.to_str()?.to_owned();
+                    let mut font = FontData::from_owned(buf);
+                    font.index = *font_index;
+                    Some((name, font))
+                }
+                Handle::Memory { .. } => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "system_fonts_fontdb")]
+enum DatabaseSource {
+    /// The installed fonts, scanned on first use.
+    System(Option<fontdb::Database>),
+
+    /// Fonts from user-provided directories.
+    Custom(fontdb::Database),
+}
+
+#[cfg(feature = "system_fonts_fontdb")]
+impl DatabaseSource {
+    fn database(&mut self) -> &mut fontdb::Database {
+        match self {
+            Self::System(db) => db.get_or_insert_with(|| {
+                let mut db = fontdb::Database::new();
+                db.load_system_fonts();
+                db
+            }),
+            Self::Custom(db) => db,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "system_fonts_fontdb", feature = "default_fonts"))]
+#[test]
+fn test_fallback_to_fonts_in_dir() {
+    use crate::text::{FontDefinitions, FontId, FontType, FontsManager};
+    use std::sync::Arc;
+
+    let dir = std::env::temp_dir().join(format!("epaint_system_fonts_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(
+        dir.join("Hack-Regular.ttf"),
+        include_bytes!("../../fonts/Hack-Regular.ttf"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("nested/NotoEmoji-Regular.ttf"),
+        include_bytes!("../../fonts/NotoEmoji-Regular.ttf"),
+    )
+    .unwrap();
+    std::fs::write(dir.join("not-a-font.txt"), "hello").unwrap();
+
+    let system_fonts = Arc::new(SystemFonts::from_dirs([&dir]));
+
+    // Only the definition fonts, and none of them has an emoji:
+    let mut definitions = FontDefinitions::default();
+    for fonts in definitions.type_fonts.values_mut() {
+        fonts.retain(|name| name == "Ubuntu-Light");
+    }
+
+    let emoji = '🌵';
+    let font_names = |fonts: Vec<(String, crate::text::FontData)>| -> Vec<String> {
+        fonts.into_iter().map(|(name, _)| name).collect()
+    };
+    assert_eq!(
+        font_names(system_fonts.fonts_for_character(emoji)),
+        ["NotoEmoji"]
+    );
+    assert_eq!(
+        font_names(system_fonts.fonts_for_character('a')),
+        ["Hack-Regular"]
+    );
+    assert!(system_fonts.fonts_for_character('\u{E000}').is_empty());
+    assert!(system_fonts.missing.lock().contains(&'\u{E000}'));
+    assert!(system_fonts.found.lock().contains_key(&emoji));
+    assert_eq!(
+        font_names(system_fonts.fonts_for_character(emoji)),
+        ["NotoEmoji"],
+        "a cached lookup gives the same font"
+    );
+
+    // The font data is shared with the database, and only mapped once:
+    let font_data = |c| system_fonts.fonts_for_character(c).remove(0).1.font;
+    let (first, second) = (font_data(emoji), font_data(emoji));
+    assert!(matches!(first, std::borrow::Cow::Borrowed(_)));
+    assert_eq!(first.as_ptr(), second.as_ptr());
+    assert_eq!(&*first, include_bytes!("../../fonts/NotoEmoji-Regular.ttf"));
+
+    let mut fonts = FontsManager::new(1.0, 1024, definitions);
+    fonts.set_system_fonts(system_fonts);
+    let font_id = FontId::proportional(14.0);
+    assert!(!fonts.font(&font_id).has_glyph_info_and_cache(emoji));
+
+    fonts.ensure_correct_fonts_for_text(&format!("cactus {}", emoji), &font_id);
+
+    assert!(fonts.font(&font_id).has_glyph_info_and_cache(emoji));
+    assert_eq!(
+        fonts.definitions().type_fonts[&FontType::Proportional],
+        ["Ubuntu-Light", "NotoEmoji"]
+    );
+    assert_eq!(
+        fonts.definitions().type_fonts[&FontType::Monospace],
+        ["Ubuntu-Light"],
+        "only the font type that needed it gets the fallback font"
+    );
+
+    // The fonts stay mapped, which keeps them from being removed on Windows:
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -1,8 +1,12 @@
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
-use super::{FontsManager, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals};
-use crate::{Color32, Mesh, Stroke, Vertex};
+use super::{
+    font::{invisible_char, FontImplManager, FontIndex, UvRect},
+    FontStyle, FontsManager, Galley, Glyph, LayoutJob, LayoutSection, Row, RowVisuals, ShapedGlyph,
+    TextDirection, TextOverflow,
+};
+use crate::{Color32, Mesh, Stroke};
 use emath::*;
 
 // ----------------------------------------------------------------------------
@@ -43,8 +47,26 @@ struct Paragraph {
     /// Start of the next glyph to be added.
     pub cursor_x: f32,
     pub glyphs: Vec<Glyph>,
+    /// Ordered by [`ShapedGlyph::char_index`], which indexes [`Self::glyphs`].
+    pub shaped_glyphs: Vec<ShapedGlyph>,
     /// In case of an empty paragraph ("\n"), use this as height.
     pub empty_paragraph_height: f32,
+    /// Is the base direction of this paragraph right-to-left?
+    pub is_rtl: bool,
+}
+
+/// Set the style of the fonts of the sections with the deprecated [`TextFormat::italics`] to italic.
+#[allow(deprecated)]
+fn italics_as_font_style(mut job: Arc<LayoutJob>) -> Arc<LayoutJob> {
+    if job.sections.iter().any(|section| section.format.italics) {
+        for section in &mut Arc::make_mut(&mut job).sections {
+            if section.format.italics {
+                section.format.font_id.style = FontStyle::Italic;
+                section.format.italics = false;
+            }
+        }
+    }
+    job
 }
 
 /// Layout text into a [`Galley`].
@@ -52,14 +74,31 @@ struct Paragraph {
 /// In most cases you should use [`crate::Fonts::layout_job`] instead
 /// since that memoizes the input, making subsequent layouting of the same text much faster.
 pub fn layout(fonts: &mut FontsManager, job: Arc<LayoutJob>) -> Galley {
+    let job = italics_as_font_style(job);
+    fonts.ensure_correct_fonts_for_job(&job);
+    let bidi = BidiLevels::new(&job);
+
+    let mut font_names = FontNames::default();
     let mut paragraphs = vec![Paragraph::default()];
     for (section_index, section) in job.sections.iter().enumerate() {
-        layout_section(fonts, &job, section_index as u32, section, &mut paragraphs);
+        layout_section(
+            fonts,
+            &job,
+            &bidi,
+            &mut font_names,
+            section_index as u32,
+            section,
+            &mut paragraphs,
+        );
     }
 
     let point_scale = PointScale::new(fonts.pixels_per_point());
 
-    let mut rows = rows_from_paragraphs(fonts, paragraphs, &job);
+    let (mut rows, elided) = rows_from_paragraphs(fonts, &mut font_names, paragraphs, &job);
+
+    for row in &mut rows {
+        reorder_row_visually(row);
+    }
 
     let justify = job.justify && job.wrap.max_width.is_finite();
 
@@ -78,12 +117,98 @@ pub fn layout(fonts: &mut FontsManager, job: Arc<LayoutJob>) -> Galley {
         }
     }
 
-    galley_from_rows(point_scale, job, rows)
+    galley_from_rows(point_scale, job, rows, elided, font_names.0)
+}
+
+/// The names of the fonts used in a layout, for [`Galley::font_names`].
+#[derive(Default)]
+struct FontNames(Vec<String>);
+
+impl FontNames {
+    /// The index of the font in [`Galley::font_names`].
+    fn index(&mut self, font_name: &str) -> u32 {
+        let index = match self.0.iter().position(|name| name == font_name) {
+            Some(index) => index,
+            None => {
+                self.0.push(font_name.to_owned());
+                self.0.len() - 1
+            }
+        };
+        index as u32
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The result of running the Unicode Bidirectional Algorithm on [`LayoutJob::text`].
+#[derive(Default)]
+struct BidiLevels {
+    /// The embedding level of each byte of the text.
+    /// Empty if everything is left-to-right.
+    levels: Vec<u8>,
+
+    /// Byte ranges of the bidi paragraphs that are right-to-left.
+    rtl_paragraphs: Vec<Range<usize>>,
+}
+
+impl BidiLevels {
+    fn new(job: &LayoutJob) -> Self {
+        use unicode_bidi::{BidiInfo, Level};
+
+        let default_level = match job.direction {
+            TextDirection::Auto => None,
+            TextDirection::LeftToRight => Some(Level::ltr()),
+            TextDirection::RightToLeft => Some(Level::rtl()),
+        };
+
+        if job.direction != TextDirection::RightToLeft && job.text.is_ascii() {
+            return Self::default(); // early-out optimization
+        }
+
+        let bidi_info = BidiInfo::new(&job.text, default_level);
+
+        let rtl_paragraphs = bidi_info
+            .paragraphs
+            .iter()
+            .filter(|paragraph| paragraph.level.is_rtl())
+            .map(|paragraph| paragraph.range.clone())
+            .collect();
+
+        let levels = if bidi_info.has_rtl() {
+            bidi_info
+                .levels
+                .iter()
+                .map(|level| level.number())
+                .collect()
+        } else {
+            vec![]
+        };
+
+        Self {
+            levels,
+            rtl_paragraphs,
+        }
+    }
+
+    #[inline]
+    fn level(&self, byte_index: usize) -> u8 {
+        self.levels.get(byte_index).copied().unwrap_or(0)
+    }
+
+    fn is_rtl_paragraph(&self, byte_index: usize) -> bool {
+        self.rtl_paragraphs
+            .iter()
+            .any(|range| range.start <= byte_index && byte_index < range.end)
+    }
 }
 
+// ----------------------------------------------------------------------------
+
 fn layout_section(
     fonts_manager: &mut FontsManager,
     job: &LayoutJob,
+    bidi: &BidiLevels,
+    font_names: &mut FontNames,
     section_index: u32,
     section: &LayoutSection,
     out_paragraphs: &mut Vec<Paragraph>,
@@ -94,46 +219,285 @@ fn layout_section(
         format,
     } = section;
 
-    #[cfg(feature = "system_fonts")]
-    fonts_manager.ensure_correct_fonts_for_text(&job.text, &format.font_id);
-
     let font = fonts_manager.font(&format.font_id);
-    let font_height = font.row_height();
+    let line_height = format.line_height.unwrap_or_else(|| font.row_height());
 
     let mut paragraph = out_paragraphs.last_mut().unwrap();
     if paragraph.glyphs.is_empty() {
-        paragraph.empty_paragraph_height = font_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+        paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
     }
 
     paragraph.cursor_x += leading_space;
 
-    let mut last_glyph_id = None;
+    let text = &job.text[byte_range.clone()];
+    let lines: Vec<&str> = if job.break_on_newline {
+        text.split('\n').collect()
+    } else {
+        vec![text]
+    };
 
-    for chr in job.text[byte_range.clone()].chars() {
-        if job.break_on_newline && chr == '\n' {
+    let mut line_start = byte_range.start;
+    for (line_nr, line) in lines.into_iter().enumerate() {
+        if line_nr > 0 {
             out_paragraphs.push(Paragraph::default());
             paragraph = out_paragraphs.last_mut().unwrap();
-            paragraph.empty_paragraph_height = font_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
-        } else {
-            let (font_impl, glyph_info) = font.glyph_info_and_font_impl(chr);
-            if let Some(font_impl) = font_impl {
-                if let Some(last_glyph_id) = last_glyph_id {
-                    paragraph.cursor_x += font_impl.pair_kerning(last_glyph_id, glyph_info.id);
+            paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+        }
+        if paragraph.glyphs.is_empty() {
+            paragraph.is_rtl = bidi.is_rtl_paragraph(line_start);
+        }
+        let first_glyph = paragraph.glyphs.len();
+
+        for run in font_runs(font, line, |i| bidi.level(line_start + i)) {
+            let run_text = &line[run.byte_range];
+            match run.font_index {
+                Some(font_index) => {
+                    let font_impl = font.font_impl(font_index);
+                    let font_name_index = font_names.index(font_impl.name());
+                    layout_shaped_run(
+                        font,
+                        font_index,
+                        font_name_index,
+                        run_text,
+                        section_index,
+                        run.bidi_level,
+                        paragraph,
+                    );
+                }
+                None => {
+                    layout_unshaped_run(
+                        font,
+                        font_names,
+                        run_text,
+                        section_index,
+                        run.bidi_level,
+                        paragraph,
+                    );
                 }
             }
+        }
 
-            paragraph.glyphs.push(Glyph {
-                chr,
-                pos: pos2(paragraph.cursor_x, f32::NAN),
-                size: vec2(glyph_info.advance_width, font_height),
-                uv_rect: glyph_info.uv_rect,
-                section_index,
+        for glyph in &mut paragraph.glyphs[first_glyph..] {
+            glyph.size.y = line_height;
+        }
+        if format.extra_letter_spacing != 0.0 {
+            add_letter_spacing(
+                &mut paragraph.glyphs[first_glyph..],
+                format.extra_letter_spacing,
+            );
+            paragraph.cursor_x += format.extra_letter_spacing * line.chars().count() as f32;
+        }
+
+        line_start += line.len() + 1; // skip the '\n'
+    }
+}
+
+/// Add [`TextFormat::extra_letter_spacing`] after each of the glyphs,
+/// which are in logical order and not yet reordered visually.
+fn add_letter_spacing(glyphs: &mut [Glyph], extra_letter_spacing: f32) {
+    let mut extra_x = 0.0;
+    for glyph in glyphs {
+        glyph.pos.x += extra_x;
+        glyph.size.x += extra_letter_spacing;
+        extra_x += extra_letter_spacing;
+    }
+}
+
+/// A piece of a line of text that can be shaped with a single font in a single direction.
+struct FontRun {
+    byte_range: Range<usize>,
+
+    /// `None` for characters that we lay out one by one instead of shaping,
+    /// i.e. tabs and characters that no font supports.
+    font_index: Option<FontIndex>,
+
+    bidi_level: u8,
+}
+
+/// Split a line of text into runs that each use a single font (primary or fallback)
+/// and have the same bidi level.
+fn font_runs(font: &FontImplManager, line: &str, bidi_level: impl Fn(usize) -> u8) -> Vec<FontRun> {
+    let mut runs = vec![];
+    let mut run_start = 0;
+    let mut current_run: Option<(Option<FontIndex>, u8)> = None;
+
+    for (byte_index, chr) in line.char_indices() {
+        let current_font_index = current_run.and_then(|(font_index, _)| font_index);
+        let font_index = if chr == '\t' {
+            None
+        } else if current_font_index.is_some() && invisible_char(chr) {
+            // Keep joiners etc in the run, since they affect how it is shaped.
+            current_font_index
+        } else {
+            font.font_index_for_char(chr, current_font_index)
+        };
+        let run = (font_index, bidi_level(byte_index));
+
+        if current_run != Some(run) {
+            if let Some((font_index, bidi_level)) = current_run {
+                runs.push(FontRun {
+                    byte_range: run_start..byte_index,
+                    font_index,
+                    bidi_level,
+                });
+            }
+            run_start = byte_index;
+            current_run = Some(run);
+        }
+    }
+
+    if let Some((font_index, bidi_level)) = current_run {
+        runs.push(FontRun {
+            byte_range: run_start..line.len(),
+            font_index,
+            bidi_level,
+        });
+    }
+
+    runs
+}
+
+/// Shape the text using a single font and add one [`Glyph`] per character to the paragraph.
+///
+/// The glyphs are positioned in logical order, even for right-to-left text.
+/// They are moved to their visual positions by [`reorder_row_visually`] after line breaking.
+fn layout_shaped_run(
+    font: &FontImplManager,
+    font_index: FontIndex,
+    font_name_index: u32,
+    text: &str,
+    section_index: u32,
+    bidi_level: u8,
+    paragraph: &mut Paragraph,
+) {
+    struct Cluster {
+        char_range: Range<usize>,
+        advance: f32,
+        /// Offset of each glyph from the visual left side of the cluster.
+        glyphs: Vec<(Vec2, UvRect)>,
+    }
+
+    let font_height = font.row_height();
+    let right_to_left = bidi_level % 2 == 1;
+    let shaped = font.font_impl(font_index).shape(text, right_to_left);
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let char_index_from_byte = |byte_index: usize| chars.partition_point(|&(b, _)| b < byte_index);
+
+    // Where each cluster starts, in logical order:
+    let mut cluster_starts: Vec<usize> = shaped.iter().map(|g| g.cluster).collect();
+    cluster_starts.sort_unstable();
+    cluster_starts.dedup();
+
+    // Group the glyphs (which are in visual order) into clusters:
+    let mut clusters = Vec::with_capacity(cluster_starts.len());
+    let mut cluster_begin = 0;
+    while cluster_begin < shaped.len() {
+        let cluster = shaped[cluster_begin].cluster;
+        let cluster_end = cluster_begin
+            + shaped[cluster_begin..]
+                .iter()
+                .take_while(|g| g.cluster == cluster)
+                .count();
+
+        let next_cluster = cluster_starts
+            .get(cluster_starts.partition_point(|&c| c <= cluster))
+            .copied()
+            .unwrap_or(text.len());
+
+        let mut advance = 0.0;
+        let mut glyphs = Vec::with_capacity(cluster_end - cluster_begin);
+        for glyph in &shaped[cluster_begin..cluster_end] {
+            glyphs.push((vec2(advance, 0.0) + glyph.offset, glyph.glyph_info.uv_rect));
+            advance += glyph.x_advance;
+        }
+
+        clusters.push(Cluster {
+            char_range: char_index_from_byte(cluster)..char_index_from_byte(next_cluster),
+            advance,
+            glyphs,
+        });
+        cluster_begin = cluster_end;
+    }
+    clusters.sort_by_key(|cluster| cluster.char_range.start);
+
+    let first_char_index = paragraph.glyphs.len();
+    paragraph.glyphs.extend(chars.iter().map(|&(_, chr)| Glyph {
+        chr,
+        pos: pos2(paragraph.cursor_x, f32::NAN),
+        size: vec2(0.0, font_height),
+        font_height,
+        section_index,
+        bidi_level,
+    }));
+
+    for cluster in clusters {
+        let pen_x = paragraph.cursor_x;
+        paragraph.cursor_x = font.round_x_to_pixel(pen_x + cluster.advance);
+
+        // The characters of the cluster share its advance, so the cursor can be placed within a ligature:
+        let num_chars = cluster.char_range.len().max(1);
+        let char_advance = (paragraph.cursor_x - pen_x) / num_chars as f32;
+        for (i, char_index) in cluster.char_range.clone().enumerate() {
+            let glyph = &mut paragraph.glyphs[first_char_index + char_index];
+            glyph.pos.x = pen_x + i as f32 * char_advance;
+            glyph.size.x = char_advance;
+        }
+
+        // The shaped glyphs follow the character that ends up visually leftmost in the cluster:
+        let owner_index = if right_to_left {
+            cluster.char_range.end.max(cluster.char_range.start + 1) - 1
+        } else {
+            cluster.char_range.start
+        };
+        for (offset, uv_rect) in cluster.glyphs {
+            paragraph.shaped_glyphs.push(ShapedGlyph {
+                char_index: (first_char_index + owner_index) as u32,
+                cluster_start: (first_char_index + cluster.char_range.start) as u32,
+                cluster_end: (first_char_index + cluster.char_range.end) as u32,
+                offset,
+                uv_rect,
+                font_index: font_name_index,
             });
+        }
+    }
+}
 
-            paragraph.cursor_x += glyph_info.advance_width;
-            paragraph.cursor_x = font.round_to_pixel(paragraph.cursor_x);
-            last_glyph_id = Some(glyph_info.id);
+/// Lay out characters one by one, without shaping.
+fn layout_unshaped_run(
+    font: &mut FontImplManager,
+    font_names: &mut FontNames,
+    text: &str,
+    section_index: u32,
+    bidi_level: u8,
+    paragraph: &mut Paragraph,
+) {
+    let font_height = font.row_height();
+    for chr in text.chars() {
+        let (font_impl, glyph_info) = font.glyph_info_and_font_impl(chr);
+
+        if let Some(font_impl) = font_impl {
+            let char_index = paragraph.glyphs.len() as u32;
+            paragraph.shaped_glyphs.push(ShapedGlyph {
+                char_index,
+                cluster_start: char_index,
+                cluster_end: char_index + 1,
+                offset: Vec2::ZERO,
+                uv_rect: glyph_info.uv_rect,
+                font_index: font_names.index(font_impl.name()),
+            });
         }
+        paragraph.glyphs.push(Glyph {
+            chr,
+            pos: pos2(paragraph.cursor_x, f32::NAN),
+            size: vec2(glyph_info.advance_width, font_height),
+            font_height,
+            section_index,
+            bidi_level,
+        });
+
+        paragraph.cursor_x += glyph_info.advance_width;
+        paragraph.cursor_x = font.round_x_to_pixel(paragraph.cursor_x);
     }
 }
 
@@ -142,27 +506,47 @@ fn rect_from_x_range(x_range: RangeInclusive<f32>) -> Rect {
     Rect::from_x_y_ranges(x_range, 0.0..=0.0)
 }
 
+/// Break the paragraphs into rows.
+///
+/// Also returns `true` if the text didn't fit in [`super::TextWrapping::max_rows`] and was elided.
 fn rows_from_paragraphs(
     fonts: &mut FontsManager,
+    font_names: &mut FontNames,
     paragraphs: Vec<Paragraph>,
     job: &LayoutJob,
-) -> Vec<Row> {
+) -> (Vec<Row>, bool) {
     let num_paragraphs = paragraphs.len();
+    let max_rows = job.wrap.max_rows;
+
+    // The end of the text, for `TextOverflow::MiddleEllipsis`:
+    let last_paragraph = if max_rows > 0 && job.wrap.overflow == TextOverflow::MiddleEllipsis {
+        paragraphs.last().cloned()
+    } else {
+        None
+    };
 
     let mut rows = vec![];
+    let mut elided = false;
 
     for (i, paragraph) in paragraphs.into_iter().enumerate() {
         let is_last_paragraph = (i + 1) == num_paragraphs;
 
+        if max_rows > 0 && rows.len() >= max_rows {
+            elided = true;
+            break;
+        }
+
         if paragraph.glyphs.is_empty() {
             rows.push(Row {
                 glyphs: vec![],
+                shaped_glyphs: vec![],
                 visuals: Default::default(),
                 rect: Rect::from_min_size(
                     pos2(paragraph.cursor_x, 0.0),
                     vec2(0.0, paragraph.empty_paragraph_height),
                 ),
                 ends_with_newline: !is_last_paragraph,
+                is_rtl: paragraph.is_rtl,
             });
         } else {
             let paragraph_max_x = paragraph.glyphs.last().unwrap().max_x();
@@ -171,42 +555,55 @@ fn rows_from_paragraphs(
                 let paragraph_min_x = paragraph.glyphs[0].pos.x;
                 rows.push(Row {
                     glyphs: paragraph.glyphs,
+                    shaped_glyphs: paragraph.shaped_glyphs,
                     visuals: Default::default(),
                     rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
                     ends_with_newline: !is_last_paragraph,
+                    is_rtl: paragraph.is_rtl,
                 });
             } else {
-                line_break(fonts, &paragraph, job, &mut rows);
+                elided = line_break(&paragraph, job, &mut rows);
                 rows.last_mut().unwrap().ends_with_newline = !is_last_paragraph;
+                if elided {
+                    break;
+                }
             }
         }
     }
 
-    rows
+    if elided {
+        if let Some(row) = rows.last_mut() {
+            row.ends_with_newline = false;
+            elide_row(fonts, font_names, job, row, last_paragraph.as_ref());
+        }
+    }
+
+    (rows, elided)
 }
 
-fn line_break(
-    fonts: &mut FontsManager,
-    paragraph: &Paragraph,
-    job: &LayoutJob,
-    out_rows: &mut Vec<Row>,
-) {
+/// Returns `true` if the paragraph didn't fit in [`super::TextWrapping::max_rows`].
+fn line_break(paragraph: &Paragraph, job: &LayoutJob, out_rows: &mut Vec<Row>) -> bool {
     // Keeps track of good places to insert row break if we exceed `wrap_width`.
     let mut row_break_candidates = RowBreakCandidates::default();
 
     let mut first_row_indentation = paragraph.glyphs[0].pos.x;
     let mut row_start_x = 0.0;
     let mut row_start_idx = 0;
-    let mut non_empty_rows = 0;
+    let mut non_empty_rows = out_rows.len();
+    let max_rows = job.wrap.max_rows;
 
     for i in 0..paragraph.glyphs.len() {
         let potential_row_width = paragraph.glyphs[i].max_x() - row_start_x;
 
-        if job.wrap.max_rows > 0 && non_empty_rows >= job.wrap.max_rows {
+        if max_rows > 0 && non_empty_rows >= max_rows {
             break;
         }
 
         if potential_row_width > job.wrap.max_width {
+            // The last row will be elided, so we might as well fill it:
+            let is_last_row = max_rows > 0 && non_empty_rows + 1 == max_rows;
+            let break_anywhere = job.wrap.break_anywhere || is_last_row;
+
             if first_row_indentation > 0.0
                 && !row_break_candidates.has_good_candidate(job.wrap.break_anywhere)
             {
@@ -214,32 +611,20 @@ fn line_break(
                 // TODO(emilk): this records the height of this first row as zero, though that is probably fine since first_row_indentation usually comes with a first_row_min_height.
                 out_rows.push(Row {
                     glyphs: vec![],
+                    shaped_glyphs: vec![],
                     visuals: Default::default(),
                     rect: rect_from_x_range(first_row_indentation..=first_row_indentation),
                     ends_with_newline: false,
+                    is_rtl: paragraph.is_rtl,
                 });
                 row_start_x += first_row_indentation;
                 first_row_indentation = 0.0;
-            } else if let Some(last_kept_index) = row_break_candidates.get(job.wrap.break_anywhere)
-            {
-                let glyphs: Vec<Glyph> = paragraph.glyphs[row_start_idx..=last_kept_index]
-                    .iter()
-                    .copied()
-                    .map(|mut glyph| {
-                        glyph.pos.x -= row_start_x;
-                        glyph
-                    })
-                    .collect();
-
-                let paragraph_min_x = glyphs[0].pos.x;
-                let paragraph_max_x = glyphs.last().unwrap().max_x();
-
-                out_rows.push(Row {
-                    glyphs,
-                    visuals: Default::default(),
-                    rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
-                    ends_with_newline: false,
-                });
+            } else if let Some(last_kept_index) = row_break_candidates.get(break_anywhere) {
+                out_rows.push(row_from_paragraph_glyphs(
+                    paragraph,
+                    row_start_idx..last_kept_index + 1,
+                    row_start_x,
+                ));
 
                 row_start_idx = last_kept_index + 1;
                 row_start_x = paragraph.glyphs[row_start_idx].pos.x;
@@ -254,83 +639,291 @@ fn line_break(
     }
 
     if row_start_idx < paragraph.glyphs.len() {
-        if job.wrap.max_rows > 0 && non_empty_rows == job.wrap.max_rows {
-            if let Some(last_row) = out_rows.last_mut() {
-                replace_last_glyph_with_overflow_character(fonts, job, last_row);
-            }
+        if max_rows > 0 && non_empty_rows >= max_rows {
+            return true;
+        }
+        out_rows.push(row_from_paragraph_glyphs(
+            paragraph,
+            row_start_idx..paragraph.glyphs.len(),
+            row_start_x,
+        ));
+    }
+    false
+}
+
+/// Cut out a row from a paragraph, moving it `row_start_x` to the left.
+fn row_from_paragraph_glyphs(
+    paragraph: &Paragraph,
+    glyph_range: Range<usize>,
+    row_start_x: f32,
+) -> Row {
+    let glyphs: Vec<Glyph> = paragraph.glyphs[glyph_range.clone()]
+        .iter()
+        .copied()
+        .map(|mut glyph| {
+            glyph.pos.x -= row_start_x;
+            glyph
+        })
+        .collect();
+
+    let shaped_start = paragraph
+        .shaped_glyphs
+        .partition_point(|g| (g.char_index as usize) < glyph_range.start);
+    let shaped_end = paragraph
+        .shaped_glyphs
+        .partition_point(|g| (g.char_index as usize) < glyph_range.end);
+    let shaped_glyphs = paragraph.shaped_glyphs[shaped_start..shaped_end]
+        .iter()
+        .copied()
+        .map(|shaped| moved_chars(shaped, glyph_range.start, 0))
+        .collect();
+
+    let paragraph_min_x = glyphs[0].pos.x;
+    let paragraph_max_x = glyphs.last().unwrap().max_x();
+
+    Row {
+        glyphs,
+        shaped_glyphs,
+        visuals: Default::default(),
+        rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
+        ends_with_newline: false,
+        is_rtl: paragraph.is_rtl,
+    }
+}
+
+/// Move the glyphs of a row from logical to visual order,
+/// following rules L1 and L2 of the Unicode Bidirectional Algorithm.
+///
+/// The glyphs stay in logical order in [`Row::glyphs`]; only their x positions change.
+fn reorder_row_visually(row: &mut Row) {
+    if row.glyphs.iter().all(|glyph| glyph.bidi_level == 0) {
+        return; // early-out optimization
+    }
+
+    let base_level = row.is_rtl as u8;
+    let mut levels: Vec<u8> = row.glyphs.iter().map(|glyph| glyph.bidi_level).collect();
+
+    // L1: trailing whitespace goes at the end of the row, in the paragraph direction.
+    for (level, glyph) in levels.iter_mut().zip(&row.glyphs).rev() {
+        if glyph.chr.is_whitespace() {
+            *level = base_level;
         } else {
-            let glyphs: Vec<Glyph> = paragraph.glyphs[row_start_idx..]
-                .iter()
-                .copied()
-                .map(|mut glyph| {
-                    glyph.pos.x -= row_start_x;
-                    glyph
-                })
-                .collect();
-
-            let paragraph_min_x = glyphs[0].pos.x;
-            let paragraph_max_x = glyphs.last().unwrap().max_x();
-
-            out_rows.push(Row {
-                glyphs,
-                visuals: Default::default(),
-                rect: rect_from_x_range(paragraph_min_x..=paragraph_max_x),
-                ends_with_newline: false,
-            });
+            break;
+        }
+    }
+
+    let max_level = levels.iter().copied().max().unwrap_or_default();
+    let lowest_odd_level = match levels.iter().copied().filter(|level| level % 2 == 1).min() {
+        Some(level) => level,
+        None => return, // Nothing is right-to-left, so there is nothing to reverse
+    };
+
+    // L2: From the highest level to the lowest odd level, reverse any run at that level or higher.
+    let mut visual_order: Vec<usize> = (0..row.glyphs.len()).collect();
+    for level in (lowest_odd_level..=max_level).rev() {
+        let mut start = 0;
+        while start < visual_order.len() {
+            if levels[visual_order[start]] < level {
+                start += 1;
+                continue;
+            }
+            let mut end = start;
+            while end < visual_order.len() && levels[visual_order[end]] >= level {
+                end += 1;
+            }
+            visual_order[start..end].reverse();
+            start = end;
         }
     }
+
+    // Keep the exact spacing of the logical layout (rounding, leading space, …):
+    let widths: Vec<f32> = row
+        .glyphs
+        .iter()
+        .zip(row.glyphs.iter().skip(1).map(|next| next.pos.x))
+        .map(|(glyph, next_x)| next_x - glyph.pos.x)
+        .chain(row.glyphs.last().map(|glyph| glyph.size.x))
+        .collect();
+
+    let mut x = row.glyphs[0].pos.x;
+    for index in visual_order {
+        let glyph = &mut row.glyphs[index];
+        glyph.pos.x = x;
+        glyph.size.x = widths[index];
+        x += widths[index];
+    }
 }
 
-fn replace_last_glyph_with_overflow_character(
+/// Shorten the last row of text that didn't fit, according to [`super::TextWrapping::overflow`].
+fn elide_row(
     fonts: &mut FontsManager,
+    font_names: &mut FontNames,
     job: &LayoutJob,
     row: &mut Row,
+    last_paragraph: Option<&Paragraph>,
 ) {
-    let overflow_character = match job.wrap.overflow_character {
-        Some(c) => c,
+    match (job.wrap.overflow, job.wrap.overflow_character) {
+        (TextOverflow::Clip, _) | (_, None) => clip_row(job.wrap.max_width, row),
+        (TextOverflow::EndEllipsis, Some(overflow_character)) => {
+            replace_last_glyph_with_overflow_character(
+                fonts,
+                font_names,
+                job,
+                overflow_character,
+                row,
+            );
+        }
+        (TextOverflow::MiddleEllipsis, Some(overflow_character)) => {
+            if let Some(last_paragraph) = last_paragraph {
+                replace_middle_with_overflow_character(
+                    fonts,
+                    font_names,
+                    job,
+                    overflow_character,
+                    row,
+                    last_paragraph,
+                );
+            }
+        }
+    }
+
+    if let (Some(first), Some(last)) = (row.glyphs.first(), row.glyphs.last()) {
+        row.rect.min.x = first.pos.x;
+        row.rect.max.x = last.max_x();
+    }
+}
+
+/// Remove the characters that don't fit in `max_width`.
+fn clip_row(max_width: f32, row: &mut Row) {
+    let row_start_x = match row.glyphs.first() {
+        Some(glyph) => glyph.pos.x,
         None => return,
     };
+    let num_kept = row
+        .glyphs
+        .iter()
+        .take_while(|glyph| glyph.max_x() - row_start_x <= max_width)
+        .count();
+    let num_kept = cluster_start(&row.shaped_glyphs, num_kept);
+    row.glyphs.truncate(num_kept);
+    row.shaped_glyphs
+        .retain(|shaped| (shaped.char_index as usize) < num_kept);
+}
 
-    loop {
-        let (prev_glyph, last_glyph) = match row.glyphs.as_mut_slice() {
-            [.., prev, last] => (Some(prev), last),
-            [.., last] => (None, last),
-            _ => break,
-        };
+/// Move the index back to the start of the cluster it is in, if any.
+fn cluster_start(shaped_glyphs: &[ShapedGlyph], char_index: usize) -> usize {
+    shaped_glyphs
+        .iter()
+        .filter(|shaped| {
+            (shaped.cluster_start as usize) < char_index && char_index < shaped.cluster_end as usize
+        })
+        .map(|shaped| shaped.cluster_start as usize)
+        .min()
+        .unwrap_or(char_index)
+}
 
-        let section = &job.sections[last_glyph.section_index as usize];
-        let font = fonts.font(&section.format.font_id);
-        let font_height = font.row_height();
+/// Move the index forward to the end of the cluster it is in, if any.
+fn cluster_end(shaped_glyphs: &[ShapedGlyph], char_index: usize) -> usize {
+    shaped_glyphs
+        .iter()
+        .filter(|shaped| {
+            (shaped.cluster_start as usize) < char_index && char_index < shaped.cluster_end as usize
+        })
+        .map(|shaped| shaped.cluster_end as usize)
+        .max()
+        .unwrap_or(char_index)
+}
 
-        let prev_glyph_id = prev_glyph.map(|prev_glyph| {
-            let (_, prev_glyph_info) = font.glyph_info_and_font_impl(prev_glyph.chr);
-            prev_glyph_info.id
-        });
+/// The shaped glyph for the characters that moved from index `from` to `to`.
+fn moved_chars(shaped: ShapedGlyph, from: usize, to: usize) -> ShapedGlyph {
+    let move_index = |index: u32| (index as usize + to).saturating_sub(from) as u32;
+    ShapedGlyph {
+        char_index: move_index(shaped.char_index),
+        cluster_start: move_index(shaped.cluster_start),
+        cluster_end: move_index(shaped.cluster_end),
+        ..shaped
+    }
+}
+
+/// A [`Glyph`] for the character that replaces elided text,
+/// and what to paint for it (if the font has it).
+fn overflow_glyph(
+    fonts: &mut FontsManager,
+    font_names: &mut FontNames,
+    job: &LayoutJob,
+    overflow_character: char,
+    next_to: &Glyph,
+) -> (Glyph, Option<ShapedGlyph>) {
+    let format = &job.sections[next_to.section_index as usize].format;
+    let font = fonts.font(&format.font_id);
+    let font_height = font.row_height();
+    let (font_impl, glyph_info) = font.glyph_info_and_font_impl(overflow_character);
+
+    let glyph = Glyph {
+        chr: overflow_character,
+        pos: next_to.pos,
+        size: vec2(
+            glyph_info.advance_width,
+            format.line_height.unwrap_or(font_height),
+        ),
+        font_height,
+        ..*next_to
+    };
+    let shaped = font_impl.map(|font_impl| ShapedGlyph {
+        char_index: 0,
+        cluster_start: 0,
+        cluster_end: 1,
+        offset: Vec2::ZERO,
+        uv_rect: glyph_info.uv_rect,
+        font_index: font_names.index(font_impl.name()),
+    });
+    (glyph, shaped)
+}
+
+fn replace_last_glyph_with_overflow_character(
+    fonts: &mut FontsManager,
+    font_names: &mut FontNames,
+    job: &LayoutJob,
+    overflow_character: char,
+    row: &mut Row,
+) {
+    // Keep all of the row if there is room for the overflow character after it,
+    // e.g. when the text was cut off by `max_rows`:
+    if let Some(last_glyph) = row.glyphs.last() {
+        let (mut glyph, shaped) =
+            overflow_glyph(fonts, font_names, job, overflow_character, last_glyph);
+        glyph.pos.x = last_glyph.max_x();
+        if glyph.max_x() - row.glyphs[0].pos.x <= job.wrap.max_width {
+            if let Some(shaped) = shaped {
+                row.shaped_glyphs
+                    .push(moved_chars(shaped, 0, row.glyphs.len()));
+            }
+            row.glyphs.push(glyph);
+            return;
+        }
+    }
+
+    while !row.glyphs.is_empty() {
+        // Replace the whole cluster of the last character:
+        let last_index = cluster_start(&row.shaped_glyphs, row.glyphs.len() - 1);
+        row.glyphs.truncate(last_index + 1);
+        let last_glyph = &row.glyphs[last_index];
 
-        // undo kerning with previous glyph
-        let (font_impl, glyph_info) = font.glyph_info_and_font_impl(last_glyph.chr);
-        last_glyph.pos.x -= font_impl
-            .zip(prev_glyph_id)
-            .map(|(font_impl, prev_glyph_id)| font_impl.pair_kerning(prev_glyph_id, glyph_info.id))
-            .unwrap_or_default();
-
-        // replace the glyph
-        last_glyph.chr = overflow_character;
-        let (font_impl, glyph_info) = font.glyph_info_and_font_impl(last_glyph.chr);
-        last_glyph.size = vec2(glyph_info.advance_width, font_height);
-        last_glyph.uv_rect = glyph_info.uv_rect;
-
-        // reapply kerning
-        last_glyph.pos.x += font_impl
-            .zip(prev_glyph_id)
-            .map(|(font_impl, prev_glyph_id)| font_impl.pair_kerning(prev_glyph_id, glyph_info.id))
-            .unwrap_or_default();
+        // Remove what was painted for the last character:
+        row.shaped_glyphs
+            .retain(|shaped| (shaped.char_index as usize) < last_index);
+
+        let (glyph, shaped) =
+            overflow_glyph(fonts, font_names, job, overflow_character, last_glyph);
 
         // check if we're still within width budget
-        let row_end_x = last_glyph.max_x();
-        let row_start_x = row.glyphs.first().unwrap().pos.x; // if `last_mut()` returned `Some`, then so will `first()`
-        let row_width = row_end_x - row_start_x;
-        if row_width <= job.wrap.max_width {
+        let row_start_x = row.glyphs[0].pos.x;
+        if glyph.max_x() - row_start_x <= job.wrap.max_width {
+            // replace the glyph
+            *row.glyphs.last_mut().unwrap() = glyph;
+            if let Some(shaped) = shaped {
+                row.shaped_glyphs.push(moved_chars(shaped, 0, last_index));
+            }
             break;
         }
 
@@ -338,6 +931,100 @@ fn replace_last_glyph_with_overflow_character(
     }
 }
 
+/// Keep as much as fits of the start of the row and the end of the text, with the overflow character in between.
+fn replace_middle_with_overflow_character(
+    fonts: &mut FontsManager,
+    font_names: &mut FontNames,
+    job: &LayoutJob,
+    overflow_character: char,
+    row: &mut Row,
+    last_paragraph: &Paragraph,
+) {
+    let (row_start_x, first_glyph) = match row.glyphs.first() {
+        Some(glyph) => (glyph.pos.x, *glyph),
+        None => return,
+    };
+    if !job.wrap.max_width.is_finite() || last_paragraph.glyphs.is_empty() {
+        // Only cut off because of `max_rows`, so there is no middle to remove.
+        replace_last_glyph_with_overflow_character(fonts, font_names, job, overflow_character, row);
+        return;
+    }
+
+    let (mut overflow, overflow_shaped) =
+        overflow_glyph(fonts, font_names, job, overflow_character, &first_glyph);
+    let max_width = job.wrap.max_width - overflow.size.x;
+
+    // Keep all of the row if it fits with the overflow character, e.g. when the text was cut off by `max_rows`.
+    // Otherwise grow the start and the end alternately, so that both are kept about equally:
+    let row_width = row.glyphs.last().unwrap().max_x() - row_start_x;
+    let (mut prefix_len, mut prefix_width) = if row_width <= max_width {
+        (row.glyphs.len(), row_width)
+    } else {
+        (0, 0.0)
+    };
+    let suffix_glyphs = &last_paragraph.glyphs;
+    let text_end_x = suffix_glyphs.last().unwrap().max_x();
+    let mut suffix_start = suffix_glyphs.len();
+    let mut suffix_width = 0.0;
+    loop {
+        let grown_prefix = row
+            .glyphs
+            .get(prefix_len)
+            .map(|glyph| glyph.max_x() - row_start_x)
+            .filter(|&width| width + suffix_width <= max_width);
+        let grown_suffix = suffix_start
+            .checked_sub(1)
+            .map(|index| text_end_x - suffix_glyphs[index].pos.x)
+            .filter(|&width| prefix_width + width <= max_width);
+
+        match (grown_prefix, grown_suffix) {
+            (Some(width), _) if grown_suffix.is_none() || prefix_width <= suffix_width => {
+                prefix_len += 1;
+                prefix_width = width;
+            }
+            (_, Some(width)) => {
+                suffix_start -= 1;
+                suffix_width = width;
+            }
+            _ => break,
+        }
+    }
+
+    // Don't cut the ligatures at either end:
+    let prefix_len = cluster_start(&row.shaped_glyphs, prefix_len);
+    let prefix_width = prefix_len
+        .checked_sub(1)
+        .map_or(0.0, |last| row.glyphs[last].max_x() - row_start_x);
+    let suffix_start = cluster_end(&last_paragraph.shaped_glyphs, suffix_start);
+
+    row.glyphs.truncate(prefix_len);
+    row.shaped_glyphs
+        .retain(|shaped| (shaped.char_index as usize) < prefix_len);
+
+    overflow.pos.x = row_start_x + prefix_width;
+    row.glyphs.push(overflow);
+    if let Some(shaped) = overflow_shaped {
+        row.shaped_glyphs.push(moved_chars(shaped, 0, prefix_len));
+    }
+
+    if suffix_start < suffix_glyphs.len() {
+        let first_suffix_index = row.glyphs.len();
+        let offset_x = overflow.max_x() - suffix_glyphs[suffix_start].pos.x;
+        row.glyphs
+            .extend(suffix_glyphs[suffix_start..].iter().map(|glyph| Glyph {
+                pos: pos2(glyph.pos.x + offset_x, glyph.pos.y),
+                ..*glyph
+            }));
+        row.shaped_glyphs.extend(
+            last_paragraph
+                .shaped_glyphs
+                .iter()
+                .filter(|shaped| shaped.char_index as usize >= suffix_start)
+                .map(|shaped| moved_chars(*shaped, suffix_start, first_suffix_index)),
+        );
+    }
+}
+
 fn halign_and_jusitfy_row(
     point_scale: PointScale,
     row: &mut Row,
@@ -371,10 +1058,26 @@ fn halign_and_jusitfy_row(
     let num_glyphs_in_range = glyph_range.1 - glyph_range.0;
     assert!(num_glyphs_in_range > 0);
 
-    let original_min_x = row.glyphs[glyph_range.0].logical_rect().min.x;
-    let original_max_x = row.glyphs[glyph_range.1 - 1].logical_rect().max.x;
+    let (original_min_x, original_max_x) = if row.has_rtl() {
+        // Glyphs are in logical order, but positioned visually:
+        let glyphs = &row.glyphs[glyph_range.0..glyph_range.1];
+        let min_x = glyphs.iter().map(|g| g.logical_rect().min.x);
+        let max_x = glyphs.iter().map(|g| g.logical_rect().max.x);
+        (
+            min_x.fold(f32::INFINITY, f32::min),
+            max_x.fold(f32::NEG_INFINITY, f32::max),
+        )
+    } else {
+        (
+            row.glyphs[glyph_range.0].logical_rect().min.x,
+            row.glyphs[glyph_range.1 - 1].logical_rect().max.x,
+        )
+    };
     let original_width = original_max_x - original_min_x;
 
+    // Justifying spreads glyphs in logical order, which is wrong for bidirectional rows.
+    let justify = justify && !row.has_rtl();
+
     let target_width = if justify && num_glyphs_in_range > 1 {
         wrap_width
     } else {
@@ -429,7 +1132,13 @@ fn halign_and_jusitfy_row(
 }
 
 /// Calculate the Y positions and tessellate the text.
-fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<Row>) -> Galley {
+fn galley_from_rows(
+    point_scale: PointScale,
+    job: Arc<LayoutJob>,
+    mut rows: Vec<Row>,
+    elided: bool,
+    font_names: Vec<String>,
+) -> Galley {
     let mut first_row_min_height = job.first_row_min_height;
     let mut cursor_y = 0.0;
     let mut min_x: f32 = 0.0;
@@ -455,6 +1164,9 @@ fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<
         min_x = min_x.min(row.rect.min.x);
         max_x = max_x.max(row.rect.max.x);
         cursor_y += row_height;
+        if row.ends_with_newline {
+            cursor_y += job.paragraph_spacing;
+        }
         cursor_y = point_scale.round_to_pixel(cursor_y);
     }
 
@@ -480,6 +1192,8 @@ fn galley_from_rows(point_scale: PointScale, job: Arc<LayoutJob>, mut rows: Vec<
         mesh_bounds,
         num_vertices,
         num_indices,
+        elided,
+        font_names,
     }
 }
 
@@ -512,8 +1226,8 @@ fn tessellate_row(
 
     let mut mesh = Mesh::default();
 
-    mesh.reserve_triangles(row.glyphs.len() * 2);
-    mesh.reserve_vertices(row.glyphs.len() * 4);
+    mesh.reserve_triangles(row.shaped_glyphs.len() * 2);
+    mesh.reserve_vertices(row.shaped_glyphs.len() * 4);
 
     if format_summary.any_background {
         add_row_backgrounds(job, row, &mut mesh);
@@ -527,7 +1241,7 @@ fn tessellate_row(
         add_row_hline(point_scale, row, &mut mesh, |glyph| {
             let format = &job.sections[glyph.section_index as usize].format;
             let stroke = format.underline;
-            let y = glyph.logical_rect().bottom();
+            let y = glyph.font_rect().bottom();
             (stroke, y)
         });
     }
@@ -536,7 +1250,7 @@ fn tessellate_row(
         add_row_hline(point_scale, row, &mut mesh, |glyph| {
             let format = &job.sections[glyph.section_index as usize].format;
             let stroke = format.strikethrough;
-            let y = glyph.logical_rect().center().y;
+            let y = glyph.font_rect().center().y;
             (stroke, y)
         });
     }
@@ -571,7 +1285,7 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
     for glyph in &row.glyphs {
         let format = &job.sections[glyph.section_index as usize].format;
         let color = format.background;
-        let rect = glyph.logical_rect();
+        let rect = glyph.font_rect();
 
         if color == Color32::TRANSPARENT {
             end_run(run_start.take(), last_rect.right());
@@ -596,11 +1310,14 @@ fn add_row_backgrounds(job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
 }
 
 fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh: &mut Mesh) {
-    for glyph in &row.glyphs {
-        let uv_rect = glyph.uv_rect;
+    for shaped in &row.shaped_glyphs {
+        let glyph = &row.glyphs[shaped.char_index as usize];
+        let uv_rect = shaped.uv_rect;
         if !uv_rect.is_nothing() {
-            let mut left_top = glyph.pos + uv_rect.offset;
-            left_top.x = point_scale.round_to_pixel(left_top.x);
+            let mut left_top = glyph.font_rect().min + shaped.offset + uv_rect.offset;
+            let pixels_per_point = point_scale.pixels_per_point();
+            let (x_in_pixels, uv_rect) = uv_rect.subpixel_variant(left_top.x * pixels_per_point);
+            left_top.x = x_in_pixels / pixels_per_point;
             left_top.y = point_scale.round_to_pixel(left_top.y);
 
             let rect = Rect::from_min_max(left_top, left_top + uv_rect.size);
@@ -611,38 +1328,13 @@ fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh:
 
             let format = &job.sections[glyph.section_index as usize].format;
 
-            let color = format.color;
-
-            if format.italics {
-                let idx = mesh.vertices.len() as u32;
-                mesh.add_triangle(idx, idx + 1, idx + 2);
-                mesh.add_triangle(idx + 2, idx + 1, idx + 3);
-
-                let top_offset = rect.height() * 0.25 * Vec2::X;
-
-                mesh.vertices.push(Vertex {
-                    pos: rect.left_top() + top_offset,
-                    uv: uv.left_top(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.right_top() + top_offset,
-                    uv: uv.right_top(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.left_bottom(),
-                    uv: uv.left_bottom(),
-                    color,
-                });
-                mesh.vertices.push(Vertex {
-                    pos: rect.right_bottom(),
-                    uv: uv.right_bottom(),
-                    color,
-                });
+            let color = if uv_rect.is_color {
+                Color32::from_white_alpha(format.color.a())
             } else {
-                mesh.add_rect_with_uv(rect, uv, color);
-            }
+                format.color
+            };
+
+            mesh.add_rect_with_uv(rect, uv, color);
         }
     }
 }
@@ -802,9 +1494,492 @@ fn is_gyoto_kinsoku(c: char) -> bool {
 
 #[test]
 fn test_zero_max_width() {
-    let mut fonts = FontsImpl::new(1.0, 1024, super::FontDefinitions::default());
+    let mut fonts = FontsManager::new(1.0, 1024, super::FontDefinitions::default());
     let mut layout_job = LayoutJob::single_section("W".into(), super::TextFormat::default());
     layout_job.wrap.max_width = 0.0;
     let galley = super::layout(&mut fonts, layout_job.into());
     assert_eq!(galley.rows.len(), 1);
 }
+
+#[test]
+fn test_shaping_keeps_one_glyph_per_char() {
+    let mut fonts = FontsManager::new(1.0, 1024, super::FontDefinitions::default());
+    let text = "office";
+    let layout_job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+    let galley = super::layout(&mut fonts, layout_job.into());
+    assert_eq!(galley.rows.len(), 1);
+    let row = &galley.rows[0];
+
+    // "ffi" is a ligature, so there are fewer glyphs to paint than there are characters…
+    assert!(row.shaped_glyphs.len() < text.chars().count());
+    // …but there is still one glyph per character for the cursor:
+    assert_eq!(row.glyphs.len(), text.chars().count());
+    assert!(row.glyphs.windows(2).all(|w| w[0].pos.x < w[1].pos.x));
+}
+
+#[test]
+fn test_elision_keeps_ligatures_whole() {
+    use super::{TextOverflow, TextWrapping};
+
+    let mut fonts = FontsManager::new(1.0, 1024, super::FontDefinitions::default());
+    let text = "office";
+    let mut layout = |wrap: TextWrapping| {
+        let mut job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+        job.wrap = wrap;
+        super::layout(&mut fonts, job.into())
+    };
+    let galley = layout(TextWrapping::default());
+    let ffi = &galley.rows[0].shaped_glyphs[1];
+    assert_eq!((ffi.cluster_start, ffi.cluster_end), (1, 4));
+
+    // Room for "of", which is in the middle of the "ffi" ligature:
+    let width = galley.rows[0].glyphs[2].max_x() - galley.rows[0].glyphs[0].pos.x;
+    for overflow in [TextOverflow::Clip, TextOverflow::EndEllipsis] {
+        let galley = layout(TextWrapping {
+            overflow,
+            overflow_character: Some('-'),
+            ..TextWrapping::truncate_at_width(width)
+        });
+        let row = &galley.rows[0];
+        let chars: String = row.glyphs.iter().map(|glyph| glyph.chr).collect();
+        assert!(chars == "o" || chars == "o-", "{:?}", chars);
+        assert!(row
+            .shaped_glyphs
+            .iter()
+            .all(|shaped| (shaped.cluster_end as usize) <= row.glyphs.len()));
+    }
+}
+
+#[test]
+fn test_bidi_reordering() {
+    let mut fonts = FontsManager::new(1.0, 1024, super::FontDefinitions::default());
+    let text = "ab אבג cd";
+    let layout_job = LayoutJob::single_section(text.into(), super::TextFormat::default());
+    let galley = super::layout(&mut fonts, layout_job.into());
+    let row = &galley.rows[0];
+    assert!(!row.is_rtl);
+
+    let x: Vec<f32> = row.glyphs.iter().map(|glyph| glyph.pos.x).collect();
+    // The latin letters stay left-to-right…
+    assert!(x[0] < x[1] && x[1] < x[2]);
+    // …but the hebrew word is reversed:
+    assert!(x[3] > x[4] && x[4] > x[5]);
+    assert!(x[2] < x[5] && x[3] < x[6]);
+
+    // The cursor before 'א' is at its right edge:
+    assert_eq!(row.x_offset(3), row.glyphs[3].max_x());
+    // Where the runs meet, two cursor positions are at the same x,
+    // but everywhere else we should be able to round-trip:
+    for column in [0, 1, 2, 4, 5, 7, 8, 9] {
+        assert_eq!(row.char_at(row.x_offset(column)), column);
+    }
+
+    // Moving right from between 'א' and 'ב' moves backwards in the text, to just before 'א':
+    let cursor = galley.from_ccursor(super::cursor::CCursor::new(4));
+    let cursor = galley.cursor_right_one_character(&cursor);
+    assert_eq!(cursor.ccursor.index, 3);
+}
+
+#[test]
+fn test_script_fallback_fonts() {
+    use super::{FontId, FontType, TextFormat};
+
+    let mut definitions = super::FontDefinitions::default();
+    let monospace_fonts = definitions.type_fonts[&FontType::Monospace].clone();
+    definitions
+        .type_fonts
+        .insert(FontType::Proportional, vec!["Ubuntu-Light".to_owned()]);
+    definitions.fallback.font_types = vec![FontType::Proportional];
+    definitions.fallback.max_fallback_fonts = 2;
+    definitions.fallback.script_fonts.insert(
+        "Zyyy".to_owned(), // Common, e.g. symbols and emojis
+        vec![
+            "NotoEmoji-Regular".to_owned(),
+            "emoji-icon-font".to_owned(),
+            "Hack".to_owned(),
+        ],
+    );
+    let mut fonts = FontsManager::new(1.0, 1024, definitions);
+
+    let layout = |fonts: &mut FontsManager, text: &str, font_id: FontId| {
+        let job = LayoutJob::simple_singleline(text.into(), font_id, Default::default());
+        super::layout(fonts, job.into())
+    };
+
+    let galley = layout(&mut fonts, "a😀⌨", FontId::proportional(14.0));
+    let glyph_fonts: Vec<(char, &str)> = galley.glyph_font_names().collect();
+    assert_eq!(
+        glyph_fonts,
+        [
+            ('a', "Ubuntu-Light"),
+            ('😀', "NotoEmoji-Regular"),
+            ('⌨', "emoji-icon-font")
+        ]
+    );
+
+    // Use the emoji font again, so that the icon font is the least recently used fallback…
+    layout(&mut fonts, "😀", FontId::proportional(14.0));
+    // …and is the one that is removed to make room for the next one:
+    let galley = layout(&mut fonts, "┼", FontId::proportional(14.0));
+    assert_eq!(galley.glyph_font_names().next(), Some(('┼', "Hack")));
+    assert_eq!(
+        fonts.fallback_fonts(&FontType::Proportional),
+        ["NotoEmoji-Regular", "Hack"]
+    );
+    assert_eq!(
+        fonts.definitions().type_fonts[&FontType::Proportional],
+        ["Ubuntu-Light", "NotoEmoji-Regular", "Hack"]
+    );
+
+    // Both fallback fonts are used by this layout, so neither is removed to make room for the icon font,
+    // even though the icon is in an earlier section:
+    let mut job = LayoutJob::default();
+    job.append(
+        "⌨",
+        0.0,
+        TextFormat::simple(FontId::proportional(14.0), Color32::WHITE),
+    );
+    job.append(
+        "😀┼",
+        0.0,
+        TextFormat::simple(FontId::proportional(20.0), Color32::WHITE),
+    );
+    let galley = super::layout(&mut fonts, job.into());
+    let glyph_fonts: Vec<(char, &str)> = galley.glyph_font_names().skip(1).collect();
+    assert_eq!(glyph_fonts, [('😀', "NotoEmoji-Regular"), ('┼', "Hack")]);
+    assert_eq!(
+        fonts.fallback_fonts(&FontType::Proportional),
+        ["NotoEmoji-Regular", "Hack"]
+    );
+
+    // Only the text of a section decides the fallback fonts of its font type:
+    let mut job = LayoutJob::default();
+    job.append(
+        "b",
+        0.0,
+        TextFormat::simple(FontId::proportional(14.0), Color32::WHITE),
+    );
+    job.append(
+        "⌨",
+        0.0,
+        TextFormat::simple(FontId::monospace(14.0), Color32::WHITE),
+    );
+    super::layout(&mut fonts, job.into());
+    assert_eq!(
+        fonts.fallback_fonts(&FontType::Proportional),
+        ["NotoEmoji-Regular", "Hack"]
+    );
+
+    // Monospace doesn't accept fallback fonts:
+    layout(&mut fonts, "╳", FontId::monospace(14.0));
+    assert_eq!(
+        fonts.definitions().type_fonts[&FontType::Monospace],
+        monospace_fonts
+    );
+    assert!(fonts.fallback_fonts(&FontType::Monospace).is_empty());
+}
+
+#[test]
+fn test_font_variants() {
+    use super::{FontId, FontType, FontVariant, FontWeight};
+
+    let mut definitions = super::FontDefinitions::default();
+    definitions
+        .type_fonts
+        .insert(FontType::Proportional, vec!["Ubuntu-Light".to_owned()]);
+    // Pretend that Hack is the bold version of Ubuntu:
+    definitions.font_variants.insert(
+        "Ubuntu-Light".to_owned(),
+        vec![FontVariant::new(
+            "Hack",
+            FontWeight::BOLD,
+            FontStyle::Normal,
+        )],
+    );
+    let mut fonts = FontsManager::new(1.0, 1024, definitions);
+
+    let layout = |fonts: &mut FontsManager, font_id: FontId| {
+        let job = LayoutJob::simple_singleline("Hi".into(), font_id, Default::default());
+        super::layout(fonts, job.into())
+    };
+    let font_names = |galley: &Galley| -> Vec<String> {
+        galley
+            .glyph_font_names()
+            .map(|(_, name)| name.to_owned())
+            .collect()
+    };
+
+    let regular = layout(&mut fonts, FontId::proportional(14.0));
+    assert_eq!(font_names(&regular), ["Ubuntu-Light", "Ubuntu-Light"]);
+
+    let bold = layout(&mut fonts, FontId::proportional(14.0).bold());
+    assert_eq!(font_names(&bold), ["Hack", "Hack"]);
+    let semibold = layout(
+        &mut fonts,
+        FontId::proportional(14.0).with_weight(FontWeight::SEMIBOLD),
+    );
+    assert_eq!(font_names(&semibold), ["Hack", "Hack"]);
+
+    // An upright bold font is no good for italics, so the regular font is slanted instead:
+    let italic = layout(&mut fonts, FontId::proportional(14.0).italic());
+    assert_eq!(font_names(&italic), ["Ubuntu-Light", "Ubuntu-Light"]);
+    assert_eq!(
+        italic.size(),
+        regular.size(),
+        "slanting doesn't change the advance"
+    );
+    let glyph_width = |galley: &Galley| galley.rows[0].shaped_glyphs[0].uv_rect.size.x;
+    assert!(glyph_width(&italic) > glyph_width(&regular));
+
+    // The deprecated `TextFormat::italics` still works:
+    let mut job =
+        LayoutJob::simple_singleline("Hi".into(), FontId::proportional(14.0), Default::default());
+    #[allow(deprecated)]
+    {
+        job.sections[0].format.italics = true;
+    }
+    let old_italic = super::layout(&mut fonts, job.into());
+    assert_eq!(
+        old_italic.job.sections[0].format.font_id.style,
+        FontStyle::Italic
+    );
+    assert_eq!(glyph_width(&old_italic), glyph_width(&italic));
+
+    // No bold monospace font, so it is made bolder:
+    let regular = layout(&mut fonts, FontId::monospace(14.0));
+    let bold = layout(&mut fonts, FontId::monospace(14.0).bold());
+    assert_eq!(font_names(&bold), ["Hack", "Hack"]);
+    assert!(bold.size().x > regular.size().x);
+    assert!(glyph_width(&bold) > glyph_width(&regular));
+}
+
+#[test]
+fn test_line_height_and_spacing() {
+    use super::{FontId, TextFormat};
+
+    let mut fonts = FontsManager::new(1.0, 1024, super::FontDefinitions::default());
+    let font_id = FontId::monospace(14.0);
+    let font_height = fonts.font(&font_id).row_height();
+
+    let mut job = LayoutJob::default();
+    job.append(
+        "abc\n",
+        0.0,
+        TextFormat {
+            extra_letter_spacing: 2.0,
+            ..TextFormat::simple(font_id.clone(), Color32::WHITE)
+        },
+    );
+    job.append(
+        "def",
+        0.0,
+        TextFormat {
+            line_height: Some(30.0),
+            ..TextFormat::simple(font_id.clone(), Color32::WHITE)
+        },
+    );
+    job.paragraph_spacing = 10.0;
+    let galley = layout(&mut fonts, job.into());
+
+    let plain = layout(
+        &mut fonts,
+        LayoutJob::simple_singleline("abc".into(), font_id, Color32::WHITE).into(),
+    );
+    let advance = plain.rows[0].glyphs[1].pos.x - plain.rows[0].glyphs[0].pos.x;
+    let row = &galley.rows[0];
+    assert_eq!(row.glyphs[1].pos.x - row.glyphs[0].pos.x, advance + 2.0);
+    assert_eq!(row.glyphs[2].max_x(), plain.rows[0].rect.max.x + 6.0);
+
+    assert_eq!(galley.rows[0].rect.height(), font_height);
+    assert_eq!(galley.rows[1].rect.min.y, font_height + 10.0);
+    assert_eq!(galley.rows[1].rect.height(), 30.0);
+    let glyph = &galley.rows[1].glyphs[0];
+    assert_eq!(
+        glyph.font_rect().center().y,
+        glyph.logical_rect().center().y
+    );
+    assert_eq!(glyph.font_rect().height(), font_height);
+
+    // Clicking in the space between the paragraphs picks the closest row:
+    let x = galley.rows[1].glyphs[1].pos.x;
+    let cursor = galley.cursor_from_pos(vec2(x, font_height + 2.0));
+    assert_eq!(cursor.rcursor.row, 0);
+    let cursor = galley.cursor_from_pos(vec2(x, font_height + 8.0));
+    assert_eq!(cursor.rcursor.row, 1);
+    assert_eq!(cursor.ccursor.index, 5);
+}
+
+#[test]
+fn test_text_overflow() {
+    use super::{FontId, TextOverflow, TextWrapping};
+
+    let mut fonts = FontsManager::new(1.0, 1024, super::FontDefinitions::default());
+    let font_id = FontId::monospace(10.0);
+    let char_width = fonts.font(&font_id).glyph_width('x');
+
+    let mut layout = |text: &str, wrap: TextWrapping| {
+        let mut job = LayoutJob::simple(text.into(), font_id.clone(), Color32::WHITE, 0.0);
+        job.wrap = wrap;
+        let galley = layout(&mut fonts, job.into());
+        let rows: Vec<String> = galley
+            .rows
+            .iter()
+            .map(|row| row.glyphs.iter().map(|glyph| glyph.chr).collect())
+            .collect();
+        (rows, galley.elided)
+    };
+
+    let path = "/home/user/projects/report.txt";
+    let width = 16.5 * char_width;
+    let wrap = |overflow| TextWrapping {
+        overflow,
+        ..TextWrapping::truncate_at_width(width)
+    };
+    assert_eq!(
+        layout(path, wrap(TextOverflow::EndEllipsis)),
+        (vec!["/home/user/proje…".to_owned()], true)
+    );
+    assert_eq!(
+        layout(path, wrap(TextOverflow::MiddleEllipsis)),
+        (vec!["/home/us…port.txt".to_owned()], true)
+    );
+    assert_eq!(
+        layout(path, wrap(TextOverflow::Clip)),
+        (vec!["/home/user/projec".to_owned()], true)
+    );
+    assert_eq!(
+        layout("short", wrap(TextOverflow::MiddleEllipsis)),
+        (vec!["short".to_owned()], false)
+    );
+
+    // `max_rows` counts the rows of all paragraphs:
+    let wrap = TextWrapping {
+        max_rows: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        layout("one\ntwo\nthree", wrap.clone()),
+        (vec!["one".to_owned(), "two…".to_owned()], true)
+    );
+    // Only replace the last character if the ellipsis doesn't fit after it:
+    assert_eq!(
+        layout(
+            "one\ntwo\nthree",
+            TextWrapping {
+                max_width: 3.5 * char_width,
+                ..wrap.clone()
+            }
+        ),
+        (vec!["one".to_owned(), "tw…".to_owned()], true)
+    );
+    let wrap = TextWrapping {
+        overflow: TextOverflow::MiddleEllipsis,
+        max_width: 5.5 * char_width,
+        ..wrap
+    };
+    assert_eq!(
+        layout("one\ntwo\nthree", wrap.clone()),
+        (vec!["one".to_owned(), "two…e".to_owned()], true)
+    );
+    assert_eq!(
+        layout(
+            "one\ntwo\nthree",
+            TextWrapping {
+                max_width: 3.5 * char_width,
+                ..wrap
+            }
+        ),
+        (vec!["one".to_owned(), "t…e".to_owned()], true)
+    );
+}
+
+#[test]
+fn test_subpixel_positioning_and_hinting() {
+    use super::FontId;
+
+    let pixels_per_point = 1.5;
+    let text = "Hello world";
+    let layout_with = |definitions: super::FontDefinitions, subpixel_positioning: bool| {
+        let mut fonts = FontsManager::new(pixels_per_point, 1024, definitions);
+        fonts.set_subpixel_positioning(subpixel_positioning);
+        let job =
+            LayoutJob::simple_singleline(text.into(), FontId::proportional(11.0), Color32::WHITE);
+        layout(&mut fonts, job.into())
+    };
+    let is_whole_pixel = |x: f32| (x * pixels_per_point).fract().abs() < 1e-3;
+
+    let snapped = layout_with(super::FontDefinitions::default(), false);
+    assert!(snapped.rows[0]
+        .glyphs
+        .iter()
+        .all(|g| is_whole_pixel(g.pos.x)));
+    assert!(snapped.rows[0]
+        .shaped_glyphs
+        .iter()
+        .all(|s| s.uv_rect.subpixel_variants <= 1));
+
+    let subpixel = layout_with(super::FontDefinitions::default(), true);
+    assert!(!subpixel.rows[0]
+        .glyphs
+        .iter()
+        .all(|g| is_whole_pixel(g.pos.x)));
+    let uv_rect = subpixel.rows[0].shaped_glyphs[0].uv_rect;
+    assert_eq!(uv_rect.subpixel_variants, 4);
+    let (x, variant) = uv_rect.subpixel_variant(10.5);
+    assert_eq!(x, 10.0);
+    assert!(variant.min[0] > uv_rect.min[0]);
+    assert_eq!(
+        variant.max[0] - variant.min[0],
+        uv_rect.max[0] - uv_rect.min[0]
+    );
+
+    // Hinting rounds the advances, so even with subpixel positioning the glyphs are on whole pixels:
+    let mut definitions = super::FontDefinitions::default();
+    for font_data in definitions.font_data_map.values_mut() {
+        font_data.tweak.hinting = true;
+    }
+    let hinted = layout_with(definitions, true);
+    assert!(hinted.rows[0]
+        .glyphs
+        .iter()
+        .all(|g| is_whole_pixel(g.pos.x)));
+}
+
+#[test]
+fn test_distance_field_glyphs() {
+    use super::FontId;
+    use crate::GlyphAtlasMode;
+
+    let fonts = super::FontPaintManager::new(1.0, 1024, super::FontDefinitions::default());
+    fonts.set_glyph_atlas_mode(GlyphAtlasMode::DistanceField);
+    assert_eq!(
+        fonts.lock().font_manager.glyph_atlas_mode(),
+        GlyphAtlasMode::DistanceField
+    );
+
+    let layout_h = |size: f32| {
+        let job =
+            LayoutJob::simple_singleline("H".into(), FontId::proportional(size), Color32::WHITE);
+        fonts.layout_job(job).rows[0].shaped_glyphs[0].uv_rect
+    };
+    let small = layout_h(10.0);
+    let large = layout_h(30.0);
+
+    // The same distance field is scaled to both sizes:
+    assert_eq!(small.min, large.min);
+    assert_eq!(small.max, large.max);
+    assert!((large.size - 3.0 * small.size).length() < 1e-3);
+
+    let delta = fonts.font_image_delta().unwrap();
+    let image = match delta.image {
+        crate::ImageData::Font(image) => image,
+        crate::ImageData::Color(_) => panic!("Expected a font image"),
+    };
+    assert!(image.distance_field);
+    let image = &image;
+    let texels = (small.min[1]..small.max[1])
+        .flat_map(|y| (small.min[0]..small.max[0]).map(move |x| image[(x as usize, y as usize)]));
+    let (inside, outside): (Vec<f32>, Vec<f32>) = texels.partition(|&value| value > 0.5);
+    assert!(!inside.is_empty());
+    assert!(outside.contains(&0.0));
+}
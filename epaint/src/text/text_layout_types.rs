@@ -4,7 +4,7 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use super::{cursor::*, font::UvRect};
-use crate::{Color32, FontId, Mesh, Stroke};
+use crate::{util::FloatOrd as _, Color32, FontId, Mesh, Stroke};
 use emath::*;
 
 /// Describes the task of laying out text.
@@ -68,6 +68,17 @@ pub struct LayoutJob {
 
     /// Justify text so that word-wrapped rows fill the whole [`TextWrapping::max_width`]
     pub justify: bool,
+
+    /// The base direction of each paragraph, used when reordering mixed
+    /// left-to-right and right-to-left text.
+    ///
+    /// This does not affect [`Self::halign`].
+    pub direction: TextDirection,
+
+    /// Extra vertical space between paragraphs (i.e. after each `\n`), in points.
+    ///
+    /// Default: `0.0`.
+    pub paragraph_spacing: f32,
 }
 
 impl Default for LayoutJob {
@@ -81,6 +92,8 @@ impl Default for LayoutJob {
             break_on_newline: true,
             halign: Align::LEFT,
             justify: false,
+            direction: TextDirection::Auto,
+            paragraph_spacing: 0.0,
         }
     }
 }
@@ -174,6 +187,8 @@ impl std::hash::Hash for LayoutJob {
             break_on_newline,
             halign,
             justify,
+            direction,
+            paragraph_spacing,
         } = self;
 
         text.hash(state);
@@ -183,6 +198,32 @@ impl std::hash::Hash for LayoutJob {
         break_on_newline.hash(state);
         halign.hash(state);
         justify.hash(state);
+        direction.hash(state);
+        crate::f32_hash(state, *paragraph_spacing);
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The base direction of a paragraph of text.
+///
+/// Text is always stored in logical order, and runs of right-to-left
+/// text (Hebrew, Arabic, …) are reordered for display using the
+/// [Unicode Bidirectional Algorithm](https://unicode.org/reports/tr9/).
+/// The base direction decides how those runs are ordered relative to each other.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextDirection {
+    /// Use the direction of the first strong character of each paragraph.
+    Auto,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Default for TextDirection {
+    #[inline]
+    fn default() -> Self {
+        Self::Auto
     }
 }
 
@@ -214,13 +255,16 @@ impl std::hash::Hash for LayoutSection {
 
 // ----------------------------------------------------------------------------
 
-#[derive(Clone, Debug, Hash, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TextFormat {
     pub font_id: FontId,
     /// Text color
     pub color: Color32,
     pub background: Color32,
+    /// Laid out like [`FontId::style`] set to [`crate::text::FontStyle::Italic`].
+    #[deprecated = "Use `FontId::style` (e.g. `FontId::italic`) instead"]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub italics: bool,
     pub underline: Stroke,
     pub strikethrough: Stroke,
@@ -228,10 +272,23 @@ pub struct TextFormat {
     /// can get the effect of raised text.
     pub valign: Align,
     // TODO(emilk): lowered
+    /// Height of the rows of this text, in points.
+    ///
+    /// The text is centered vertically within the row, and may overflow it if this is less than the font height.
+    /// `None` (the default) means the height of the font.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub line_height: Option<f32>,
+
+    /// Extra space after each character, in points. Can be negative to tighten the text.
+    ///
+    /// Default: `0.0`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extra_letter_spacing: f32,
 }
 
 impl Default for TextFormat {
     #[inline]
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             font_id: FontId::default(),
@@ -241,7 +298,39 @@ impl Default for TextFormat {
             underline: Stroke::none(),
             strikethrough: Stroke::none(),
             valign: Align::BOTTOM,
+            line_height: None,
+            extra_letter_spacing: 0.0,
+        }
+    }
+}
+
+impl std::hash::Hash for TextFormat {
+    #[inline]
+    #[allow(deprecated)]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let Self {
+            font_id,
+            color,
+            background,
+            italics,
+            underline,
+            strikethrough,
+            valign,
+            line_height,
+            extra_letter_spacing,
+        } = self;
+        font_id.hash(state);
+        color.hash(state);
+        background.hash(state);
+        italics.hash(state);
+        underline.hash(state);
+        strikethrough.hash(state);
+        valign.hash(state);
+        line_height.is_some().hash(state);
+        if let Some(line_height) = line_height {
+            crate::f32_hash(state, *line_height);
         }
+        crate::f32_hash(state, *extra_letter_spacing);
     }
 }
 
@@ -268,13 +357,21 @@ pub struct TextWrapping {
 
     /// Maximum amount of rows the text should have.
     /// Set to `0` to disable this.
+    ///
+    /// Text that doesn't fit is shortened as decided by [`Self::overflow`].
     pub max_rows: usize,
 
     /// Don't try to break text at an appropriate place.
     pub break_anywhere: bool,
 
     /// Character to use to represent clipped text, `…` for example, which is the default.
+    ///
+    /// If `None`, the text is clipped.
     pub overflow_character: Option<char>,
+
+    /// How to shorten text that doesn't fit in [`Self::max_rows`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overflow: TextOverflow,
 }
 
 impl std::hash::Hash for TextWrapping {
@@ -285,11 +382,13 @@ impl std::hash::Hash for TextWrapping {
             max_rows,
             break_anywhere,
             overflow_character,
+            overflow,
         } = self;
         crate::f32_hash(state, *max_width);
         max_rows.hash(state);
         break_anywhere.hash(state);
         overflow_character.hash(state);
+        overflow.hash(state);
     }
 }
 
@@ -300,10 +399,45 @@ impl Default for TextWrapping {
             max_rows: 0,
             break_anywhere: false,
             overflow_character: Some('…'),
+            overflow: TextOverflow::EndEllipsis,
+        }
+    }
+}
+
+impl TextWrapping {
+    /// A single row of at most `max_width`, shortened with `…` at the end if it doesn't fit.
+    pub fn truncate_at_width(max_width: f32) -> Self {
+        Self {
+            max_width,
+            max_rows: 1,
+            ..Default::default()
         }
     }
 }
 
+/// How to shorten text that doesn't fit in [`TextWrapping::max_rows`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextOverflow {
+    /// Cut off the characters that don't fit.
+    Clip,
+
+    /// Replace the end of the text with [`TextWrapping::overflow_character`].
+    EndEllipsis,
+
+    /// Keep the start and the end of the text, and replace the middle with [`TextWrapping::overflow_character`].
+    ///
+    /// Good for file paths, where the file name at the end is the most important part.
+    MiddleEllipsis,
+}
+
+impl Default for TextOverflow {
+    #[inline]
+    fn default() -> Self {
+        Self::EndEllipsis
+    }
+}
+
 // ----------------------------------------------------------------------------
 
 /// Text that has been layed out, ready for painting.
@@ -317,7 +451,8 @@ pub struct Galley {
     pub job: Arc<LayoutJob>,
 
     /// Rows of text, from top to bottom.
-    /// The number of characters in all rows sum up to `job.text.chars().count()`.
+    /// The number of characters in all rows sum up to `job.text.chars().count()`,
+    /// unless the text was [`Self::elided`].
     /// Note that each paragraph (pieces of text separated with `\n`)
     /// can be split up into multiple rows.
     pub rows: Vec<Row>,
@@ -341,6 +476,15 @@ pub struct Galley {
 
     /// Total number of indices in all the row meshes.
     pub num_indices: usize,
+
+    /// The text didn't fit in [`TextWrapping::max_rows`] and was shortened,
+    /// so [`Self::rows`] doesn't contain all of the text.
+    pub elided: bool,
+
+    /// The fonts that the glyphs were painted with, indexed by [`ShapedGlyph::font_index`].
+    ///
+    /// Useful to debug font fallback, see [`Self::glyph_font_names`].
+    pub font_names: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -349,6 +493,11 @@ pub struct Row {
     /// One for each `char`.
     pub glyphs: Vec<Glyph>,
 
+    /// The glyphs to paint, as output by the text shaper.
+    ///
+    /// Ordered by [`ShapedGlyph::char_index`].
+    pub shaped_glyphs: Vec<ShapedGlyph>,
+
     /// Logical bounding rectangle based on font heights etc.
     /// Use this when drawing a selection or similar!
     /// Includes leading and trailing whitespace.
@@ -363,6 +512,9 @@ pub struct Row {
     /// so that text that ends with `\n` has an empty [`Row`] last.
     /// This also implies that the last [`Row`] in a [`Galley`] always has `ends_with_newline == false`.
     pub ends_with_newline: bool,
+
+    /// True if the paragraph this row belongs to has a right-to-left base direction.
+    pub is_rtl: bool,
 }
 
 /// The tessellated output of a row.
@@ -399,12 +551,44 @@ pub struct Glyph {
     /// Relative to the galley position.
     /// Logical position: pos.y is the same for all chars of the same [`TextFormat`].
     pub pos: Pos2,
-    /// Advance width and font row height.
+    /// Advance width (including [`TextFormat::extra_letter_spacing`]) and row height
+    /// (the [`TextFormat::line_height`], or else the font height).
+    ///
+    /// If several characters were shaped into one cluster (e.g. a ligature),
+    /// they share the advance width of the cluster equally.
     pub size: Vec2,
-    /// Position of the glyph in the font texture, in texels.
-    pub uv_rect: UvRect,
+    /// Height of the font, which is centered vertically in [`Self::logical_rect`].
+    pub font_height: f32,
     /// Index into [`LayoutJob::sections`]. Decides color etc.
     pub section_index: u32,
+    /// The bidi embedding level of the character. Odd levels are right-to-left.
+    ///
+    /// The glyphs of a [`Row`] are in logical order, but positioned in visual order.
+    pub bidi_level: u8,
+}
+
+/// A glyph from the font texture, positioned by the text shaper.
+///
+/// Shaping can merge several characters into one glyph (ligatures),
+/// or produce several glyphs for one character (marks, split vowels),
+/// which is why these are separate from the [`Glyph`]s (one per `char`) used for cursor placement.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ShapedGlyph {
+    /// Index into [`Row::glyphs`] of the character this glyph is painted at:
+    /// the visually leftmost character of the cluster it was shaped from.
+    pub char_index: u32,
+    /// The characters of that cluster, as indices into [`Row::glyphs`] from `cluster_start` up to `cluster_end`.
+    ///
+    /// Elided text is cut between clusters, so that a ligature is not painted for only some of its characters.
+    pub cluster_start: u32,
+    pub cluster_end: u32,
+    /// Offset from the top left of the [`Glyph::font_rect`] of that character.
+    pub offset: Vec2,
+    /// Position of the glyph in the font texture, in texels.
+    pub uv_rect: UvRect,
+    /// Index into [`Galley::font_names`] of the font this glyph is from.
+    pub font_index: u32,
 }
 
 impl Glyph {
@@ -417,6 +601,20 @@ impl Glyph {
     pub fn logical_rect(&self) -> Rect {
         Rect::from_min_size(self.pos, self.size)
     }
+
+    /// The part of [`Self::logical_rect`] covered by the font,
+    /// which differs when the [`TextFormat::line_height`] is not the font height.
+    #[inline]
+    pub fn font_rect(&self) -> Rect {
+        let y = self.pos.y + 0.5 * (self.size.y - self.font_height);
+        Rect::from_min_size(pos2(self.pos.x, y), vec2(self.size.x, self.font_height))
+    }
+
+    /// Is this character part of right-to-left text?
+    #[inline]
+    pub fn is_rtl(&self) -> bool {
+        self.bidi_level % 2 == 1
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -449,22 +647,65 @@ impl Row {
         self.rect.height()
     }
 
+    /// Does this row contain any right-to-left text?
+    #[inline]
+    pub fn has_rtl(&self) -> bool {
+        self.glyphs.iter().any(Glyph::is_rtl)
+    }
+
     /// Closest char at the desired x coordinate.
     /// Returns something in the range `[0, char_count_excluding_newline()]`.
     pub fn char_at(&self, desired_x: f32) -> usize {
-        for (i, glyph) in self.glyphs.iter().enumerate() {
-            if desired_x < glyph.logical_rect().center().x {
-                return i;
+        if !self.has_rtl() {
+            for (i, glyph) in self.glyphs.iter().enumerate() {
+                if desired_x < glyph.logical_rect().center().x {
+                    return i;
+                }
             }
+            return self.char_count_excluding_newline();
+        }
+
+        // The glyphs are not sorted by x, so find the one closest to `desired_x`:
+        let distance = |glyph: &Glyph| {
+            if desired_x < glyph.pos.x {
+                glyph.pos.x - desired_x
+            } else {
+                (desired_x - glyph.max_x()).max(0.0)
+            }
+        };
+        let closest = self
+            .glyphs
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, glyph)| distance(glyph).ord());
+
+        if let Some((i, glyph)) = closest {
+            let left_half = desired_x < glyph.logical_rect().center().x;
+            if left_half != glyph.is_rtl() {
+                i
+            } else {
+                i + 1
+            }
+        } else {
+            0
         }
-        self.char_count_excluding_newline()
     }
 
+    /// The x coordinate of the cursor placed before the character at `column`.
+    ///
+    /// In right-to-left text that is the right side of the character.
     pub fn x_offset(&self, column: usize) -> f32 {
         if let Some(glyph) = self.glyphs.get(column) {
-            glyph.pos.x
+            if glyph.is_rtl() {
+                glyph.max_x()
+            } else {
+                glyph.pos.x
+            }
         } else {
-            self.rect.right()
+            match self.glyphs.last() {
+                Some(last) if last.is_rtl() => last.pos.x,
+                _ => self.rect.right(),
+            }
         }
     }
 }
@@ -483,6 +724,20 @@ impl Galley {
     pub fn size(&self) -> Vec2 {
         self.rect.size()
     }
+
+    /// Which font painted each glyph, as `(character, font name)`.
+    ///
+    /// A character can have several glyphs (e.g. accents), or none (e.g. a space, or the tail of a ligature).
+    pub fn glyph_font_names(&self) -> impl Iterator<Item = (char, &str)> + '_ {
+        self.rows.iter().flat_map(move |row| {
+            row.shaped_glyphs.iter().map(move |shaped| {
+                (
+                    row.glyphs[shaped.char_index as usize].chr,
+                    self.font_names[shaped.font_index as usize].as_str(),
+                )
+            })
+        })
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -795,7 +1050,57 @@ impl Galley {
 
 /// ## Cursor positions
 impl Galley {
+    /// Move the cursor one character to the left on screen.
+    ///
+    /// In right-to-left text this moves the cursor forward in the text.
     pub fn cursor_left_one_character(&self, cursor: &Cursor) -> Cursor {
+        self.cursor_move_visually(cursor, false)
+            .unwrap_or_else(|| self.cursor_previous_character(cursor))
+    }
+
+    /// Move the cursor one character to the right on screen.
+    ///
+    /// In right-to-left text this moves the cursor backwards in the text.
+    pub fn cursor_right_one_character(&self, cursor: &Cursor) -> Cursor {
+        self.cursor_move_visually(cursor, true)
+            .unwrap_or_else(|| self.cursor_next_character(cursor))
+    }
+
+    /// Move to the closest cursor position to the left or right in the same row.
+    ///
+    /// Returns `None` if the row is all left-to-right, in which case moving in logical order is the same thing.
+    fn cursor_move_visually(&self, cursor: &Cursor, rightwards: bool) -> Option<Cursor> {
+        let row_nr = cursor.rcursor.row;
+        let row = self.rows.get(row_nr)?;
+        if !row.has_rtl() {
+            return None;
+        }
+
+        let current_x = row.x_offset(cursor.rcursor.column);
+        let closest_column = (0..=row.char_count_excluding_newline())
+            .map(|column| (column, row.x_offset(column) - current_x))
+            .filter(|&(_, dx)| if rightwards { dx > 0.0 } else { dx < 0.0 })
+            .min_by_key(|(_, dx)| dx.abs().ord())
+            .map(|(column, _)| column);
+
+        Some(match closest_column {
+            Some(column) => self.from_rcursor(RCursor {
+                row: row_nr,
+                column,
+            }),
+            None => {
+                // At the edge of the row, so continue into the next or previous row:
+                if rightwards != row.is_rtl {
+                    self.cursor_next_character(cursor)
+                } else {
+                    self.cursor_previous_character(cursor)
+                }
+            }
+        })
+    }
+
+    /// Move the cursor one character backwards in the text (logical order).
+    pub fn cursor_previous_character(&self, cursor: &Cursor) -> Cursor {
         if cursor.ccursor.index == 0 {
             Default::default()
         } else {
@@ -807,7 +1112,8 @@ impl Galley {
         }
     }
 
-    pub fn cursor_right_one_character(&self, cursor: &Cursor) -> Cursor {
+    /// Move the cursor one character forward in the text (logical order).
+    pub fn cursor_next_character(&self, cursor: &Cursor) -> Cursor {
         let ccursor = CCursor {
             index: cursor.ccursor.index,
             prefer_next_row: true, // default to this when navigating. It is more often useful to put cursor at the begging of a row than at the end.
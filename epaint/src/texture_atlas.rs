@@ -2,6 +2,32 @@ use emath::{remap_clamp, Rect};
 
 use crate::{textures::TextureFilter, FontImage, ImageDelta};
 
+/// How glyphs are stored in a [`TextureAtlas`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GlyphAtlasMode {
+    /// Each glyph is rasterized for the size and `pixels_per_point` it is shown at.
+    ///
+    /// This gives the sharpest small text, but every new size or `pixels_per_point` adds new glyphs,
+    /// and a changed `pixels_per_point` rebuilds the whole atlas.
+    Coverage,
+
+    /// Each glyph is stored once as a signed distance field, which is scaled to any size.
+    ///
+    /// Use this if text is zoomed a lot, e.g. in a node editor.
+    /// Small text is a bit softer, and color glyphs (e.g. emojis) are painted as plain glyphs.
+    ///
+    /// This requires a painter with a distance field shader, like `egui_glow` or `egui-wgpu`.
+    /// See [`FontImage::distance_field`].
+    DistanceField,
+}
+
+impl Default for GlyphAtlasMode {
+    fn default() -> Self {
+        Self::Coverage
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct Rectu {
     /// inclusive
@@ -65,27 +91,57 @@ pub struct TextureAtlas {
     /// Set when someone requested more space than was available.
     overflowed: bool,
 
+    /// How many rectangles have been allocated, for [`crate::stats::FontAtlasStats`].
+    num_allocations: usize,
+
+    /// How many times the image has grown.
+    num_resizes: usize,
+
     /// pre-rasterized discs of radii `2^i`, where `i` is the index.
     discs: Vec<PrerasterizedDisc>,
+
+    mode: GlyphAtlasMode,
+
+    /// The glyphs in a [`GlyphAtlasMode::DistanceField`] atlas, shared by all font sizes.
+    ///
+    /// The key is a hash of the font and glyph, and the offset and size are in pixels at
+    /// the size the distance field was made for.
+    distance_field_glyphs: ahash::AHashMap<u64, crate::text::UvRect>,
 }
 
 impl TextureAtlas {
     pub fn new(size: [usize; 2]) -> Self {
+        Self::new_with_mode(size, GlyphAtlasMode::Coverage)
+    }
+
+    pub fn new_with_mode(size: [usize; 2], mode: GlyphAtlasMode) -> Self {
         assert!(size[0] >= 1024, "Tiny texture atlas");
+        let mut image = FontImage::new(size);
+        image.distance_field = mode == GlyphAtlasMode::DistanceField;
         let mut atlas = Self {
-            image: FontImage::new(size),
+            image,
             dirty: Rectu::EVERYTHING,
             cursor: (0, 0),
             row_height: 0,
             overflowed: false,
+            num_allocations: 0,
+            num_resizes: 0,
             discs: vec![], // will be filled in below
+            mode,
+            distance_field_glyphs: Default::default(),
         };
 
-        // Make the top left pixel fully white for `WHITE_UV`, i.e. painting something with solid color:
+        // Make the top left pixel fully white for `WHITE_UV`, i.e. painting something with solid color.
+        // This also works as a distance field, where `1.0` is far inside:
         let (pos, image) = atlas.allocate((1, 1));
         assert_eq!(pos, (0, 0));
         image[pos] = 1.0;
 
+        if mode == GlyphAtlasMode::DistanceField {
+            // The pre-rasterized discs below only work as coverage.
+            return atlas;
+        }
+
         // Allocate a series of anti-aliased discs used to render small filled circles:
         // TODO(emilk): these circles can be packed A LOT better.
         // In fact, the whole texture atlas could be packed a lot better.
@@ -131,6 +187,28 @@ impl TextureAtlas {
         self.image.size
     }
 
+    /// The whole image, e.g. for painting text without a GPU.
+    #[inline]
+    pub fn image(&self) -> &FontImage {
+        &self.image
+    }
+
+    #[inline]
+    pub fn mode(&self) -> GlyphAtlasMode {
+        self.mode
+    }
+
+    /// A glyph added with [`Self::insert_distance_field_glyph`].
+    pub(crate) fn distance_field_glyph(&self, key: u64) -> Option<crate::text::UvRect> {
+        self.distance_field_glyphs.get(&key).copied()
+    }
+
+    /// Remember where a glyph is in a [`GlyphAtlasMode::DistanceField`] atlas,
+    /// so that all sizes of the font can use it.
+    pub(crate) fn insert_distance_field_glyph(&mut self, key: u64, uv_rect: crate::text::UvRect) {
+        self.distance_field_glyphs.insert(key, uv_rect);
+    }
+
     /// Returns the locations and sizes of pre-rasterized discs (filled circles) in this atlas.
     pub fn prepared_discs(&self) -> Vec<PreparedDisc> {
         let size = self.size();
@@ -170,6 +248,16 @@ impl TextureAtlas {
         }
     }
 
+    /// How many rectangles (glyphs etc) have been allocated.
+    pub fn num_allocations(&self) -> usize {
+        self.num_allocations
+    }
+
+    /// How many times the image has grown to fit more glyphs.
+    pub fn num_resizes(&self) -> usize {
+        self.num_resizes
+    }
+
     /// Call to get the change to the image since last call.
     pub fn take_delta(&mut self) -> Option<ImageDelta> {
         let dirty = std::mem::replace(&mut self.dirty, Rectu::NOTHING);
@@ -220,8 +308,10 @@ impl TextureAtlas {
             self.overflowed = true; // this will signal the user that we need to recreate the texture atlas next frame.
         } else if resize_to_min_height(&mut self.image, required_height) {
             self.dirty = Rectu::EVERYTHING;
+            self.num_resizes += 1;
         }
 
+        self.num_allocations += 1;
         let pos = self.cursor;
         self.cursor.0 += w + PADDING;
 
@@ -241,6 +331,11 @@ fn resize_to_min_height(image: &mut FontImage, required_height: usize) -> bool {
 
     if image.width() * image.height() > image.pixels.len() {
         image.pixels.resize(image.width() * image.height(), 0.0);
+        if !image.colors.is_empty() {
+            image
+                .colors
+                .resize(image.width() * image.height(), crate::Color32::TRANSPARENT);
+        }
         true
     } else {
         false